@@ -553,6 +553,8 @@ mod susp;
 mod timer;
 
 mod forward;
+#[cfg(feature = "mock")]
+mod mock;
 mod traits;
 
 /// The RustSBI logo without blank lines on the beginning.
@@ -1138,6 +1140,8 @@ pub use susp::Susp;
 pub use timer::Timer;
 
 pub use forward::Forward;
+#[cfg(feature = "mock")]
+pub use mock::with_mock;
 pub use traits::{EnvInfo, RustSBI};
 
 // Macro internal functions and structures
@@ -0,0 +1,34 @@
+use crate::RustSBI;
+
+/// Run `f` with `sbi-rt` SBI calls on the current thread handled by `sbi`.
+///
+/// If crate feature `mock` is enabled, supervisor code using `sbi-rt` functions can be unit tested
+/// on non-RISC-V hosts against a fake SBI implementation built with RustSBI.
+/// Previous handler on the current thread is restored when `f` returns.
+///
+/// # Examples
+///
+/// ```rust
+/// use rustsbi::{RustSBI, SbiRet};
+///
+/// struct FakeSBI;
+///
+/// impl RustSBI for FakeSBI {
+///     fn handle_ecall(&self, extension: usize, function: usize, _param: [usize; 6]) -> SbiRet {
+///         match (extension, function) {
+///             (0x54494D45, 0) => SbiRet::success(0),
+///             _ => SbiRet::not_supported(),
+///         }
+///     }
+/// }
+///
+/// let ret = rustsbi::with_mock(&FakeSBI, || sbi_rt::set_timer(1000));
+/// assert!(ret.is_ok());
+/// ```
+#[inline]
+pub fn with_mock<T: RustSBI, R>(sbi: &T, f: impl FnOnce() -> R) -> R {
+    sbi_rt::mock::with_handler(
+        &|extension, function, param| sbi.handle_ecall(extension, function, param),
+        f,
+    )
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "mock")]
+
+use core::cell::RefCell;
+use rustsbi::{RustSBI, with_mock};
+use sbi_spec::binary::{Physical, SbiRet};
+
+#[derive(RustSBI)]
+struct FakeSBI {
+    console: FakeConsole,
+    hsm: FakeHsm,
+    timer: FakeTimer,
+    info: FakeEnvInfo,
+}
+
+#[test]
+fn mock_sbi_rt_calls() {
+    let sbi = FakeSBI {
+        console: FakeConsole(RefCell::new(Vec::new())),
+        hsm: FakeHsm(RefCell::new(None)),
+        timer: FakeTimer(RefCell::new(None)),
+        info: FakeEnvInfo,
+    };
+    with_mock(&sbi, || {
+        assert!(sbi_rt::probe_extension(sbi_rt::Timer).is_available());
+        assert!(sbi_rt::probe_extension(sbi_rt::Fence).is_unavailable());
+        assert_eq!(sbi_rt::get_mvendorid(), 0x2333);
+
+        assert!(sbi_rt::set_timer(0x1234_5678).is_ok());
+
+        let bytes = b"hello";
+        let ret = sbi_rt::console_write(Physical::new(bytes.len(), bytes.as_ptr() as usize, 0));
+        assert_eq!(ret, SbiRet::success(5));
+
+        assert!(sbi_rt::hart_start(1, 0x8020_0000, 42).is_ok());
+        assert_eq!(
+            sbi_rt::remote_fence_i(sbi_rt::HartMask::all()),
+            SbiRet::not_supported()
+        );
+    });
+    assert_eq!(*sbi.timer.0.borrow(), Some(0x1234_5678));
+    assert_eq!(sbi.console.0.borrow().as_slice(), b"hello");
+    assert_eq!(*sbi.hsm.0.borrow(), Some((1, 0x8020_0000, 42)));
+}
+
+#[test]
+#[should_panic(expected = "without mock handler")]
+fn mock_no_handler() {
+    sbi_rt::set_timer(0);
+}
+
+struct FakeConsole(RefCell<Vec<u8>>);
+
+impl rustsbi::Console for FakeConsole {
+    fn write(&self, bytes: Physical<&[u8]>) -> SbiRet {
+        // Host addresses are used as physical addresses under the mock environment.
+        let slice = unsafe {
+            core::slice::from_raw_parts(bytes.phys_addr_lo() as *const u8, bytes.num_bytes())
+        };
+        self.0.borrow_mut().extend_from_slice(slice);
+        SbiRet::success(slice.len())
+    }
+
+    fn read(&self, _: Physical<&mut [u8]>) -> SbiRet {
+        SbiRet::success(0)
+    }
+
+    fn write_byte(&self, byte: u8) -> SbiRet {
+        self.0.borrow_mut().push(byte);
+        SbiRet::success(0)
+    }
+}
+
+struct FakeHsm(RefCell<Option<(usize, usize, usize)>>);
+
+impl rustsbi::Hsm for FakeHsm {
+    fn hart_start(&self, hartid: usize, start_addr: usize, opaque: usize) -> SbiRet {
+        self.0.replace(Some((hartid, start_addr, opaque)));
+        SbiRet::success(0)
+    }
+
+    fn hart_stop(&self) -> SbiRet {
+        SbiRet::failed()
+    }
+
+    fn hart_get_status(&self, _: usize) -> SbiRet {
+        SbiRet::success(0)
+    }
+}
+
+struct FakeTimer(RefCell<Option<u64>>);
+
+impl rustsbi::Timer for FakeTimer {
+    fn set_timer(&self, stime_value: u64) {
+        self.0.replace(Some(stime_value));
+    }
+}
+
+struct FakeEnvInfo;
+
+impl rustsbi::EnvInfo for FakeEnvInfo {
+    fn mvendorid(&self) -> usize {
+        0x2333
+    }
+
+    fn marchid(&self) -> usize {
+        0
+    }
+
+    fn mimpid(&self) -> usize {
+        0
+    }
+}
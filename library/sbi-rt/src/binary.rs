@@ -25,8 +25,8 @@ pub(crate) fn sbi_call_0(eid: usize, fid: usize) -> SbiRet {
 
 #[inline(always)]
 #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
-pub(crate) fn sbi_call_0(_eid: usize, _fid: usize) -> SbiRet {
-    unimplemented!("unsupported architecture")
+pub(crate) fn sbi_call_0(eid: usize, fid: usize) -> SbiRet {
+    host_call(eid, fid, [0; 6])
 }
 
 #[inline(always)]
@@ -47,8 +47,8 @@ pub(crate) fn sbi_call_1(eid: usize, fid: usize, arg0: usize) -> SbiRet {
 
 #[inline(always)]
 #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
-pub(crate) fn sbi_call_1(_eid: usize, _fid: usize, _arg0: usize) -> SbiRet {
-    unimplemented!("unsupported architecture")
+pub(crate) fn sbi_call_1(eid: usize, fid: usize, arg0: usize) -> SbiRet {
+    host_call(eid, fid, [arg0, 0, 0, 0, 0, 0])
 }
 
 #[inline(always)]
@@ -69,8 +69,8 @@ pub(crate) fn sbi_call_2(eid: usize, fid: usize, arg0: usize, arg1: usize) -> Sb
 
 #[inline(always)]
 #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
-pub(crate) fn sbi_call_2(_eid: usize, _fid: usize, _arg0: usize, _arg1: usize) -> SbiRet {
-    unimplemented!("unsupported architecture")
+pub(crate) fn sbi_call_2(eid: usize, fid: usize, arg0: usize, arg1: usize) -> SbiRet {
+    host_call(eid, fid, [arg0, arg1, 0, 0, 0, 0])
 }
 
 #[inline(always)]
@@ -92,14 +92,8 @@ pub(crate) fn sbi_call_3(eid: usize, fid: usize, arg0: usize, arg1: usize, arg2:
 
 #[inline(always)]
 #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
-pub(crate) fn sbi_call_3(
-    _eid: usize,
-    _fid: usize,
-    _arg0: usize,
-    _arg1: usize,
-    _arg2: usize,
-) -> SbiRet {
-    unimplemented!("unsupported architecture")
+pub(crate) fn sbi_call_3(eid: usize, fid: usize, arg0: usize, arg1: usize, arg2: usize) -> SbiRet {
+    host_call(eid, fid, [arg0, arg1, arg2, 0, 0, 0])
 }
 
 #[inline(always)]
@@ -130,14 +124,14 @@ pub(crate) fn sbi_call_4(
 #[inline(always)]
 #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
 pub(crate) fn sbi_call_4(
-    _eid: usize,
-    _fid: usize,
-    _arg0: usize,
-    _arg1: usize,
-    _arg2: usize,
-    _arg3: usize,
+    eid: usize,
+    fid: usize,
+    arg0: usize,
+    arg1: usize,
+    arg2: usize,
+    arg3: usize,
 ) -> SbiRet {
-    unimplemented!("unsupported architecture")
+    host_call(eid, fid, [arg0, arg1, arg2, arg3, 0, 0])
 }
 
 #[inline(always)]
@@ -170,15 +164,15 @@ pub(crate) fn sbi_call_5(
 #[inline(always)]
 #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
 pub(crate) fn sbi_call_5(
-    _eid: usize,
-    _fid: usize,
-    _arg0: usize,
-    _arg1: usize,
-    _arg2: usize,
-    _arg3: usize,
-    _arg4: usize,
+    eid: usize,
+    fid: usize,
+    arg0: usize,
+    arg1: usize,
+    arg2: usize,
+    arg3: usize,
+    arg4: usize,
 ) -> SbiRet {
-    unimplemented!("unsupported architecture")
+    host_call(eid, fid, [arg0, arg1, arg2, arg3, arg4, 0])
 }
 
 #[inline(always)]
@@ -215,14 +209,29 @@ pub(crate) fn sbi_call_6(
 #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
 #[allow(unused)] // only used on RV32 for RISC-V SBI 2.0 specification
 pub(crate) fn sbi_call_6(
-    _eid: usize,
-    _fid: usize,
-    _arg0: usize,
-    _arg1: usize,
-    _arg2: usize,
-    _arg3: usize,
-    _arg4: usize,
-    _arg5: usize,
+    eid: usize,
+    fid: usize,
+    arg0: usize,
+    arg1: usize,
+    arg2: usize,
+    arg3: usize,
+    arg4: usize,
+    arg5: usize,
 ) -> SbiRet {
+    host_call(eid, fid, [arg0, arg1, arg2, arg3, arg4, arg5])
+}
+
+#[inline(always)]
+#[cfg(all(
+    feature = "mock",
+    not(any(target_arch = "riscv32", target_arch = "riscv64"))
+))]
+pub(crate) fn host_call(eid: usize, fid: usize, param: [usize; 6]) -> SbiRet {
+    crate::mock::handle_ecall(eid, fid, param)
+}
+
+#[inline(always)]
+#[cfg(not(any(feature = "mock", target_arch = "riscv32", target_arch = "riscv64")))]
+pub(crate) fn host_call(_eid: usize, _fid: usize, _param: [usize; 6]) -> SbiRet {
     unimplemented!("unsupported architecture")
 }
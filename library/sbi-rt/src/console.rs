@@ -0,0 +1,250 @@
+//! Formatted console output over the Debug Console Extension.
+//!
+//! [`SbiConsole`] implements [`core::fmt::Write`] on top of `console_write`, so that supervisors
+//! can print before any UART driver is initialized. With the `log` feature, [`SbiLogger`]
+//! provides a `log` crate backend printing to the same console.
+
+use crate::{Console, console_write, probe_extension};
+use core::{
+    fmt,
+    sync::atomic::{AtomicU8, Ordering},
+};
+use sbi_spec::binary::Physical;
+
+/// Console output through the SBI environment.
+///
+/// Bytes are written using the `DBCN` extension if the SBI implementation provides it.
+/// Otherwise, the legacy `console_putchar` function is used when the `legacy` feature is enabled.
+#[derive(Clone, Copy, Debug)]
+pub struct SbiConsole {
+    virt_to_phys: fn(usize) -> usize,
+}
+
+impl SbiConsole {
+    /// Create a console assuming virtual addresses of written bytes equal their physical addresses.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            virt_to_phys: |addr| addr,
+        }
+    }
+
+    /// Create a console translating virtual addresses of written bytes using `virt_to_phys`.
+    ///
+    /// Bytes are passed to the SBI implementation page by page, thus `virt_to_phys` is only
+    /// required to translate addresses inside one 4 KiB page contiguously.
+    #[inline]
+    pub const fn with_virt_to_phys(virt_to_phys: fn(usize) -> usize) -> Self {
+        Self { virt_to_phys }
+    }
+
+    /// Write all `bytes` to the console.
+    ///
+    /// Partial writes are retried until all bytes are written or the SBI implementation returns an error.
+    /// Returns `fmt::Error` if the SBI implementation writes no byte at all, instead of retrying forever.
+    pub fn write_bytes(&self, bytes: &[u8]) -> fmt::Result {
+        match backend() {
+            Backend::Dbcn => self.write_dbcn(bytes),
+            #[cfg(feature = "legacy")]
+            Backend::Legacy => {
+                for &byte in bytes {
+                    #[allow(deprecated)]
+                    crate::legacy::console_putchar(byte as usize);
+                }
+                Ok(())
+            }
+            #[cfg(not(feature = "legacy"))]
+            Backend::Legacy => Err(fmt::Error),
+        }
+    }
+
+    fn write_dbcn(&self, mut bytes: &[u8]) -> fmt::Result {
+        while !bytes.is_empty() {
+            let addr = bytes.as_ptr() as usize;
+            let len = bytes.len().min(PAGE_SIZE - addr % PAGE_SIZE);
+            let phys = (self.virt_to_phys)(addr);
+            let num_bytes = console_write(Physical::new(len, phys, 0))
+                .into_result()
+                .map_err(|_| fmt::Error)?;
+            if num_bytes == 0 {
+                return Err(fmt::Error);
+            }
+            bytes = &bytes[num_bytes.min(len)..];
+        }
+        Ok(())
+    }
+}
+
+impl Default for SbiConsole {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Write for SbiConsole {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_bytes(s.as_bytes())
+    }
+}
+
+const PAGE_SIZE: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Dbcn = 1,
+    Legacy = 2,
+}
+
+// Probed once and shared by all consoles; 0 means not probed yet.
+static BACKEND: AtomicU8 = AtomicU8::new(0);
+
+fn backend() -> Backend {
+    // Each mock handler may provide different extensions, so don't cache the probe result.
+    if cfg!(all(
+        feature = "mock",
+        not(any(target_arch = "riscv32", target_arch = "riscv64"))
+    )) {
+        return probe_backend();
+    }
+    match BACKEND.load(Ordering::Relaxed) {
+        1 => Backend::Dbcn,
+        2 => Backend::Legacy,
+        _ => {
+            let backend = probe_backend();
+            BACKEND.store(backend as u8, Ordering::Relaxed);
+            backend
+        }
+    }
+}
+
+fn probe_backend() -> Backend {
+    if probe_extension(Console).is_available() {
+        Backend::Dbcn
+    } else {
+        Backend::Legacy
+    }
+}
+
+/// A `log` crate backend printing records to [`SbiConsole`].
+///
+/// Each record is printed in one line with its level.
+/// Lines from different harts may interleave, as no lock is held while printing.
+#[cfg(feature = "log")]
+#[derive(Clone, Copy, Debug)]
+pub struct SbiLogger {
+    console: SbiConsole,
+}
+
+#[cfg(feature = "log")]
+impl SbiLogger {
+    /// Create a logger printing to the provided `console`.
+    #[inline]
+    pub const fn new(console: SbiConsole) -> Self {
+        Self { console }
+    }
+
+    /// Install a logger on identity mapped [`SbiConsole`] as the global logger of `log` crate.
+    #[inline]
+    pub fn init(max_level: log::LevelFilter) -> Result<(), log::SetLoggerError> {
+        static LOGGER: SbiLogger = SbiLogger::new(SbiConsole::new());
+        log::set_logger(&LOGGER)?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "log")]
+impl log::Log for SbiLogger {
+    #[inline]
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    #[inline]
+    fn log(&self, record: &log::Record) {
+        use fmt::Write;
+        let mut console = self.console;
+        let _ = writeln!(console, "[{:>5}] {}", record.level(), record.args());
+    }
+
+    #[inline]
+    fn flush(&self) {}
+}
+
+#[cfg(all(
+    test,
+    feature = "mock",
+    not(any(target_arch = "riscv32", target_arch = "riscv64"))
+))]
+mod tests {
+    use super::*;
+    use crate::{SbiRet, mock::with_handler};
+    use core::cell::Cell;
+    use sbi_spec::{
+        base::{EID_BASE, PROBE_EXTENSION},
+        dbcn::{CONSOLE_WRITE, EID_DBCN},
+    };
+
+    // Fake DBCN implementation writing at most `chunk` bytes per call.
+    fn fake_dbcn(
+        chunk: usize,
+        written: &Cell<usize>,
+    ) -> impl Fn(usize, usize, [usize; 6]) -> SbiRet {
+        move |extension, function, param| match (extension, function) {
+            (EID_BASE, PROBE_EXTENSION) => SbiRet::success((param[0] == EID_DBCN) as usize),
+            (EID_DBCN, CONSOLE_WRITE) => {
+                let num_bytes = param[0].min(chunk);
+                written.set(written.get() + num_bytes);
+                SbiRet::success(num_bytes)
+            }
+            _ => SbiRet::not_supported(),
+        }
+    }
+
+    #[test]
+    fn partial_writes() {
+        let written = Cell::new(0);
+        let result = with_handler(&fake_dbcn(3, &written), || {
+            SbiConsole::new().write_bytes(b"hello, world")
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(written.get(), 12);
+    }
+
+    #[test]
+    fn nothing_written() {
+        let written = Cell::new(0);
+        let result = with_handler(&fake_dbcn(0, &written), || {
+            SbiConsole::new().write_bytes(b"hello, world")
+        });
+        assert_eq!(result, Err(fmt::Error));
+    }
+
+    #[test]
+    fn backend_probed_per_handler() {
+        let written = Cell::new(0);
+        let result = with_handler(&fake_dbcn(64, &written), || {
+            SbiConsole::new().write_bytes(b"dbcn")
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(written.get(), 4);
+        // SBI v0.1 firmware, providing only legacy `console_putchar` of EID 0x01
+        let putchar = Cell::new(0);
+        let legacy = |extension: usize, _function: usize, _param: [usize; 6]| {
+            if extension != 0x01 {
+                return SbiRet::not_supported();
+            }
+            putchar.set(putchar.get() + 1);
+            SbiRet::success(0)
+        };
+        let result = with_handler(&legacy, || SbiConsole::new().write_bytes(b"legacy"));
+        if cfg!(feature = "legacy") {
+            assert_eq!(result, Ok(()));
+            assert_eq!(putchar.get(), 6);
+        } else {
+            assert_eq!(result, Err(fmt::Error));
+        }
+    }
+}
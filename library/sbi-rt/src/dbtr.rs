@@ -15,7 +15,8 @@
 //! called `trig_idx` by the SBI implementation where `-1 < trig_idx < trig_max`.
 
 use crate::binary::{sbi_call_1, sbi_call_2, sbi_call_3};
-use sbi_spec::binary::{SbiRet, SharedPtr, TriggerMask};
+use core::{cell::UnsafeCell, ops::BitOr};
+use sbi_spec::binary::{Error, SbiRet, SharedPtr, TriggerMask};
 use sbi_spec::dbtr::*;
 
 /// Get the number of debug triggers on the calling hart which can support the trigger
@@ -247,3 +248,594 @@ pub fn debug_disable_triggers(triggers: TriggerMask) -> SbiRet {
     let (trig_idx_mask, trig_idx_base) = triggers.into_inner();
     sbi_call_2(EID_DBTR, DISABLE_TRIGGERS, trig_idx_base, trig_idx_mask)
 }
+
+const XLEN: usize = usize::BITS as usize;
+
+/// Action taken by a debug trigger when it fires, encoded in the `action` field of `tdata1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(usize)]
+pub enum TriggerAction {
+    /// Raise a breakpoint exception, which is handled by supervisor software.
+    Breakpoint = 0,
+    /// Enter debug mode; only valid if the trigger is for an external debugger.
+    DebugMode = 1,
+    /// Trace on action 0.
+    TraceOn = 2,
+    /// Trace off action 0.
+    TraceOff = 3,
+    /// Trace notify action 0.
+    TraceNotify = 4,
+    /// Send a signal to external trace hardware.
+    External0 = 8,
+    /// Send a signal to external trace hardware.
+    External1 = 9,
+}
+
+/// How `tdata2` is compared against an address or data value in `mcontrol6` triggers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(usize)]
+pub enum MatchType {
+    /// Matches when the value equals `tdata2`.
+    Equal = 0,
+    /// Matches when the top bits of the value match those of `tdata2`, where `tdata2` encodes
+    /// a naturally aligned power-of-two range.
+    Napot = 1,
+    /// Matches when the value is greater than or equal to `tdata2`.
+    GreaterEqual = 2,
+    /// Matches when the value is less than `tdata2`.
+    Less = 3,
+    /// Matches when the lower half of the value, masked by the upper half of `tdata2`,
+    /// equals the lower half of `tdata2`.
+    MaskLow = 4,
+    /// Matches when the upper half of the value, masked by the upper half of `tdata2`,
+    /// equals the lower half of `tdata2`.
+    MaskHigh = 5,
+    /// Matches when [`MatchType::Equal`] does not.
+    NotEqual = 8,
+    /// Matches when [`MatchType::Napot`] does not.
+    NotNapot = 9,
+    /// Matches when [`MatchType::MaskLow`] does not.
+    NotMaskLow = 12,
+    /// Matches when [`MatchType::MaskHigh`] does not.
+    NotMaskHigh = 13,
+}
+
+/// Size of accesses matched by `mcontrol6` triggers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(usize)]
+pub enum AccessSize {
+    /// Match accesses of any size.
+    Any = 0,
+    /// Match 8-bit accesses.
+    Bits8 = 1,
+    /// Match 16-bit accesses.
+    Bits16 = 2,
+    /// Match 32-bit accesses.
+    Bits32 = 3,
+    /// Match 48-bit instructions.
+    Bits48 = 4,
+    /// Match 64-bit accesses.
+    Bits64 = 5,
+    /// Match 128-bit accesses.
+    Bits128 = 6,
+}
+
+/// Privilege modes in which a debug trigger is enabled.
+///
+/// The SBI implementation saves these bits of `tdata1` in `trig_state`, and restores
+/// them when the trigger is enabled by [`debug_enable_triggers`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TriggerModes(u8);
+
+impl TriggerModes {
+    /// Not enabled in any mode.
+    pub const NONE: Self = Self(0);
+    /// User mode, or VU-mode if the trigger is installed by a guest.
+    pub const U: Self = Self(1 << 0);
+    /// Supervisor mode, or VS-mode if the trigger is installed by a guest.
+    pub const S: Self = Self(1 << 1);
+    /// Virtual user mode.
+    pub const VU: Self = Self(1 << 2);
+    /// Virtual supervisor mode.
+    pub const VS: Self = Self(1 << 3);
+
+    /// Returns whether all modes in `other` are included in `self`.
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[inline]
+    const fn encode(self, u: u32, s: u32, vu: u32, vs: u32) -> usize {
+        (self.contains(Self::U) as usize) << u
+            | (self.contains(Self::S) as usize) << s
+            | (self.contains(Self::VU) as usize) << vu
+            | (self.contains(Self::VS) as usize) << vs
+    }
+}
+
+impl BitOr for TriggerModes {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+#[inline]
+const fn set_field(raw: usize, shift: u32, width: u32, value: usize) -> usize {
+    let mask = ((1 << width) - 1) << shift;
+    (raw & !mask) | ((value << shift) & mask)
+}
+
+macro_rules! define_tdata1 {
+    ($($(#[$doc:meta])* $name:ident(type $ty:literal, action $action:literal $action_width:literal, modes $u:literal $s:literal $vu:literal $vs:literal);)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            pub struct $name(usize);
+
+            impl $name {
+                /// Value of the `type` field of this trigger type.
+                pub const TYPE: usize = $ty;
+
+                /// Create a `tdata1` value from a raw word.
+                #[inline]
+                pub const fn from_raw(raw: usize) -> Self {
+                    Self(raw)
+                }
+
+                /// Returns the raw `tdata1` word.
+                #[inline]
+                pub const fn into_raw(self) -> usize {
+                    self.0
+                }
+
+                /// Set privilege modes in which the trigger is enabled.
+                #[inline]
+                pub const fn modes(self, modes: TriggerModes) -> Self {
+                    let mask = TriggerModes(0xf).encode($u, $s, $vu, $vs);
+                    Self((self.0 & !mask) | modes.encode($u, $s, $vu, $vs))
+                }
+
+                /// Set the action taken when the trigger fires.
+                #[inline]
+                pub const fn action(self, action: TriggerAction) -> Self {
+                    Self(set_field(self.0, $action, $action_width, action as usize))
+                }
+            }
+
+            impl From<$name> for usize {
+                #[inline]
+                fn from(value: $name) -> usize {
+                    value.0
+                }
+            }
+        )*
+    };
+}
+
+define_tdata1! {
+    /// `tdata1` of match control type 6 (`mcontrol6`) triggers, matching addresses or data
+    /// of instruction fetches, loads and stores.
+    Mcontrol6(type 6, action 12 4, modes 3 4 23 24);
+    /// `tdata1` of instruction count (`icount`) triggers, firing after a number of instructions retire.
+    Icount(type 3, action 0 6, modes 6 7 25 26);
+    /// `tdata1` of interrupt (`itrigger`) triggers, firing on selected interrupts.
+    Itrigger(type 4, action 0 6, modes 6 7 11 12);
+    /// `tdata1` of exception (`etrigger`) triggers, firing on selected exceptions.
+    Etrigger(type 5, action 0 6, modes 6 7 11 12);
+}
+
+impl Mcontrol6 {
+    /// Create an `mcontrol6` trigger matching nothing, with equal match on addresses of any
+    /// access size and the breakpoint action.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(Self::TYPE << (XLEN - 4))
+    }
+
+    /// Fire on instruction fetches.
+    #[inline]
+    pub const fn execute(self, enable: bool) -> Self {
+        Self(set_field(self.0, 2, 1, enable as usize))
+    }
+
+    /// Fire on stores.
+    #[inline]
+    pub const fn store(self, enable: bool) -> Self {
+        Self(set_field(self.0, 1, 1, enable as usize))
+    }
+
+    /// Fire on loads.
+    #[inline]
+    pub const fn load(self, enable: bool) -> Self {
+        Self(set_field(self.0, 0, 1, enable as usize))
+    }
+
+    /// Set how `tdata2` is compared against the address or data.
+    #[inline]
+    pub const fn match_type(self, match_type: MatchType) -> Self {
+        Self(set_field(self.0, 7, 4, match_type as usize))
+    }
+
+    /// Set the size of accesses to match.
+    #[inline]
+    pub const fn size(self, size: AccessSize) -> Self {
+        Self(set_field(self.0, 16, 3, size as usize))
+    }
+
+    /// Compare `tdata2` against data values instead of addresses.
+    #[inline]
+    pub const fn select_data(self, enable: bool) -> Self {
+        Self(set_field(self.0, 21, 1, enable as usize))
+    }
+
+    /// Chain with the next trigger, firing only if both triggers match.
+    #[inline]
+    pub const fn chain(self, enable: bool) -> Self {
+        Self(set_field(self.0, 11, 1, enable as usize))
+    }
+}
+
+impl Icount {
+    /// Create an `icount` trigger firing after `count` instructions retire, with the breakpoint action.
+    ///
+    /// Only the low 14 bits of `count` are used.
+    #[inline]
+    pub const fn new(count: usize) -> Self {
+        Self(set_field(Self::TYPE << (XLEN - 4), 10, 14, count))
+    }
+}
+
+impl Itrigger {
+    /// Create an `itrigger` trigger with the breakpoint action.
+    ///
+    /// Interrupts to fire on are selected by the bit mask in `tdata2`, indexed by interrupt cause.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(Self::TYPE << (XLEN - 4))
+    }
+
+    /// Fire on non-maskable interrupts.
+    #[inline]
+    pub const fn nmi(self, enable: bool) -> Self {
+        Self(set_field(self.0, 10, 1, enable as usize))
+    }
+}
+
+impl Etrigger {
+    /// Create an `etrigger` trigger with the breakpoint action.
+    ///
+    /// Exceptions to fire on are selected by the bit mask in `tdata2`, indexed by exception cause.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(Self::TYPE << (XLEN - 4))
+    }
+}
+
+impl Default for Mcontrol6 {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Itrigger {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Etrigger {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Configuration of one debug trigger, consisting of its `tdata1`, `tdata2` and `tdata3` words.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TriggerConfig {
+    /// Trigger `tdata1` word, typically built by [`Mcontrol6`], [`Icount`], [`Itrigger`] or [`Etrigger`].
+    pub tdata1: usize,
+    /// Trigger `tdata2` word.
+    pub tdata2: usize,
+    /// Trigger `tdata3` word.
+    pub tdata3: usize,
+}
+
+impl TriggerConfig {
+    /// Create a trigger configuration from `tdata1` and `tdata2`, with `tdata3` set to zero.
+    #[inline]
+    pub fn new(tdata1: impl Into<usize>, tdata2: usize) -> Self {
+        Self {
+            tdata1: tdata1.into(),
+            tdata2,
+            tdata3: 0,
+        }
+    }
+}
+
+/// One entry of the debug trigger shared memory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TriggerEntry {
+    /// `trig_idx` after install or update, or `trig_state` after read.
+    pub idx_or_state: usize,
+    /// Trigger configuration words.
+    pub config: TriggerConfig,
+}
+
+/// Shared memory for debug trigger configuration with `N` entries.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sbi_rt::{Mcontrol6, MatchType, TriggerConfig, TriggerModes, TriggerShmem};
+///
+/// static mut SHMEM: TriggerShmem<4> = TriggerShmem::new();
+///
+/// let shmem = unsafe { &mut *(&raw mut SHMEM) };
+/// // Safety: statics in this kernel are mapped at their physical addresses.
+/// unsafe { shmem.register() }.expect("set DBTR shared memory");
+/// let tdata1 = Mcontrol6::new()
+///     .store(true)
+///     .match_type(MatchType::Equal)
+///     .modes(TriggerModes::S);
+/// shmem.install(&[TriggerConfig::new(tdata1, 0x8020_1000)]).expect("install trigger");
+/// let trig_idx = shmem.entry(0).idx_or_state;
+/// # let _ = trig_idx;
+/// ```
+#[repr(C)]
+pub struct TriggerShmem<const N: usize> {
+    entries: UnsafeCell<[[usize; 4]; N]>,
+}
+
+impl<const N: usize> TriggerShmem<N> {
+    /// Create a zeroed debug trigger shared memory.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            entries: UnsafeCell::new([[0; 4]; N]),
+        }
+    }
+
+    /// Set this structure as the debug trigger shared memory of the calling hart.
+    ///
+    /// # Safety
+    ///
+    /// This structure MUST be mapped at the same virtual and physical address,
+    /// and MUST NOT be moved or dropped until another shared memory is set on the calling hart.
+    #[inline]
+    pub unsafe fn register(&mut self) -> Result<(), Error> {
+        let phys_addr = self as *mut Self as usize;
+        debug_set_shmem(SharedPtr::new(phys_addr, 0), 0)
+            .into_result()
+            .map(drop)
+    }
+
+    /// Disable debug trigger shared memory on the calling hart.
+    #[inline]
+    pub fn unregister() -> Result<(), Error> {
+        debug_set_shmem(SharedPtr::new(usize::MAX, usize::MAX), 0)
+            .into_result()
+            .map(drop)
+    }
+
+    /// Read entry `i` of the shared memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= N`.
+    #[inline]
+    pub fn entry(&self, i: usize) -> TriggerEntry {
+        assert!(i < N, "trigger shared memory index out of bounds");
+        let ptr = self.entries.get().cast::<usize>();
+        let word = |w: usize| usize::from_le(unsafe { ptr.add(i * 4 + w).read_volatile() });
+        TriggerEntry {
+            idx_or_state: word(0),
+            config: TriggerConfig {
+                tdata1: word(1),
+                tdata2: word(2),
+                tdata3: word(3),
+            },
+        }
+    }
+
+    #[inline]
+    fn set_entry(&mut self, i: usize, idx: usize, config: &TriggerConfig) {
+        let entry = [idx, config.tdata1, config.tdata2, config.tdata3];
+        let ptr = self.entries.get().cast::<usize>();
+        for (w, value) in entry.into_iter().enumerate() {
+            unsafe { ptr.add(i * 4 + w).write_volatile(value.to_le()) };
+        }
+    }
+
+    /// Install debug triggers from `configs`.
+    ///
+    /// On success, `trig_idx` assigned to the i'th configuration is available at
+    /// [`entry(i)`](Self::entry). On failure, the error is returned with the index
+    /// of the failing configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `configs` has more than `N` elements.
+    pub fn install(&mut self, configs: &[TriggerConfig]) -> Result<(), (Error, usize)> {
+        assert!(configs.len() <= N, "too many trigger configurations");
+        for (i, config) in configs.iter().enumerate() {
+            self.set_entry(i, 0, config);
+        }
+        let ret = debug_install_triggers(configs.len());
+        ret.into_result().map(drop).map_err(|e| (e, ret.value))
+    }
+
+    /// Update installed debug triggers, each identified by its `trig_idx` and new configuration.
+    ///
+    /// On failure, the error is returned with the index of the failing update.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `updates` has more than `N` elements.
+    pub fn update(&mut self, updates: &[(usize, TriggerConfig)]) -> Result<(), (Error, usize)> {
+        assert!(updates.len() <= N, "too many trigger configurations");
+        for (i, (trig_idx, config)) in updates.iter().enumerate() {
+            self.set_entry(i, *trig_idx, config);
+        }
+        let ret = debug_update_triggers(updates.len());
+        ret.into_result().map(drop).map_err(|e| (e, ret.value))
+    }
+
+    /// Read state and configuration of `count` debug triggers from `trig_idx_base`.
+    ///
+    /// On success, the trigger `trig_idx_base + i` is available at [`entry(i)`](Self::entry).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count > N`.
+    #[inline]
+    pub fn read(&mut self, trig_idx_base: usize, count: usize) -> Result<(), Error> {
+        assert!(count <= N, "too many triggers to read");
+        debug_read_triggers(trig_idx_base, count)
+            .into_result()
+            .map(drop)
+    }
+}
+
+impl<const N: usize> Default for TriggerShmem<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Handle of one installed trigger, uninstalled on drop.
+macro_rules! define_trigger_handle {
+    ($($(#[$doc:meta])* $name:ident;)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug)]
+            pub struct $name {
+                trig_idx: usize,
+            }
+
+            impl $name {
+                /// Returns `trig_idx` assigned by the SBI implementation.
+                #[inline]
+                pub const fn trig_idx(&self) -> usize {
+                    self.trig_idx
+                }
+
+                /// Enable this trigger in privilege modes it was installed with.
+                #[inline]
+                pub fn enable(&self) -> Result<(), Error> {
+                    debug_enable_triggers(TriggerMask::from_mask_base(1, self.trig_idx))
+                        .into_result()
+                        .map(drop)
+                }
+
+                /// Disable this trigger in all privilege modes.
+                #[inline]
+                pub fn disable(&self) -> Result<(), Error> {
+                    debug_disable_triggers(TriggerMask::from_mask_base(1, self.trig_idx))
+                        .into_result()
+                        .map(drop)
+                }
+            }
+
+            impl Drop for $name {
+                #[inline]
+                fn drop(&mut self) {
+                    let _ = debug_uninstall_triggers(TriggerMask::from_mask_base(1, self.trig_idx));
+                }
+            }
+        )*
+    };
+}
+
+define_trigger_handle! {
+    /// Hardware breakpoint on instruction execution at one address, uninstalled on drop.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sbi_rt::{Breakpoint, TriggerModes, TriggerShmem};
+    ///
+    /// static mut SHMEM: TriggerShmem<1> = TriggerShmem::new();
+    ///
+    /// let shmem = unsafe { &mut *(&raw mut SHMEM) };
+    /// // Safety: statics in this kernel are mapped at their physical addresses.
+    /// unsafe { shmem.register() }.expect("set DBTR shared memory");
+    /// let bp = Breakpoint::install(shmem, 0x8020_0000, TriggerModes::S).expect("install breakpoint");
+    /// bp.disable().expect("disable breakpoint");
+    /// drop(bp); // uninstalled here
+    /// ```
+    Breakpoint;
+    /// Hardware watchpoint on loads and/or stores in an address range, uninstalled on drop.
+    Watchpoint;
+}
+
+impl Breakpoint {
+    /// Install a breakpoint on execution of the instruction at `addr` in `modes`.
+    #[inline]
+    pub fn install<const N: usize>(
+        shmem: &mut TriggerShmem<N>,
+        addr: usize,
+        modes: TriggerModes,
+    ) -> Result<Self, Error> {
+        let tdata1 = Mcontrol6::new().execute(true).modes(modes);
+        install_one(shmem, TriggerConfig::new(tdata1, addr)).map(|trig_idx| Self { trig_idx })
+    }
+}
+
+/// Kind of memory accesses watched by a [`Watchpoint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WatchAccess {
+    /// Fire on loads.
+    Load,
+    /// Fire on stores.
+    Store,
+    /// Fire on loads and stores.
+    LoadStore,
+}
+
+impl Watchpoint {
+    /// Install a watchpoint on `access` to the `len` bytes starting at `addr` in `modes`.
+    ///
+    /// `len` MUST be 1, or a power of two with `addr` aligned to `len`; otherwise
+    /// `Error::InvalidParam` is returned.
+    pub fn install<const N: usize>(
+        shmem: &mut TriggerShmem<N>,
+        addr: usize,
+        len: usize,
+        access: WatchAccess,
+        modes: TriggerModes,
+    ) -> Result<Self, Error> {
+        let (match_type, tdata2) = match len {
+            1 => (MatchType::Equal, addr),
+            _ if len.is_power_of_two() && addr & (len - 1) == 0 => {
+                (MatchType::Napot, addr | ((len >> 1) - 1))
+            }
+            _ => return Err(Error::InvalidParam),
+        };
+        let tdata1 = Mcontrol6::new()
+            .load(matches!(access, WatchAccess::Load | WatchAccess::LoadStore))
+            .store(matches!(
+                access,
+                WatchAccess::Store | WatchAccess::LoadStore
+            ))
+            .match_type(match_type)
+            .modes(modes);
+        install_one(shmem, TriggerConfig::new(tdata1, tdata2)).map(|trig_idx| Self { trig_idx })
+    }
+}
+
+#[inline]
+fn install_one<const N: usize>(
+    shmem: &mut TriggerShmem<N>,
+    config: TriggerConfig,
+) -> Result<usize, Error> {
+    shmem.install(&[config]).map_err(|(e, _)| e)?;
+    Ok(shmem.entry(0).idx_or_state)
+}
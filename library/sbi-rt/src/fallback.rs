@@ -0,0 +1,544 @@
+//! Probe cache and fallback wrappers across SBI specification versions.
+//!
+//! [`SbiCaps`] is a snapshot of the SBI specification version, implementation ID and
+//! available extensions, probed once on first use. Wrappers in [`timer`], [`ipi`] and [`fence`]
+//! use it to pick the best mechanism the SBI implementation provides, falling back to legacy
+//! extensions on SBI v0.1 environments, so that one supervisor binary works on SBI v0.1
+//! through v3.0 firmware.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use sbi_rt::{HartMask, fallback::{SbiCaps, ipi, timer}};
+//!
+//! let caps = SbiCaps::get();
+//! if caps.is_legacy() {
+//!     // running on SBI v0.1 firmware, only legacy extensions are available
+//! }
+//! timer::set(0x1000_0000);
+//! ipi::send(HartMask::from_mask_base(0b10, 0));
+//! ```
+
+use crate::{
+    Extension, Version,
+    binary::{sbi_call_0, sbi_call_1},
+};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use sbi_spec::base::{
+    EID_BASE, GET_SBI_IMPL_ID, GET_SBI_IMPL_VERSION, GET_SBI_SPEC_VERSION, PROBE_EXTENSION,
+};
+
+/// Snapshot of capabilities of the current SBI environment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SbiCaps {
+    spec_version: Version,
+    impl_id: usize,
+    impl_version: usize,
+    extensions: usize,
+}
+
+impl SbiCaps {
+    /// Return capabilities of the current SBI environment, probing them on first call.
+    ///
+    /// The snapshot is cached and shared by all harts. Harts calling this function concurrently
+    /// before the cache is filled may probe the environment more than once with identical results.
+    ///
+    /// With the `mock` feature on non-RISC-V targets, nothing is cached and every call probes
+    /// the environment, so that each mock handler is probed on its own.
+    #[inline]
+    pub fn get() -> Self {
+        if cfg!(all(
+            feature = "mock",
+            not(any(target_arch = "riscv32", target_arch = "riscv64"))
+        )) {
+            return Self::probe();
+        }
+        let extensions = CACHE_EXTENSIONS.load(Ordering::Acquire);
+        if extensions & READY == 0 {
+            return Self::probe_and_cache();
+        }
+        Self {
+            spec_version: Version::from_raw(CACHE_SPEC_VERSION.load(Ordering::Relaxed)),
+            impl_id: CACHE_IMPL_ID.load(Ordering::Relaxed),
+            impl_version: CACHE_IMPL_VERSION.load(Ordering::Relaxed),
+            extensions,
+        }
+    }
+
+    /// Probe capabilities of the current SBI environment without using the cache.
+    ///
+    /// SBI v0.1 environments do not provide the Base extension; they are reported as
+    /// specification version 0.1 with all legacy extensions available.
+    pub fn probe() -> Self {
+        let ret = sbi_call_0(EID_BASE, GET_SBI_SPEC_VERSION);
+        if ret.is_err() {
+            return Self {
+                spec_version: Version::V0_1,
+                impl_id: 0,
+                impl_version: 0,
+                extensions: READY | LEGACY_ALL,
+            };
+        }
+        let mut extensions = READY;
+        for (i, &eid) in EXTENSIONS.iter().enumerate() {
+            if sbi_call_1(EID_BASE, PROBE_EXTENSION, eid).value != 0 {
+                extensions |= 1 << i;
+            }
+        }
+        Self {
+            spec_version: Version::from_raw(ret.value),
+            impl_id: sbi_call_0(EID_BASE, GET_SBI_IMPL_ID).value,
+            impl_version: sbi_call_0(EID_BASE, GET_SBI_IMPL_VERSION).value,
+            extensions,
+        }
+    }
+
+    fn probe_and_cache() -> Self {
+        let caps = Self::probe();
+        let version = caps.spec_version;
+        CACHE_SPEC_VERSION.store((version.major() << 24) | version.minor(), Ordering::Relaxed);
+        CACHE_IMPL_ID.store(caps.impl_id, Ordering::Relaxed);
+        CACHE_IMPL_VERSION.store(caps.impl_version, Ordering::Relaxed);
+        CACHE_EXTENSIONS.store(caps.extensions, Ordering::Release);
+        caps
+    }
+
+    /// SBI specification version of the current environment.
+    #[inline]
+    pub const fn spec_version(&self) -> Version {
+        self.spec_version
+    }
+
+    /// SBI implementation ID, or 0 on SBI v0.1 environments.
+    #[inline]
+    pub const fn impl_id(&self) -> usize {
+        self.impl_id
+    }
+
+    /// SBI implementation version, or 0 on SBI v0.1 environments.
+    #[inline]
+    pub const fn impl_version(&self) -> usize {
+        self.impl_version
+    }
+
+    /// Is the current environment an SBI v0.1 environment without the Base extension?
+    #[inline]
+    pub fn is_legacy(&self) -> bool {
+        self.spec_version < Version::V0_2
+    }
+
+    /// Is the provided extension available in the current environment?
+    ///
+    /// Only extensions defined in the RISC-V SBI Specification are recorded in the snapshot;
+    /// this function returns `false` for any other extension ID.
+    #[inline]
+    pub fn has_extension<E: Extension>(&self, extension: E) -> bool {
+        self.has_eid(extension.extension_id())
+    }
+
+    #[inline]
+    fn has_eid(&self, eid: usize) -> bool {
+        match EXTENSIONS.iter().position(|&x| x == eid) {
+            Some(i) => self.extensions & (1 << i) != 0,
+            None => false,
+        }
+    }
+}
+
+// Extensions recorded in the snapshot; bit `i` of `SbiCaps::extensions` tracks `EXTENSIONS[i]`.
+const EXTENSIONS: [usize; 22] = [
+    sbi_spec::legacy::LEGACY_SET_TIMER,
+    sbi_spec::legacy::LEGACY_CONSOLE_PUTCHAR,
+    sbi_spec::legacy::LEGACY_SEND_IPI,
+    sbi_spec::legacy::LEGACY_REMOTE_FENCE_I,
+    sbi_spec::legacy::LEGACY_REMOTE_SFENCE_VMA,
+    sbi_spec::legacy::LEGACY_REMOTE_SFENCE_VMA_ASID,
+    EID_BASE,
+    sbi_spec::time::EID_TIME,
+    sbi_spec::spi::EID_SPI,
+    sbi_spec::rfnc::EID_RFNC,
+    sbi_spec::hsm::EID_HSM,
+    sbi_spec::srst::EID_SRST,
+    sbi_spec::pmu::EID_PMU,
+    sbi_spec::dbcn::EID_DBCN,
+    sbi_spec::susp::EID_SUSP,
+    sbi_spec::cppc::EID_CPPC,
+    sbi_spec::nacl::EID_NACL,
+    sbi_spec::sta::EID_STA,
+    sbi_spec::sse::EID_SSE,
+    sbi_spec::fwft::EID_FWFT,
+    sbi_spec::dbtr::EID_DBTR,
+    sbi_spec::mpxy::EID_MPXY,
+];
+const LEGACY_ALL: usize = 0b11_1111;
+const READY: usize = 1 << (usize::BITS - 1);
+
+static CACHE_SPEC_VERSION: AtomicUsize = AtomicUsize::new(0);
+static CACHE_IMPL_ID: AtomicUsize = AtomicUsize::new(0);
+static CACHE_IMPL_VERSION: AtomicUsize = AtomicUsize::new(0);
+// Written last with `READY` bit set, publishing the other cached values.
+static CACHE_EXTENSIONS: AtomicUsize = AtomicUsize::new(0);
+
+#[inline]
+fn has_legacy(eid: usize) -> bool {
+    SbiCaps::get().has_eid(eid)
+}
+
+#[inline]
+fn legacy_ret(error: usize) -> crate::SbiRet {
+    crate::SbiRet { error, value: 0 }
+}
+
+// Hart mask in memory as legacy extensions require, covering `LEGACY_MASK_WORDS * usize::BITS` harts.
+const LEGACY_MASK_WORDS: usize = 4;
+
+fn legacy_hart_mask(hart_mask: crate::HartMask) -> Option<[usize; LEGACY_MASK_WORDS]> {
+    let (mask, base) = hart_mask.into_inner();
+    if base == usize::MAX {
+        return Some([usize::MAX; LEGACY_MASK_WORDS]);
+    }
+    let mut words = [0; LEGACY_MASK_WORDS];
+    let bits = usize::BITS as usize;
+    for i in 0..bits {
+        if mask & (1 << i) == 0 {
+            continue;
+        }
+        let hart_id = base.checked_add(i)?;
+        *words.get_mut(hart_id / bits)? |= 1 << (hart_id % bits);
+    }
+    Some(words)
+}
+
+/// Timer programming on any SBI version.
+pub mod timer {
+    use super::{SbiCaps, has_legacy, legacy_ret};
+    use crate::{SbiRet, Timer};
+
+    /// Program the clock for the next event after an absolute time.
+    ///
+    /// Uses [`set_timer`](crate::set_timer) from the `TIME` extension if available,
+    /// or legacy [`set_timer`](crate::legacy::set_timer) otherwise.
+    /// Returns `SbiRet::not_supported()` if neither is available.
+    #[inline]
+    pub fn set(stime_value: u64) -> SbiRet {
+        if SbiCaps::get().has_extension(Timer) {
+            crate::set_timer(stime_value)
+        } else if has_legacy(sbi_spec::legacy::LEGACY_SET_TIMER) {
+            #[allow(deprecated)]
+            legacy_ret(crate::legacy::set_timer(stime_value))
+        } else {
+            SbiRet::not_supported()
+        }
+    }
+}
+
+/// Inter-processor interrupts on any SBI version.
+pub mod ipi {
+    use super::{SbiCaps, has_legacy, legacy_hart_mask, legacy_ret};
+    use crate::{HartMask, Ipi, SbiRet};
+
+    /// Send an inter-processor interrupt to all harts defined in hart mask.
+    ///
+    /// Uses [`send_ipi`](crate::send_ipi) from the `sPI` extension if available,
+    /// or legacy [`send_ipi`](crate::legacy::send_ipi) otherwise.
+    /// Returns `SbiRet::not_supported()` if neither is available.
+    ///
+    /// On legacy environments, `SbiRet::invalid_param()` is returned if `hart_mask`
+    /// selects a hart beyond the hart mask buffer this module passes to the legacy call.
+    #[inline]
+    pub fn send(hart_mask: HartMask) -> SbiRet {
+        if SbiCaps::get().has_extension(Ipi) {
+            crate::send_ipi(hart_mask)
+        } else if has_legacy(sbi_spec::legacy::LEGACY_SEND_IPI) {
+            let Some(words) = legacy_hart_mask(hart_mask) else {
+                return SbiRet::invalid_param();
+            };
+            #[allow(deprecated)]
+            legacy_ret(crate::legacy::send_ipi(words.as_ptr() as usize))
+        } else {
+            SbiRet::not_supported()
+        }
+    }
+}
+
+/// Remote fences on any SBI version.
+pub mod fence {
+    use super::{SbiCaps, has_legacy, legacy_hart_mask, legacy_ret};
+    use crate::{Fence, HartMask, SbiRet};
+
+    /// Execute `FENCE.I` instruction on remote harts.
+    ///
+    /// Uses [`remote_fence_i`](crate::remote_fence_i) from the `RFNC` extension if available,
+    /// or legacy [`remote_fence_i`](crate::legacy::remote_fence_i) otherwise.
+    /// Returns `SbiRet::not_supported()` if neither is available.
+    #[inline]
+    pub fn fence_i(hart_mask: HartMask) -> SbiRet {
+        if SbiCaps::get().has_extension(Fence) {
+            crate::remote_fence_i(hart_mask)
+        } else if has_legacy(sbi_spec::legacy::LEGACY_REMOTE_FENCE_I) {
+            let Some(words) = legacy_hart_mask(hart_mask) else {
+                return SbiRet::invalid_param();
+            };
+            #[allow(deprecated)]
+            legacy_ret(crate::legacy::remote_fence_i(words.as_ptr() as usize))
+        } else {
+            SbiRet::not_supported()
+        }
+    }
+
+    /// Execute `SFENCE.VMA` instructions for all address spaces on remote harts.
+    ///
+    /// Uses [`remote_sfence_vma`](crate::remote_sfence_vma) from the `RFNC` extension if available,
+    /// or legacy [`remote_fence_vma`](crate::legacy::remote_fence_vma) otherwise.
+    /// Returns `SbiRet::not_supported()` if neither is available.
+    #[inline]
+    pub fn sfence_vma(hart_mask: HartMask, start_addr: usize, size: usize) -> SbiRet {
+        if SbiCaps::get().has_extension(Fence) {
+            crate::remote_sfence_vma(hart_mask, start_addr, size)
+        } else if has_legacy(sbi_spec::legacy::LEGACY_REMOTE_SFENCE_VMA) {
+            let Some(words) = legacy_hart_mask(hart_mask) else {
+                return SbiRet::invalid_param();
+            };
+            #[allow(deprecated)]
+            legacy_ret(crate::legacy::remote_fence_vma(
+                words.as_ptr() as usize,
+                start_addr,
+                size,
+            ))
+        } else {
+            SbiRet::not_supported()
+        }
+    }
+
+    /// Execute `SFENCE.VMA` instructions for one address space on remote harts.
+    ///
+    /// Uses [`remote_sfence_vma_asid`](crate::remote_sfence_vma_asid) from the `RFNC` extension
+    /// if available, or legacy [`remote_fence_vma_asid`](crate::legacy::remote_fence_vma_asid) otherwise.
+    /// Returns `SbiRet::not_supported()` if neither is available.
+    #[inline]
+    pub fn sfence_vma_asid(
+        hart_mask: HartMask,
+        start_addr: usize,
+        size: usize,
+        asid: usize,
+    ) -> SbiRet {
+        if SbiCaps::get().has_extension(Fence) {
+            crate::remote_sfence_vma_asid(hart_mask, start_addr, size, asid)
+        } else if has_legacy(sbi_spec::legacy::LEGACY_REMOTE_SFENCE_VMA_ASID) {
+            let Some(words) = legacy_hart_mask(hart_mask) else {
+                return SbiRet::invalid_param();
+            };
+            #[allow(deprecated)]
+            legacy_ret(crate::legacy::remote_fence_vma_asid(
+                words.as_ptr() as usize,
+                start_addr,
+                size,
+                asid,
+            ))
+        } else {
+            SbiRet::not_supported()
+        }
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "mock",
+    not(any(target_arch = "riscv32", target_arch = "riscv64"))
+))]
+mod tests {
+    use super::*;
+    use crate::{HartMask, SbiRet, mock::with_handler};
+    use core::cell::Cell;
+    use sbi_spec::{
+        legacy::*,
+        rfnc::{EID_RFNC, REMOTE_FENCE_I, REMOTE_SFENCE_VMA, REMOTE_SFENCE_VMA_ASID},
+        spi::{EID_SPI, SEND_IPI},
+        time::{EID_TIME, SET_TIMER},
+    };
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Env {
+        // SBI v2.0 providing `TIME`, `sPI` and `RFNC` extensions
+        Extension,
+        // SBI v2.0 without `TIME`, `sPI`, `RFNC` or legacy extensions
+        NoExtension,
+        // SBI v0.1 without the Base extension
+        Legacy,
+    }
+
+    // Fake SBI implementation recording the last call other than the Base extension.
+    struct Fake {
+        env: Env,
+        calls: Cell<usize>,
+        last: Cell<(usize, usize, [usize; 6])>,
+        // First word of the hart mask passed to the last legacy call
+        legacy_mask: Cell<usize>,
+    }
+
+    impl Fake {
+        fn new(env: Env) -> Self {
+            Self {
+                env,
+                calls: Cell::new(0),
+                last: Cell::new((0, 0, [0; 6])),
+                legacy_mask: Cell::new(0),
+            }
+        }
+
+        fn handle(&self, extension: usize, function: usize, param: [usize; 6]) -> SbiRet {
+            match (self.env, extension) {
+                (Env::Legacy, EID_BASE) => return SbiRet::not_supported(),
+                (_, EID_BASE) => {
+                    return match function {
+                        GET_SBI_SPEC_VERSION => SbiRet::success(0x0200_0000),
+                        PROBE_EXTENSION => SbiRet::success(
+                            (self.env == Env::Extension
+                                && [EID_TIME, EID_SPI, EID_RFNC].contains(&param[0]))
+                                as usize,
+                        ),
+                        _ => SbiRet::success(0),
+                    };
+                }
+                (Env::Extension, EID_TIME | EID_SPI | EID_RFNC) => {}
+                (Env::Legacy, LEGACY_SET_TIMER) => {}
+                (
+                    Env::Legacy,
+                    LEGACY_SEND_IPI
+                    | LEGACY_REMOTE_FENCE_I
+                    | LEGACY_REMOTE_SFENCE_VMA
+                    | LEGACY_REMOTE_SFENCE_VMA_ASID,
+                ) => self
+                    .legacy_mask
+                    .set(unsafe { (param[0] as *const usize).read() }),
+                _ => panic!("unexpected SBI call to extension {extension:#x}"),
+            }
+            self.calls.set(self.calls.get() + 1);
+            self.last.set((extension, function, param));
+            SbiRet::success(0)
+        }
+
+        fn run(&self, f: impl FnOnce() -> SbiRet) -> SbiRet {
+            with_handler(&|e, f, p| self.handle(e, f, p), f)
+        }
+    }
+
+    // Run `f` in all environments, checking the extension call and the legacy call it makes.
+    fn check(
+        f: impl Fn() -> SbiRet,
+        extension: (usize, usize, [usize; 6]),
+        legacy: (usize, [usize; 6]),
+        legacy_mask: usize,
+    ) {
+        let fake = Fake::new(Env::Extension);
+        assert_eq!(fake.run(&f), SbiRet::success(0));
+        assert_eq!(fake.calls.get(), 1);
+        assert_eq!(fake.last.get(), extension);
+
+        let fake = Fake::new(Env::NoExtension);
+        assert_eq!(fake.run(&f), SbiRet::not_supported());
+        assert_eq!(fake.calls.get(), 0);
+
+        let fake = Fake::new(Env::Legacy);
+        assert_eq!(fake.run(&f), SbiRet::success(0));
+        assert_eq!(fake.calls.get(), 1);
+        let (eid, _, mut param) = fake.last.get();
+        // the hart mask pointer is checked through `legacy_mask`
+        if legacy_mask != 0 {
+            param[0] = 0;
+        }
+        assert_eq!((eid, param), legacy);
+        assert_eq!(fake.legacy_mask.get(), legacy_mask);
+    }
+
+    #[test]
+    fn probe_legacy_environment() {
+        let fake = Fake::new(Env::Legacy);
+        let caps = with_handler(&|e, f, p| fake.handle(e, f, p), SbiCaps::probe);
+        assert!(caps.is_legacy());
+        assert_eq!(caps.spec_version(), Version::V0_1);
+        assert!(caps.has_eid(LEGACY_SEND_IPI));
+    }
+
+    #[test]
+    fn timer_set() {
+        check(
+            || timer::set(0x1234_5678),
+            (EID_TIME, SET_TIMER, [0x1234_5678, 0, 0, 0, 0, 0]),
+            (LEGACY_SET_TIMER, [0x1234_5678, 0, 0, 0, 0, 0]),
+            0,
+        );
+    }
+
+    #[test]
+    fn ipi_send() {
+        check(
+            || ipi::send(HartMask::from_mask_base(0b101, 2)),
+            (EID_SPI, SEND_IPI, [0b101, 2, 0, 0, 0, 0]),
+            (LEGACY_SEND_IPI, [0; 6]),
+            0b10100,
+        );
+        // harts beyond the legacy hart mask buffer
+        let fake = Fake::new(Env::Legacy);
+        let hart_mask = HartMask::from_mask_base(1, LEGACY_MASK_WORDS * usize::BITS as usize);
+        assert_eq!(fake.run(|| ipi::send(hart_mask)), SbiRet::invalid_param());
+        assert_eq!(fake.calls.get(), 0);
+    }
+
+    #[test]
+    fn fence_wrappers() {
+        let hart_mask = HartMask::from_mask_base(0b11, 1);
+        check(
+            || fence::fence_i(hart_mask),
+            (EID_RFNC, REMOTE_FENCE_I, [0b11, 1, 0, 0, 0, 0]),
+            (LEGACY_REMOTE_FENCE_I, [0; 6]),
+            0b110,
+        );
+        check(
+            || fence::sfence_vma(hart_mask, 0x1000, 0x2000),
+            (EID_RFNC, REMOTE_SFENCE_VMA, [0b11, 1, 0x1000, 0x2000, 0, 0]),
+            (LEGACY_REMOTE_SFENCE_VMA, [0, 0x1000, 0x2000, 0, 0, 0]),
+            0b110,
+        );
+        check(
+            || fence::sfence_vma_asid(hart_mask, 0x1000, 0x2000, 7),
+            (
+                EID_RFNC,
+                REMOTE_SFENCE_VMA_ASID,
+                [0b11, 1, 0x1000, 0x2000, 7, 0],
+            ),
+            (LEGACY_REMOTE_SFENCE_VMA_ASID, [0, 0x1000, 0x2000, 7, 0, 0]),
+            0b110,
+        );
+    }
+
+    #[test]
+    fn legacy_hart_masks() {
+        let bits = usize::BITS as usize;
+        assert_eq!(
+            legacy_hart_mask(HartMask::from_mask_base(0b11, bits - 1)),
+            Some([1 << (bits - 1), 1, 0, 0])
+        );
+        // `hart_mask_base` of all ones selects all harts
+        assert_eq!(
+            legacy_hart_mask(HartMask::all()),
+            Some([usize::MAX; LEGACY_MASK_WORDS])
+        );
+        // hart ID overflows
+        assert_eq!(
+            legacy_hart_mask(HartMask::from_mask_base(1 << (bits - 1), usize::MAX - 1)),
+            None
+        );
+        // the last hart the mask buffer covers, and the first beyond it
+        let end = LEGACY_MASK_WORDS * bits;
+        assert_eq!(
+            legacy_hart_mask(HartMask::from_mask_base(1, end - 1)),
+            Some([0, 0, 0, 1 << (bits - 1)])
+        );
+        assert_eq!(legacy_hart_mask(HartMask::from_mask_base(1, end)), None);
+        assert_eq!(
+            legacy_hart_mask(HartMask::from_mask_base(0b11, end - 1)),
+            None
+        );
+    }
+}
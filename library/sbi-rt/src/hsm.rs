@@ -2,9 +2,10 @@
 
 use crate::binary::{sbi_call_0, sbi_call_1, sbi_call_3};
 
+use core::cell::UnsafeCell;
 use sbi_spec::{
     binary::SbiRet,
-    hsm::{EID_HSM, HART_GET_STATUS, HART_START, HART_STOP, HART_SUSPEND},
+    hsm::{EID_HSM, HART_GET_STATUS, HART_START, HART_STOP, HART_SUSPEND, hart_state},
 };
 
 /// Start executing the given hart at specified address in supervisor-mode.
@@ -240,3 +241,136 @@ define_suspend_type! {
     Retentive(sbi_spec::hsm::suspend_type::RETENTIVE) /// Default retentive hart suspension.
     NonRetentive(sbi_spec::hsm::suspend_type::NON_RETENTIVE) /// Default non-retentive hart suspension.
 }
+
+/// Function run on a hart started or resumed by [`hart_start_on`] or [`hart_suspend_on`].
+///
+/// It is called with the hart ID; the hart is stopped using `hart_stop` once it returns.
+pub type HartMain = &'static (dyn Fn(usize) + Sync);
+
+/// Stack for a hart started or resumed by [`hart_start_on`] or [`hart_suspend_on`].
+///
+/// It contains `N` bytes of stack space, followed by the function to run on the hart.
+#[repr(C, align(16))]
+pub struct HartStack<const N: usize> {
+    stack: UnsafeCell<[u8; N]>,
+    main: UnsafeCell<Option<HartMain>>,
+}
+
+// Each stack is used by one hart at a time, as required by functions using it.
+unsafe impl<const N: usize> Sync for HartStack<N> {}
+
+impl<const N: usize> HartStack<N> {
+    /// Create an empty hart stack.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            stack: UnsafeCell::new([0; N]),
+            main: UnsafeCell::new(None),
+        }
+    }
+
+    // The stack grows down from the function record; `opaque` of HSM calls points to it.
+    #[inline]
+    unsafe fn prepare(&self, main: HartMain) -> usize {
+        unsafe { self.main.get().write(Some(main)) };
+        self.main.get() as usize
+    }
+}
+
+impl<const N: usize> Default for HartStack<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Start hart `hartid` on `stack` running `main`.
+///
+/// The started hart sets its stack pointer to the top of `stack` and calls `main` with its hart ID.
+/// Other registers including `gp` and `tp` are not initialized; `main` should set them up if needed.
+///
+/// The return value is the same as [`hart_start`].
+///
+/// # Safety
+///
+/// - The `stack` MUST NOT be used by any other hart until the started hart stops or suspends.
+/// - The `stack` and code of `main` MUST be mapped at the same virtual and physical address,
+///   as the hart starts with address translation disabled.
+#[inline]
+pub unsafe fn hart_start_on<const N: usize>(
+    hartid: usize,
+    stack: &'static HartStack<N>,
+    main: HartMain,
+) -> SbiRet {
+    let opaque = unsafe { stack.prepare(main) };
+    hart_start(hartid, hart_boot_entry as usize, opaque)
+}
+
+/// Start all stopped harts with hart ID less than `stacks.len()`, each on `stacks[hartid]`.
+///
+/// Hart IDs which are invalid, or not in `STOPPED` state, are skipped; the calling hart is
+/// thus never restarted. Returns the number of harts started successfully.
+///
+/// # Safety
+///
+/// Requirements of [`hart_start_on`] apply to each stack of started harts.
+pub unsafe fn hart_start_all<const N: usize>(
+    stacks: &'static [HartStack<N>],
+    main: HartMain,
+) -> usize {
+    stacks
+        .iter()
+        .enumerate()
+        .filter(|&(hartid, _)| hart_get_status(hartid) == SbiRet::success(hart_state::STOPPED))
+        .filter(|&(hartid, stack)| unsafe { hart_start_on(hartid, stack, main) }.is_ok())
+        .count()
+}
+
+/// Suspend the calling hart in non-retentive mode, and resume on `stack` running `main`.
+///
+/// On resumption, the hart sets its stack pointer to the top of `stack` and calls `main` with its hart ID,
+/// the same way as [`hart_start_on`]. The `stack` can be the stack this hart is currently running on,
+/// as its content is not needed after a non-retentive suspend.
+///
+/// The return value is the same as [`hart_suspend`], returned only if the suspend request failed.
+///
+/// # Safety
+///
+/// Requirements of [`hart_start_on`] apply to `stack` and `main`.
+#[inline]
+pub unsafe fn hart_suspend_on<const N: usize>(
+    stack: &'static HartStack<N>,
+    main: HartMain,
+) -> SbiRet {
+    let opaque = unsafe { stack.prepare(main) };
+    hart_suspend(NonRetentive, hart_boot_entry as usize, opaque)
+}
+
+#[cfg_attr(
+    not(any(target_arch = "riscv32", target_arch = "riscv64")),
+    allow(unused)
+)]
+extern "C" fn hart_boot_main(hartid: usize, main: &Option<HartMain>) -> ! {
+    if let Some(main) = main {
+        main(hartid);
+    }
+    hart_stop();
+    unreachable!("hart_stop returned on hart {hartid}")
+}
+
+// Started or resumed with `a0` holding the hart ID and `a1` holding the `opaque` parameter.
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+#[unsafe(naked)]
+unsafe extern "C" fn hart_boot_entry(hartid: usize, opaque: usize) -> ! {
+    core::arch::naked_asm!(
+        "andi sp, a1, -16",
+        "call {main}",
+        "unimp",
+        main = sym hart_boot_main,
+    )
+}
+
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+unsafe extern "C" fn hart_boot_entry(_hartid: usize, _opaque: usize) -> ! {
+    unimplemented!("unsupported architecture")
+}
@@ -76,6 +76,7 @@ pub fn shutdown() -> ! {
 }
 
 #[inline(always)]
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
 fn sbi_call_legacy_0(eid: usize) -> usize {
     let error;
     unsafe {
@@ -89,6 +90,13 @@ fn sbi_call_legacy_0(eid: usize) -> usize {
 }
 
 #[inline(always)]
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+fn sbi_call_legacy_0(eid: usize) -> usize {
+    crate::binary::host_call(eid, 0, [0, 0, 0, 0, 0, 0]).error
+}
+
+#[inline(always)]
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
 fn sbi_call_legacy_1(eid: usize, arg0: usize) -> usize {
     let error;
     unsafe {
@@ -101,8 +109,15 @@ fn sbi_call_legacy_1(eid: usize, arg0: usize) -> usize {
     error
 }
 
+#[inline(always)]
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+fn sbi_call_legacy_1(eid: usize, arg0: usize) -> usize {
+    crate::binary::host_call(eid, 0, [arg0, 0, 0, 0, 0, 0]).error
+}
+
 #[cfg(target_pointer_width = "32")]
 #[inline(always)]
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
 fn sbi_call_legacy_2(eid: usize, arg0: usize, arg1: usize) -> usize {
     let error;
     unsafe {
@@ -116,7 +131,15 @@ fn sbi_call_legacy_2(eid: usize, arg0: usize, arg1: usize) -> usize {
     error
 }
 
+#[cfg(target_pointer_width = "32")]
 #[inline(always)]
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+fn sbi_call_legacy_2(eid: usize, arg0: usize, arg1: usize) -> usize {
+    crate::binary::host_call(eid, 0, [arg0, arg1, 0, 0, 0, 0]).error
+}
+
+#[inline(always)]
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
 fn sbi_call_legacy_3(eid: usize, arg0: usize, arg1: usize, arg2: usize) -> usize {
     let error;
     unsafe {
@@ -132,6 +155,13 @@ fn sbi_call_legacy_3(eid: usize, arg0: usize, arg1: usize, arg2: usize) -> usize
 }
 
 #[inline(always)]
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+fn sbi_call_legacy_3(eid: usize, arg0: usize, arg1: usize, arg2: usize) -> usize {
+    crate::binary::host_call(eid, 0, [arg0, arg1, arg2, 0, 0, 0]).error
+}
+
+#[inline(always)]
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
 fn sbi_call_legacy_4(eid: usize, arg0: usize, arg1: usize, arg2: usize, arg3: usize) -> usize {
     let error;
     unsafe {
@@ -146,3 +176,9 @@ fn sbi_call_legacy_4(eid: usize, arg0: usize, arg1: usize, arg2: usize, arg3: us
     }
     error
 }
+
+#[inline(always)]
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+fn sbi_call_legacy_4(eid: usize, arg0: usize, arg1: usize, arg2: usize, arg3: usize) -> usize {
+    crate::binary::host_call(eid, 0, [arg0, arg1, arg2, arg3, 0, 0]).error
+}
@@ -49,6 +49,20 @@ mod dbtr;
 // §20
 mod mpxy;
 
+// Formatted console output built on §12 (DBCN)
+pub mod console;
+// Message proxy client and RPMI service groups built on §20 (MPXY)
+pub mod rpmi;
+// Probe cache and fallback across SBI versions built on §4 (Base) and §5 (Legacy)
+#[cfg(feature = "fallback")]
+pub mod fallback;
+// Host-side mock SBI environment in place of §3 (Binary Encoding)
+#[cfg(all(
+    feature = "mock",
+    not(any(target_arch = "riscv32", target_arch = "riscv64"))
+))]
+pub mod mock;
+
 pub use sbi_spec::{
     base::Version,
     binary::{CounterMask, HartMask, Physical, SbiRet, SharedPtr},
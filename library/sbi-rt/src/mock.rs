@@ -0,0 +1,75 @@
+//! Mock SBI environment for host-side unit tests.
+//!
+//! On non-RISC-V targets with the `mock` feature, every SBI call of this crate is routed to
+//! a handler installed on the current thread by [`with_handler`], so supervisor logic calling
+//! functions like `hart_start`, `set_timer` or `console_write` can be tested with `cargo test`
+//! against a fake SBI implementation.
+//!
+//! Probe results otherwise cached across the process, like the console backend and
+//! `fallback::SbiCaps`, are probed again on every use, so handlers never see each other's results.
+//!
+//! The handler has the same signature as `handle_ecall` of `rustsbi::RustSBI`; with the `mock`
+//! feature of the `rustsbi` crate, `rustsbi::with_mock` installs any RustSBI implementation
+//! as the handler.
+//!
+//! # Examples
+//!
+//! ```
+//! use sbi_rt::{SbiRet, mock::with_handler};
+//!
+//! let fake = |extension: usize, function: usize, _param: [usize; 6]| match (extension, function) {
+//!     (0x10, 0) => SbiRet::success(0x0300_0000),
+//!     _ => SbiRet::not_supported(),
+//! };
+//! let version = with_handler(&fake, sbi_rt::get_spec_version);
+//! assert_eq!(version, sbi_rt::Version::V3_0);
+//! ```
+
+extern crate std;
+
+use core::{cell::Cell, ptr::NonNull};
+use sbi_spec::binary::SbiRet;
+
+type RawHandler = NonNull<dyn Fn(usize, usize, [usize; 6]) -> SbiRet>;
+
+std::thread_local! {
+    static HANDLER: Cell<Option<RawHandler>> = const { Cell::new(None) };
+}
+
+/// Run `f` with SBI calls on the current thread handled by `handler`.
+///
+/// The handler receives the extension ID, function ID and six parameters of each SBI call,
+/// and returns its `SbiRet` result. Handlers may be nested; the previous handler is restored
+/// when `f` returns or panics. Other threads are not affected.
+pub fn with_handler<H, R>(handler: &H, f: impl FnOnce() -> R) -> R
+where
+    H: Fn(usize, usize, [usize; 6]) -> SbiRet,
+{
+    struct Restore(Option<RawHandler>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            HANDLER.with(|cell| cell.set(self.0));
+        }
+    }
+    let handler: &dyn Fn(usize, usize, [usize; 6]) -> SbiRet = handler;
+    // SAFETY: only the lifetime is erased; `Restore` uninstalls the handler before `with_handler`
+    // returns or unwinds, so it is never called after the borrow of `handler` ends.
+    let raw: RawHandler = unsafe { core::mem::transmute(NonNull::from(handler)) };
+    let _restore = Restore(HANDLER.with(|cell| cell.replace(Some(raw))));
+    f()
+}
+
+/// Handle an SBI call using the handler of the current thread.
+///
+/// Panics if no handler is installed.
+pub(crate) fn handle_ecall(extension: usize, function: usize, param: [usize; 6]) -> SbiRet {
+    let Some(raw) = HANDLER.with(Cell::get) else {
+        panic!(
+            "SBI call (extension {extension:#x}, function {function}) without mock handler; \
+            wrap the call in `sbi_rt::mock::with_handler`"
+        )
+    };
+    // SAFETY: the handler is alive as long as it is installed, see `with_handler`.
+    let handler = unsafe { raw.as_ref() };
+    handler(extension, function, param)
+}
@@ -2,9 +2,13 @@
 
 use crate::binary::{sbi_call_0, sbi_call_1, sbi_call_3};
 
+use core::cell::UnsafeCell;
 use sbi_spec::{
     binary::{SbiRet, SharedPtr},
-    nacl::{EID_NACL, PROBE_FEATURE, SET_SHMEM, SYNC_CSR, SYNC_HFENCE, SYNC_SRET, shmem_size},
+    nacl::{
+        EID_NACL, PROBE_FEATURE, SET_SHMEM, SYNC_CSR, SYNC_HFENCE, SYNC_SRET, feature_id,
+        shmem_offset, shmem_size,
+    },
 };
 
 /// Probe a nested acceleration feature.
@@ -149,3 +153,358 @@ pub fn nacl_sync_hfence(entry_index: usize) -> SbiRet {
 pub fn nacl_sync_sret() -> SbiRet {
     sbi_call_0(EID_NACL, SYNC_SRET)
 }
+
+/// Nested acceleration features provided by the SBI implementation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NaclFeatures {
+    /// CSRs in the shared memory can be synchronized using `nacl_sync_csr`.
+    pub sync_csr: bool,
+    /// HFENCE entries in the shared memory can be synchronized using `nacl_sync_hfence`.
+    pub sync_hfence: bool,
+    /// SRET can be emulated using `nacl_sync_sret`.
+    pub sync_sret: bool,
+    /// CSRs in the auto-swap context are swapped by `nacl_sync_sret`.
+    pub autoswap_csr: bool,
+}
+
+impl NaclFeatures {
+    /// Probe all nested acceleration features on the SBI implementation.
+    #[inline]
+    pub fn probe() -> Self {
+        let available = |id: usize| nacl_probe_feature(id as u32).value != 0;
+        Self {
+            sync_csr: available(feature_id::SYNC_CSR),
+            sync_hfence: available(feature_id::SYNC_HFENCE),
+            sync_sret: available(feature_id::SYNC_SRET),
+            autoswap_csr: available(feature_id::AUTOSWAP_CSR),
+        }
+    }
+}
+
+/// A nested HFENCE entry in the NACL shared memory.
+///
+/// The `config` field of an entry is encoded as follows, where the `VMID` and `ASID`
+/// fields are 14 and 16 bits wide on RV64, or 7 and 9 bits wide on RV32:
+///
+/// ```text
+///     config[XLEN-1] = Pending;
+///     config[XLEN-5:XLEN-8] = Type; // (one of `sbi_spec::nacl::hfence_type`)
+///     config[XLEN-10:XLEN-16] = Order; // (page size is 2^(Order + 12) bytes)
+///     config[XLEN-17:XLEN-16-VMID_BITS] = VMID;
+///     config[ASID_BITS-1:0] = ASID;
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HfenceEntry {
+    config: usize,
+    pnum: usize,
+    pcount: usize,
+}
+
+impl HfenceEntry {
+    const PEND_SHIFT: u32 = usize::BITS - 1;
+    const TYPE_SHIFT: u32 = usize::BITS - 8;
+    const ORDER_SHIFT: u32 = usize::BITS - 16;
+    #[cfg(target_pointer_width = "64")]
+    const VMID_BITS: u32 = 14;
+    #[cfg(target_pointer_width = "32")]
+    const VMID_BITS: u32 = 7;
+    #[cfg(target_pointer_width = "64")]
+    const ASID_BITS: u32 = 16;
+    #[cfg(target_pointer_width = "32")]
+    const ASID_BITS: u32 = 9;
+    const VMID_SHIFT: u32 = Self::ORDER_SHIFT - Self::VMID_BITS;
+
+    /// Create a pending HFENCE entry.
+    ///
+    /// The entry fences `pcount` pages of size `2^(order + 12)` bytes starting from page number `pnum`.
+    #[inline]
+    pub const fn new(
+        hfence_type: usize,
+        order: usize,
+        vmid: usize,
+        asid: usize,
+        pnum: usize,
+        pcount: usize,
+    ) -> Self {
+        let config = (1 << Self::PEND_SHIFT)
+            | (hfence_type & 0xf) << Self::TYPE_SHIFT
+            | (order & 0x7f) << Self::ORDER_SHIFT
+            | (vmid & ((1 << Self::VMID_BITS) - 1)) << Self::VMID_SHIFT
+            | (asid & ((1 << Self::ASID_BITS) - 1));
+        Self {
+            config,
+            pnum,
+            pcount,
+        }
+    }
+
+    /// Returns whether this entry is pending to be processed by the SBI implementation.
+    #[inline]
+    pub const fn is_pending(&self) -> bool {
+        self.config >> Self::PEND_SHIFT != 0
+    }
+
+    /// Returns the type of this entry.
+    #[inline]
+    pub const fn hfence_type(&self) -> usize {
+        (self.config >> Self::TYPE_SHIFT) & 0xf
+    }
+
+    /// Returns the page size order of this entry.
+    #[inline]
+    pub const fn order(&self) -> usize {
+        (self.config >> Self::ORDER_SHIFT) & 0x7f
+    }
+
+    /// Returns the VMID of this entry.
+    #[inline]
+    pub const fn vmid(&self) -> usize {
+        (self.config >> Self::VMID_SHIFT) & ((1 << Self::VMID_BITS) - 1)
+    }
+
+    /// Returns the ASID of this entry.
+    #[inline]
+    pub const fn asid(&self) -> usize {
+        self.config & ((1 << Self::ASID_BITS) - 1)
+    }
+
+    /// Returns the page number of this entry.
+    #[inline]
+    pub const fn pnum(&self) -> usize {
+        self.pnum
+    }
+
+    /// Returns the page count of this entry.
+    #[inline]
+    pub const fn pcount(&self) -> usize {
+        self.pcount
+    }
+}
+
+const XLENB: usize = size_of::<usize>();
+const HFENCE_ENTRY_WORDS: usize = 4;
+const HFENCE_SIZE: usize = shmem_offset::DIRTY_BITMAP - shmem_offset::HFENCE;
+
+/// Nested acceleration shared memory of a hart.
+///
+/// It provides typed access to the scratch space and the CSR space defined in chapter 15.
+/// Values are stored in little-endian as required by the RISC-V SBI specification.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sbi_rt::{NaclFeatures, NaclShmem};
+///
+/// const CSR_VSSTATUS: u16 = 0x200;
+/// static mut SHMEM: NaclShmem = NaclShmem::new();
+///
+/// let shmem = unsafe { &mut *(&raw mut SHMEM) };
+/// if NaclFeatures::probe().sync_sret {
+///     // Safety: statics in this hypervisor are mapped at their physical addresses.
+///     unsafe { shmem.register() }.expect("register NACL shared memory");
+///     let guest_gprs = [0usize; 32];
+///     shmem.set_sret_gprs(&guest_gprs);
+///     let _ = shmem.write_csrs_and_sync_sret(&[(CSR_VSSTATUS, 0)]);
+/// }
+/// ```
+#[repr(C, align(4096))]
+pub struct NaclShmem {
+    scratch: UnsafeCell<[usize; 4096 / XLENB]>,
+    csrs: UnsafeCell<[usize; 1024]>,
+}
+
+const _: () = assert!(size_of::<NaclShmem>() == shmem_size::NATIVE);
+
+impl NaclShmem {
+    /// Number of nested HFENCE entries in the scratch space.
+    pub const HFENCE_ENTRIES: usize = HFENCE_SIZE / (HFENCE_ENTRY_WORDS * XLENB);
+
+    /// Create a zeroed NACL shared memory.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            scratch: UnsafeCell::new([0; 4096 / XLENB]),
+            csrs: UnsafeCell::new([0; 1024]),
+        }
+    }
+
+    /// Set this structure as the NACL shared memory of the calling hart.
+    ///
+    /// The return value is the same as [`nacl_set_shmem`].
+    ///
+    /// # Safety
+    ///
+    /// This structure MUST be mapped at the same virtual and physical address,
+    /// and MUST NOT be moved or dropped until another shared memory is set on the calling hart.
+    #[inline]
+    pub unsafe fn register(&mut self) -> SbiRet {
+        let phys_addr = self as *mut Self as usize;
+        nacl_set_shmem(SharedPtr::new(phys_addr, 0), 0)
+    }
+
+    /// Disable NACL shared memory on the calling hart.
+    ///
+    /// The return value is the same as [`nacl_set_shmem`].
+    #[inline]
+    pub fn unregister() -> SbiRet {
+        nacl_set_shmem(SharedPtr::new(usize::MAX, usize::MAX), 0)
+    }
+
+    /// Returns the index of CSR `csr_num` in the CSR space and the CSR dirty bitmap.
+    #[inline]
+    pub const fn csr_index(csr_num: u16) -> usize {
+        let csr_num = csr_num as usize;
+        ((csr_num & 0xc00) >> 2) | (csr_num & 0xff)
+    }
+
+    /// Read CSR `csr_num` from the CSR space.
+    #[inline]
+    pub fn csr_read(&self, csr_num: u16) -> usize {
+        let ptr = self.csrs.get().cast::<usize>();
+        usize::from_le(unsafe { ptr.add(Self::csr_index(csr_num)).read_volatile() })
+    }
+
+    /// Write CSR `csr_num` into the CSR space, and mark it dirty in the CSR dirty bitmap.
+    #[inline]
+    pub fn csr_write(&mut self, csr_num: u16, value: usize) {
+        let index = Self::csr_index(csr_num);
+        let ptr = self.csrs.get().cast::<usize>();
+        unsafe { ptr.add(index).write_volatile(value.to_le()) };
+        let word = shmem_offset::DIRTY_BITMAP / XLENB + index / usize::BITS as usize;
+        let dirty = self.scratch_read(word) | 1 << (index % usize::BITS as usize);
+        self.scratch_write(word, dirty);
+    }
+
+    /// Returns whether CSR `csr_num` is marked dirty in the CSR dirty bitmap.
+    #[inline]
+    pub fn is_csr_dirty(&self, csr_num: u16) -> bool {
+        let index = Self::csr_index(csr_num);
+        let word = shmem_offset::DIRTY_BITMAP / XLENB + index / usize::BITS as usize;
+        self.scratch_read(word) & 1 << (index % usize::BITS as usize) != 0
+    }
+
+    /// Synchronize CSR `csr_num`, or all CSRs if `csr_num` is `None`.
+    ///
+    /// The return value is the same as [`nacl_sync_csr`].
+    #[inline]
+    pub fn sync_csr(&mut self, csr_num: Option<u16>) -> SbiRet {
+        nacl_sync_csr(csr_num.map_or(usize::MAX, usize::from))
+    }
+
+    /// Read nested HFENCE entry `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`Self::HFENCE_ENTRIES`].
+    #[inline]
+    pub fn hfence_read(&self, index: usize) -> HfenceEntry {
+        let word = Self::hfence_word(index);
+        HfenceEntry {
+            config: self.scratch_read(word),
+            pnum: self.scratch_read(word + 1),
+            pcount: self.scratch_read(word + 3),
+        }
+    }
+
+    /// Write nested HFENCE entry `index`.
+    ///
+    /// The `config` field is written last, so that the SBI implementation observes
+    /// a complete entry once it is pending.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`Self::HFENCE_ENTRIES`].
+    #[inline]
+    pub fn hfence_write(&mut self, index: usize, entry: HfenceEntry) {
+        let word = Self::hfence_word(index);
+        self.scratch_write(word + 1, entry.pnum);
+        self.scratch_write(word + 3, entry.pcount);
+        self.scratch_write(word, entry.config);
+    }
+
+    /// Write `entry` into the first nested HFENCE entry which is not pending.
+    ///
+    /// Returns the index of the written entry, or `None` if all entries are pending.
+    #[inline]
+    pub fn hfence_push(&mut self, entry: HfenceEntry) -> Option<usize> {
+        let index = (0..Self::HFENCE_ENTRIES).find(|&i| !self.hfence_read(i).is_pending())?;
+        self.hfence_write(index, entry);
+        Some(index)
+    }
+
+    /// Synchronize nested HFENCE entry `index`, or all entries if `index` is `None`.
+    ///
+    /// The return value is the same as [`nacl_sync_hfence`].
+    #[inline]
+    pub fn sync_hfence(&mut self, index: Option<usize>) -> SbiRet {
+        nacl_sync_hfence(index.unwrap_or(usize::MAX))
+    }
+
+    /// Read general purpose register `x{index}` from the nested SRET context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is zero or larger than 31.
+    #[inline]
+    pub fn sret_gpr(&self, index: usize) -> usize {
+        assert!((1..32).contains(&index));
+        self.scratch_read(shmem_offset::SRET / XLENB + index)
+    }
+
+    /// Write general purpose register `x{index}` into the nested SRET context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is zero or larger than 31.
+    #[inline]
+    pub fn set_sret_gpr(&mut self, index: usize, value: usize) {
+        assert!((1..32).contains(&index));
+        self.scratch_write(shmem_offset::SRET / XLENB + index, value);
+    }
+
+    /// Write general purpose registers `x1` to `x31` into the nested SRET context.
+    ///
+    /// The value at index 0 of `gprs` is ignored.
+    #[inline]
+    pub fn set_sret_gprs(&mut self, gprs: &[usize; 32]) {
+        for (index, &value) in gprs.iter().enumerate().skip(1) {
+            self.scratch_write(shmem_offset::SRET / XLENB + index, value);
+        }
+    }
+
+    /// Write `csrs` into the CSR space, then synchronize them and emulate SRET using `nacl_sync_sret`.
+    ///
+    /// The return value is the same as [`nacl_sync_sret`].
+    #[inline]
+    pub fn write_csrs_and_sync_sret(&mut self, csrs: &[(u16, usize)]) -> SbiRet {
+        for &(csr_num, value) in csrs {
+            self.csr_write(csr_num, value);
+        }
+        nacl_sync_sret()
+    }
+
+    #[inline]
+    fn hfence_word(index: usize) -> usize {
+        assert!(index < Self::HFENCE_ENTRIES);
+        shmem_offset::HFENCE / XLENB + index * HFENCE_ENTRY_WORDS
+    }
+
+    #[inline]
+    fn scratch_read(&self, word: usize) -> usize {
+        let ptr = self.scratch.get().cast::<usize>();
+        usize::from_le(unsafe { ptr.add(word).read_volatile() })
+    }
+
+    #[inline]
+    fn scratch_write(&mut self, word: usize, value: usize) {
+        let ptr = self.scratch.get().cast::<usize>();
+        unsafe { ptr.add(word).write_volatile(value.to_le()) };
+    }
+}
+
+impl Default for NaclShmem {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
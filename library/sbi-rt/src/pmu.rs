@@ -2,11 +2,15 @@
 
 use crate::binary::{sbi_call_0, sbi_call_1, sbi_call_3};
 
+use core::cell::UnsafeCell;
 use sbi_spec::{
-    binary::{CounterMask, SbiRet, SharedPtr},
+    binary::{CounterMask, Error, SbiRet, SharedPtr},
     pmu::{
         COUNTER_CONFIG_MATCHING, COUNTER_FW_READ, COUNTER_FW_READ_HI, COUNTER_GET_INFO,
-        COUNTER_START, COUNTER_STOP, EID_PMU, NUM_COUNTERS, SNAPSHOT_SET_SHMEM, shmem_size::SIZE,
+        COUNTER_START, COUNTER_STOP, CounterInfo, EID_PMU, EventIdx, NUM_COUNTERS,
+        SNAPSHOT_SET_SHMEM,
+        flags::{CounterCfgFlags, CounterStartFlags, CounterStopFlags},
+        shmem_size::SIZE,
     },
 };
 
@@ -365,3 +369,340 @@ impl StopFlags for usize {
         *self
     }
 }
+
+impl ConfigFlags for CounterCfgFlags {
+    #[inline]
+    fn raw(&self) -> usize {
+        self.bits()
+    }
+}
+
+impl StartFlags for CounterStartFlags {
+    #[inline]
+    fn raw(&self) -> usize {
+        self.bits()
+    }
+}
+
+impl StopFlags for CounterStopFlags {
+    #[inline]
+    fn raw(&self) -> usize {
+        self.bits()
+    }
+}
+
+/// A counter configured to monitor a PMU event.
+///
+/// The counter is found and configured by [`PmuCounter::config`], and is released
+/// using `pmu_counter_stop` with the `RESET` flag once the handle is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sbi_rt::{CounterMask, PmuCounter};
+/// use sbi_spec::pmu::{EventIdx, flags::CounterCfgFlags, hardware_event::INSTRUCTIONS};
+///
+/// let counters = CounterMask::from_mask_base(usize::MAX >> 1, 0);
+/// let event = EventIdx::hardware_general(INSTRUCTIONS);
+/// let mut counter = PmuCounter::config(counters, event, 0, CounterCfgFlags::CLEAR_VALUE)?;
+/// counter.start()?;
+/// let before = counter.read()?;
+/// // ... workload ...
+/// let instructions = counter.delta(before, counter.read()?);
+/// # Ok::<(), sbi_spec::binary::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct PmuCounter {
+    counter_idx: usize,
+    info: CounterInfo,
+    event: EventIdx,
+    running: bool,
+}
+
+impl PmuCounter {
+    /// Find and configure a counter from `counters` to monitor `event`.
+    ///
+    /// The counter is started if `config_flags` contains `AUTO_START`.
+    pub fn config(
+        counters: CounterMask,
+        event: EventIdx,
+        event_data: u64,
+        config_flags: CounterCfgFlags,
+    ) -> Result<Self, Error> {
+        let counter_idx =
+            pmu_counter_config_matching(counters, config_flags, event.raw(), event_data)
+                .into_result()?;
+        let info = pmu_counter_get_info(counter_idx).into_result();
+        let counter = Self {
+            counter_idx,
+            info: CounterInfo::from_raw(info.unwrap_or(0)),
+            event,
+            running: config_flags.contains(CounterCfgFlags::AUTO_START),
+        };
+        // counter is released on drop if its information is invalid.
+        info.map(|_| counter)
+    }
+
+    /// Returns the index of this counter.
+    #[inline]
+    pub const fn counter_idx(&self) -> usize {
+        self.counter_idx
+    }
+
+    /// Returns details about this counter.
+    #[inline]
+    pub const fn info(&self) -> CounterInfo {
+        self.info
+    }
+
+    /// Returns the event monitored by this counter.
+    #[inline]
+    pub const fn event(&self) -> EventIdx {
+        self.event
+    }
+
+    /// Returns whether this counter has been started.
+    #[inline]
+    pub const fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Start counting from the current counter value.
+    #[inline]
+    pub fn start(&mut self) -> Result<(), Error> {
+        self.start_with_flags(CounterStartFlags::empty(), 0)
+    }
+
+    /// Start counting from `initial_value`.
+    #[inline]
+    pub fn start_with(&mut self, initial_value: u64) -> Result<(), Error> {
+        self.start_with_flags(CounterStartFlags::INIT_VALUE, initial_value)
+    }
+
+    /// Start counting from the value in the snapshot shared memory.
+    ///
+    /// A shared memory must have been set using [`pmu_snapshot_register`] on the calling hart.
+    #[inline]
+    pub fn start_from_snapshot(&mut self) -> Result<(), Error> {
+        self.start_with_flags(CounterStartFlags::INIT_SNAPSHOT, 0)
+    }
+
+    fn start_with_flags(
+        &mut self,
+        flags: CounterStartFlags,
+        initial_value: u64,
+    ) -> Result<(), Error> {
+        pmu_counter_start(self.mask(), flags, initial_value).into_result()?;
+        self.running = true;
+        Ok(())
+    }
+
+    /// Stop counting, keeping the counter configured.
+    #[inline]
+    pub fn stop(&mut self) -> Result<(), Error> {
+        pmu_counter_stop(self.mask(), CounterStopFlags::empty()).into_result()?;
+        self.running = false;
+        Ok(())
+    }
+
+    /// Stop counting, and returns the counter value saved into `snapshot`
+    /// with whether this counter has overflowed.
+    ///
+    /// The `snapshot` must have been set using [`pmu_snapshot_register`] on the calling hart.
+    pub fn stop_snapshot(&mut self, snapshot: &PmuSnapshot) -> Result<(u64, bool), Error> {
+        pmu_counter_stop(self.mask(), CounterStopFlags::TAKE_SNAPSHOT).into_result()?;
+        self.running = false;
+        // snapshot values are relative to `counter_idx_base`, which is this counter.
+        let value = snapshot.counter_value(0) & self.value_mask();
+        let overflowed = snapshot.overflow_bitmap() & 1 != 0;
+        Ok((value, overflowed))
+    }
+
+    /// Read the current counter value.
+    ///
+    /// Hardware counters are read from their CSR directly, while firmware counters are read
+    /// using `pmu_counter_fw_read`.
+    ///
+    /// # Panics
+    ///
+    /// Panics on hardware counters on non-RISC-V targets, including the `mock` environment,
+    /// as counter CSRs are not SBI calls and can't be handled by a mock handler.
+    pub fn read(&self) -> Result<u64, Error> {
+        if self.info.is_firmware() {
+            let lo = pmu_counter_fw_read(self.counter_idx).into_result()? as u64;
+            match () {
+                #[cfg(target_pointer_width = "32")]
+                () => {
+                    // `pmu_counter_fw_read_hi` is only available since SBI 2.0.
+                    let hi = pmu_counter_fw_read_hi(self.counter_idx).unwrap_or(0) as u64;
+                    Ok(hi << 32 | lo)
+                }
+                #[cfg(target_pointer_width = "64")]
+                () => Ok(lo),
+            }
+        } else {
+            read_counter_csr(self.info.csr())
+                .map(|value| value & self.value_mask())
+                .ok_or(Error::NotSupported)
+        }
+    }
+
+    /// Returns the mask of valid bits in counter values.
+    #[inline]
+    pub const fn value_mask(&self) -> u64 {
+        if self.info.is_firmware() || self.info.width() >= 63 {
+            u64::MAX
+        } else {
+            (1 << (self.info.width() + 1)) - 1
+        }
+    }
+
+    /// Returns events counted between two values read from this counter,
+    /// taking the wraparound of narrow counters into account.
+    #[inline]
+    pub const fn delta(&self, earlier: u64, later: u64) -> u64 {
+        later.wrapping_sub(earlier) & self.value_mask()
+    }
+
+    #[inline]
+    fn mask(&self) -> CounterMask {
+        CounterMask::from_mask_base(1, self.counter_idx)
+    }
+}
+
+impl Drop for PmuCounter {
+    #[inline]
+    fn drop(&mut self) {
+        // counters already stopped are not reset by `pmu_counter_stop`, start it first.
+        if !self.running {
+            let _ = pmu_counter_start(self.mask(), CounterStartFlags::empty(), 0);
+        }
+        let _ = pmu_counter_stop(self.mask(), CounterStopFlags::RESET);
+    }
+}
+
+/// PMU snapshot shared memory of a hart.
+///
+/// Counter values and overflow bits are relative to the `counter_idx_base` of the
+/// last `pmu_counter_stop` call with the `TAKE_SNAPSHOT` flag.
+#[repr(C, align(4096))]
+pub struct PmuSnapshot {
+    counter_overflow_bitmap: UnsafeCell<u64>,
+    counter_values: UnsafeCell<[u64; 64]>,
+    _reserved: [u64; 447],
+}
+
+const _: () = assert!(size_of::<PmuSnapshot>() == SIZE);
+
+// Only written by the SBI implementation on the hart the snapshot is registered on.
+unsafe impl Sync for PmuSnapshot {}
+
+impl PmuSnapshot {
+    /// Create a zeroed snapshot shared memory.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            counter_overflow_bitmap: UnsafeCell::new(0),
+            counter_values: UnsafeCell::new([0; 64]),
+            _reserved: [0; 447],
+        }
+    }
+
+    /// Returns the bitmap of overflown counters, valid only if the `Sscofpmf` extension is available.
+    #[inline]
+    pub fn overflow_bitmap(&self) -> u64 {
+        unsafe { self.counter_overflow_bitmap.get().read_volatile() }
+    }
+
+    /// Returns the value of counter `offset` relative to `counter_idx_base`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is not less than 64.
+    #[inline]
+    pub fn counter_value(&self, offset: usize) -> u64 {
+        assert!(offset < 64);
+        unsafe {
+            self.counter_values
+                .get()
+                .cast::<u64>()
+                .add(offset)
+                .read_volatile()
+        }
+    }
+}
+
+impl Default for PmuSnapshot {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Set `snapshot` as the PMU snapshot shared memory of the calling hart.
+///
+/// The return value is the same as [`pmu_snapshot_set_shmem`].
+///
+/// # Safety
+///
+/// The `snapshot` MUST be mapped at the same virtual and physical address.
+#[inline]
+pub unsafe fn pmu_snapshot_register(snapshot: &'static PmuSnapshot) -> SbiRet {
+    let phys_addr = snapshot as *const PmuSnapshot as usize;
+    pmu_snapshot_set_shmem(SharedPtr::new(phys_addr, 0), 0)
+}
+
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+fn read_counter_csr(csr: u16) -> Option<u64> {
+    macro_rules! read_csr {
+        ($csr:expr, $($num:literal)*) => {
+            match $csr {
+                $($num => {
+                    let value: usize;
+                    unsafe { core::arch::asm!(concat!("csrr {}, ", stringify!($num)), out(reg) value) };
+                    Some(value)
+                })*
+                _ => None,
+            }
+        };
+    }
+    macro_rules! read_counter {
+        ($csr:expr) => {
+            read_csr!($csr,
+                0xc00 0xc01 0xc02 0xc03 0xc04 0xc05 0xc06 0xc07
+                0xc08 0xc09 0xc0a 0xc0b 0xc0c 0xc0d 0xc0e 0xc0f
+                0xc10 0xc11 0xc12 0xc13 0xc14 0xc15 0xc16 0xc17
+                0xc18 0xc19 0xc1a 0xc1b 0xc1c 0xc1d 0xc1e 0xc1f
+            )
+        };
+    }
+    #[cfg(target_arch = "riscv32")]
+    macro_rules! read_counter_hi {
+        ($csr:expr) => {
+            read_csr!($csr,
+                0xc80 0xc81 0xc82 0xc83 0xc84 0xc85 0xc86 0xc87
+                0xc88 0xc89 0xc8a 0xc8b 0xc8c 0xc8d 0xc8e 0xc8f
+                0xc90 0xc91 0xc92 0xc93 0xc94 0xc95 0xc96 0xc97
+                0xc98 0xc99 0xc9a 0xc9b 0xc9c 0xc9d 0xc9e 0xc9f
+            )
+        };
+    }
+    match () {
+        #[cfg(target_arch = "riscv32")]
+        () => loop {
+            // re-read if the low half overflows between reading two halves.
+            let hi = read_counter_hi!(csr + 0x80)?;
+            let lo = read_counter!(csr)?;
+            if read_counter_hi!(csr + 0x80)? == hi {
+                break Some((hi as u64) << 32 | lo as u64);
+            }
+        },
+        #[cfg(target_arch = "riscv64")]
+        () => read_counter!(csr).map(|value| value as u64),
+    }
+}
+
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+fn read_counter_csr(_csr: u16) -> Option<u64> {
+    unimplemented!("unsupported architecture")
+}
@@ -0,0 +1,866 @@
+//! Message proxy client and RPMI service groups over the Message Proxy Extension.
+//!
+//! [`MpxyClient`] owns the MPXY shared memory of the calling hart. It enumerates message channels,
+//! reads channel attributes and sends messages. On top of it, [`RpmiSystemReset`], [`RpmiClock`]
+//! and [`RpmiCppc`] encode requests and decode responses of RISC-V Platform Management Interface
+//! (RPMI) service groups.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use sbi_rt::rpmi::{MpxyClient, RpmiClock, service_group};
+//!
+//! static mut CLIENT: MpxyClient<1> = MpxyClient::new();
+//!
+//! let client = unsafe { &mut *(&raw mut CLIENT) };
+//! // Safety: statics in this kernel are mapped at their physical addresses.
+//! unsafe { client.register() }.expect("set MPXY shared memory");
+//! if let Some(channel) = client.find_rpmi_channel(service_group::CLOCK).unwrap() {
+//!     let clock = RpmiClock::new(channel);
+//!     let rate = clock.rate(client, 0).expect("read clock rate");
+//!     # let _ = rate;
+//! }
+//! ```
+
+use crate::{
+    mpxy_get_channel_ids, mpxy_get_shmem_size, mpxy_read_attributes,
+    mpxy_send_message_with_response, mpxy_send_message_without_response, mpxy_set_shmem,
+    mpxy_write_attributes,
+};
+use core::cell::UnsafeCell;
+use sbi_spec::{
+    binary::{Error, SharedPtr},
+    mpxy::{attr_id, protocol_id},
+};
+
+const PAGE_SIZE: usize = 4096;
+// Channel IDs buffered at a time while searching for an RPMI channel.
+const CHANNEL_BATCH: usize = 16;
+
+/// Message proxy client owning `PAGES` pages of MPXY shared memory.
+///
+/// The shared memory MUST be at least as large as [`mpxy_get_shmem_size`] returns,
+/// which is checked on [`register`](Self::register).
+#[repr(C, align(4096))]
+pub struct MpxyClient<const PAGES: usize> {
+    shmem: UnsafeCell<[[u8; PAGE_SIZE]; PAGES]>,
+}
+
+impl<const PAGES: usize> MpxyClient<PAGES> {
+    /// Create a client with zeroed shared memory.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            shmem: UnsafeCell::new([[0; PAGE_SIZE]; PAGES]),
+        }
+    }
+
+    /// Set the shared memory of this client as the MPXY shared memory of the calling hart.
+    ///
+    /// Returns `Error::InvalidParam` if the shared memory is smaller than the size
+    /// required by the SBI implementation.
+    ///
+    /// # Safety
+    ///
+    /// This structure MUST be mapped at the same virtual and physical address,
+    /// and MUST NOT be moved or dropped until another shared memory is set on the calling hart.
+    #[inline]
+    pub unsafe fn register(&mut self) -> Result<(), Error> {
+        if mpxy_get_shmem_size() > PAGES * PAGE_SIZE {
+            return Err(Error::InvalidParam);
+        }
+        let phys_addr = self as *mut Self as usize;
+        mpxy_set_shmem(SharedPtr::new(phys_addr, 0), 0)
+            .into_result()
+            .map(drop)
+    }
+
+    /// Disable MPXY shared memory on the calling hart.
+    #[inline]
+    pub fn unregister() -> Result<(), Error> {
+        mpxy_set_shmem(SharedPtr::new(usize::MAX, usize::MAX), 0)
+            .into_result()
+            .map(drop)
+    }
+
+    /// Capacity of the shared memory in 32-bit words.
+    const WORDS: usize = PAGES * PAGE_SIZE / 4;
+
+    // Returns `Error::InvalidParam` if `len` words don't fit in the shared memory.
+    #[inline]
+    fn check_len(len: usize) -> Result<(), Error> {
+        if len > Self::WORDS {
+            Err(Error::InvalidParam)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn read_u32(&self, index: usize) -> u32 {
+        assert!(
+            index < Self::WORDS,
+            "MPXY shared memory index out of bounds"
+        );
+        let ptr = self.shmem.get().cast::<u32>();
+        u32::from_le(unsafe { ptr.add(index).read_volatile() })
+    }
+
+    #[inline]
+    fn write_u32(&mut self, index: usize, value: u32) {
+        assert!(
+            index < Self::WORDS,
+            "MPXY shared memory index out of bounds"
+        );
+        let ptr = self.shmem.get().cast::<u32>();
+        unsafe { ptr.add(index).write_volatile(value.to_le()) };
+    }
+
+    /// Returns the message channel ID at `index` of all channels, or `None` past the last channel.
+    ///
+    /// Each call fetches channel IDs from the SBI implementation;
+    /// use [`channel_ids`](Self::channel_ids) to iterate over all channels.
+    #[inline]
+    pub fn channel_id(&mut self, index: u32) -> Result<Option<u32>, Error> {
+        let returned = self.get_channel_ids(index)?;
+        Ok((returned > 0).then(|| self.read_u32(2)))
+    }
+
+    /// Iterate over IDs of all message channels accessible to the supervisor software.
+    ///
+    /// Channel IDs are fetched into the shared memory page by page.
+    #[inline]
+    pub fn channel_ids(&mut self) -> ChannelIds<'_, PAGES> {
+        self.channel_ids_from(0)
+    }
+
+    #[inline]
+    fn channel_ids_from(&mut self, start_index: u32) -> ChannelIds<'_, PAGES> {
+        ChannelIds {
+            client: self,
+            next_index: start_index,
+            cursor: 0,
+            returned: 0,
+            remaining: u32::MAX,
+        }
+    }
+
+    // Returns number of channel IDs returned in shared memory.
+    #[inline]
+    fn get_channel_ids(&mut self, start_index: u32) -> Result<u32, Error> {
+        mpxy_get_channel_ids(start_index).into_result()?;
+        Ok(self.read_u32(1))
+    }
+
+    /// Read `values.len()` consecutive attributes from `base_attribute_id` of channel `channel_id`.
+    ///
+    /// Returns `Error::InvalidParam` without calling the SBI implementation
+    /// if `values` doesn't fit in the shared memory.
+    pub fn read_attributes(
+        &mut self,
+        channel_id: u32,
+        base_attribute_id: u32,
+        values: &mut [u32],
+    ) -> Result<(), Error> {
+        Self::check_len(values.len())?;
+        mpxy_read_attributes(channel_id, base_attribute_id, values.len() as u32).into_result()?;
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = self.read_u32(i);
+        }
+        Ok(())
+    }
+
+    /// Write `values` into consecutive attributes from `base_attribute_id` of channel `channel_id`.
+    ///
+    /// Returns `Error::InvalidParam` without calling the SBI implementation
+    /// if `values` doesn't fit in the shared memory.
+    pub fn write_attributes(
+        &mut self,
+        channel_id: u32,
+        base_attribute_id: u32,
+        values: &[u32],
+    ) -> Result<(), Error> {
+        Self::check_len(values.len())?;
+        for (i, &value) in values.iter().enumerate() {
+            self.write_u32(i, value);
+        }
+        mpxy_write_attributes(channel_id, base_attribute_id, values.len() as u32)
+            .into_result()
+            .map(drop)
+    }
+
+    /// Read standard attributes of channel `channel_id`.
+    #[inline]
+    pub fn channel_attributes(&mut self, channel_id: u32) -> Result<ChannelAttributes, Error> {
+        let mut values = [0; 6];
+        self.read_attributes(channel_id, attr_id::MSG_PROT_ID, &mut values)?;
+        let [
+            protocol_id,
+            protocol_version,
+            max_message_len,
+            send_timeout_us,
+            completion_timeout_us,
+            capability,
+        ] = values;
+        Ok(ChannelAttributes {
+            protocol_id,
+            protocol_version,
+            max_message_len,
+            send_timeout_us,
+            completion_timeout_us,
+            capability,
+        })
+    }
+
+    /// Send `request` words as message `message_id` on channel `channel_id` and wait for response.
+    ///
+    /// Returns the response length in bytes; response words are read by [`response`](Self::response).
+    /// Returns `Error::InvalidParam` without calling the SBI implementation
+    /// if `request` doesn't fit in the shared memory.
+    pub fn send_with_response(
+        &mut self,
+        channel_id: u32,
+        message_id: u32,
+        request: &[u32],
+    ) -> Result<usize, Error> {
+        Self::check_len(request.len())?;
+        for (i, &word) in request.iter().enumerate() {
+            self.write_u32(i, word);
+        }
+        mpxy_send_message_with_response(channel_id, message_id, request.len() * 4).into_result()
+    }
+
+    /// Send `request` words as message `message_id` on channel `channel_id` without waiting for response.
+    ///
+    /// Returns `Error::InvalidParam` without calling the SBI implementation
+    /// if `request` doesn't fit in the shared memory.
+    pub fn send_without_response(
+        &mut self,
+        channel_id: u32,
+        message_id: u32,
+        request: &[u32],
+    ) -> Result<(), Error> {
+        Self::check_len(request.len())?;
+        for (i, &word) in request.iter().enumerate() {
+            self.write_u32(i, word);
+        }
+        mpxy_send_message_without_response(channel_id, message_id, request.len() * 4)
+            .into_result()
+            .map(drop)
+    }
+
+    /// Read word `index` of the last response in the shared memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is beyond the shared memory.
+    #[inline]
+    pub fn response(&self, index: usize) -> u32 {
+        self.read_u32(index)
+    }
+
+    /// Find an RPMI channel of `service_group`.
+    pub fn find_rpmi_channel(&mut self, service_group: u16) -> Result<Option<u32>, Error> {
+        let mut start_index = 0;
+        loop {
+            // Attribute reads overwrite the shared memory, so take a batch of channel IDs first.
+            let mut ids = [0; CHANNEL_BATCH];
+            let mut len = 0;
+            for id in self.channel_ids_from(start_index).take(CHANNEL_BATCH) {
+                ids[len] = id?;
+                len += 1;
+            }
+            for &channel_id in &ids[..len] {
+                let mut protocol = [0];
+                self.read_attributes(channel_id, attr_id::MSG_PROT_ID, &mut protocol)?;
+                if protocol[0] == protocol_id::RPMI {
+                    let mut group = [0];
+                    self.read_attributes(channel_id, RPMI_ATTR_SERVICEGROUP_ID, &mut group)?;
+                    if group[0] == service_group as u32 {
+                        return Ok(Some(channel_id));
+                    }
+                }
+            }
+            if len < CHANNEL_BATCH {
+                return Ok(None);
+            }
+            start_index += CHANNEL_BATCH as u32;
+        }
+    }
+
+    /// Send an RPMI request and read the response after its status word into `response`.
+    ///
+    /// Returns `RpmiError::Status` if the RPMI status is not success, or
+    /// `RpmiError::InvalidResponse` if the response is shorter than expected.
+    /// Returns `Error::InvalidParam` without calling the SBI implementation
+    /// if `request` or `response` with its status word doesn't fit in the shared memory.
+    pub fn rpmi_request(
+        &mut self,
+        channel_id: u32,
+        service_id: u8,
+        request: &[u32],
+        response: &mut [u32],
+    ) -> Result<(), RpmiError> {
+        Self::check_len(response.len() + 1)?;
+        let len = self.send_with_response(channel_id, service_id as u32, request)?;
+        if len < 4 {
+            return Err(RpmiError::InvalidResponse);
+        }
+        let status = self.read_u32(0) as i32;
+        if status != 0 {
+            return Err(RpmiError::Status(status));
+        }
+        if len < (response.len() + 1) * 4 {
+            return Err(RpmiError::InvalidResponse);
+        }
+        for (i, word) in response.iter_mut().enumerate() {
+            *word = self.read_u32(i + 1);
+        }
+        Ok(())
+    }
+}
+
+impl<const PAGES: usize> Default for MpxyClient<PAGES> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Iterator over message channel IDs, created by [`MpxyClient::channel_ids`].
+pub struct ChannelIds<'a, const PAGES: usize> {
+    client: &'a mut MpxyClient<PAGES>,
+    next_index: u32,
+    cursor: u32,
+    returned: u32,
+    remaining: u32,
+}
+
+impl<const PAGES: usize> Iterator for ChannelIds<'_, PAGES> {
+    type Item = Result<u32, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cursor == self.returned {
+            if self.remaining == 0 {
+                return None;
+            }
+            if let Err(e) = self.client.get_channel_ids(self.next_index) {
+                self.remaining = 0;
+                self.returned = 0;
+                self.cursor = 0;
+                return Some(Err(e));
+            }
+            self.remaining = self.client.read_u32(0);
+            self.returned = self.client.read_u32(1);
+            self.cursor = 0;
+            if self.returned == 0 {
+                self.remaining = 0;
+                return None;
+            }
+        }
+        let id = self.client.read_u32(2 + self.cursor as usize);
+        self.cursor += 1;
+        self.next_index += 1;
+        Some(Ok(id))
+    }
+}
+
+/// Standard attributes of a message channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChannelAttributes {
+    /// Message protocol ID, e.g. `protocol_id::RPMI`.
+    pub protocol_id: u32,
+    /// Message protocol version.
+    pub protocol_version: u32,
+    /// Maximum message data length in bytes.
+    pub max_message_len: u32,
+    /// Timeout for sending a message in microseconds.
+    pub send_timeout_us: u32,
+    /// Timeout for a message to complete with its response in microseconds.
+    pub completion_timeout_us: u32,
+    /// Channel capability bits, see `sbi_spec::mpxy::channel_capability`.
+    pub capability: u32,
+}
+
+/// RPMI channel attribute of service group ID.
+pub const RPMI_ATTR_SERVICEGROUP_ID: u32 = attr_id::MSG_PROT_ATTR_START;
+/// RPMI channel attribute of service group version.
+pub const RPMI_ATTR_SERVICEGROUP_VERSION: u32 = attr_id::MSG_PROT_ATTR_START + 1;
+
+/// RPMI service group IDs.
+pub mod service_group {
+    /// Base service group.
+    pub const BASE: u16 = 0x0001;
+    /// System MSI service group.
+    pub const SYSTEM_MSI: u16 = 0x0002;
+    /// System reset service group.
+    pub const SYSTEM_RESET: u16 = 0x0003;
+    /// System suspend service group.
+    pub const SYSTEM_SUSPEND: u16 = 0x0004;
+    /// Hart state management service group.
+    pub const HART_STATE_MANAGEMENT: u16 = 0x0005;
+    /// CPPC service group.
+    pub const CPPC: u16 = 0x0006;
+    /// Voltage service group.
+    pub const VOLTAGE: u16 = 0x0007;
+    /// Clock service group.
+    pub const CLOCK: u16 = 0x0008;
+    /// Device power service group.
+    pub const DEVICE_POWER: u16 = 0x0009;
+    /// Performance service group.
+    pub const PERFORMANCE: u16 = 0x000A;
+}
+
+/// Error of an RPMI request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RpmiError {
+    /// The SBI call failed.
+    Sbi(Error),
+    /// The RPMI service returned a non-success status, e.g. -2 for not supported.
+    Status(i32),
+    /// The response is shorter than defined by the RPMI service.
+    InvalidResponse,
+}
+
+impl From<Error> for RpmiError {
+    #[inline]
+    fn from(value: Error) -> Self {
+        Self::Sbi(value)
+    }
+}
+
+/// Client of the RPMI system reset service group on one channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RpmiSystemReset {
+    channel_id: u32,
+}
+
+impl RpmiSystemReset {
+    /// Shutdown reset type.
+    pub const SHUTDOWN: u32 = 0;
+    /// Cold reboot reset type.
+    pub const COLD_REBOOT: u32 = 1;
+    /// Warm reboot reset type.
+    pub const WARM_REBOOT: u32 = 2;
+
+    const GET_ATTRIBUTES: u8 = 0x02;
+    const RESET: u8 = 0x03;
+
+    /// Create a client on RPMI channel `channel_id`.
+    #[inline]
+    pub const fn new(channel_id: u32) -> Self {
+        Self { channel_id }
+    }
+
+    /// Returns whether `reset_type` is supported.
+    #[inline]
+    pub fn is_supported<const P: usize>(
+        &self,
+        client: &mut MpxyClient<P>,
+        reset_type: u32,
+    ) -> Result<bool, RpmiError> {
+        let mut response = [0];
+        client.rpmi_request(
+            self.channel_id,
+            Self::GET_ATTRIBUTES,
+            &[reset_type],
+            &mut response,
+        )?;
+        Ok(response[0] & 1 != 0)
+    }
+
+    /// Request a system reset of `reset_type`.
+    ///
+    /// This function returns only if the request could not be sent.
+    #[inline]
+    pub fn reset<const P: usize>(
+        &self,
+        client: &mut MpxyClient<P>,
+        reset_type: u32,
+    ) -> Result<(), RpmiError> {
+        client.send_without_response(self.channel_id, Self::RESET as u32, &[reset_type])?;
+        Ok(())
+    }
+}
+
+/// Attributes of an RPMI clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ClockAttributes {
+    /// Clock flags; bits 1:0 give the clock rate format.
+    pub flags: u32,
+    /// Number of rates supported by the clock.
+    pub num_rates: u32,
+    /// Transition latency in microseconds.
+    pub transition_latency_us: u32,
+    /// Clock name as a NUL padded string.
+    pub name: [u8; 16],
+}
+
+/// Client of the RPMI clock service group on one channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RpmiClock {
+    channel_id: u32,
+}
+
+impl RpmiClock {
+    /// Round the requested rate down to a supported rate.
+    pub const ROUND_DOWN: u32 = 0;
+    /// Round the requested rate up to a supported rate.
+    pub const ROUND_UP: u32 = 1;
+    /// Round the requested rate to the closest supported rate.
+    pub const ROUND_AUTO: u32 = 2;
+
+    const GET_NUM_CLOCKS: u8 = 0x02;
+    const GET_ATTRIBUTES: u8 = 0x03;
+    const SET_CONFIG: u8 = 0x05;
+    const GET_CONFIG: u8 = 0x06;
+    const SET_RATE: u8 = 0x07;
+    const GET_RATE: u8 = 0x08;
+
+    /// Create a client on RPMI channel `channel_id`.
+    #[inline]
+    pub const fn new(channel_id: u32) -> Self {
+        Self { channel_id }
+    }
+
+    /// Returns the number of clocks.
+    #[inline]
+    pub fn num_clocks<const P: usize>(&self, client: &mut MpxyClient<P>) -> Result<u32, RpmiError> {
+        let mut response = [0];
+        client.rpmi_request(self.channel_id, Self::GET_NUM_CLOCKS, &[], &mut response)?;
+        Ok(response[0])
+    }
+
+    /// Returns attributes of clock `clock_id`.
+    pub fn attributes<const P: usize>(
+        &self,
+        client: &mut MpxyClient<P>,
+        clock_id: u32,
+    ) -> Result<ClockAttributes, RpmiError> {
+        let mut response = [0; 7];
+        client.rpmi_request(
+            self.channel_id,
+            Self::GET_ATTRIBUTES,
+            &[clock_id],
+            &mut response,
+        )?;
+        let mut name = [0; 16];
+        for (chunk, word) in name.chunks_exact_mut(4).zip(&response[3..]) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        Ok(ClockAttributes {
+            flags: response[0],
+            num_rates: response[1],
+            transition_latency_us: response[2],
+            name,
+        })
+    }
+
+    /// Enable or disable clock `clock_id`.
+    #[inline]
+    pub fn set_enabled<const P: usize>(
+        &self,
+        client: &mut MpxyClient<P>,
+        clock_id: u32,
+        enabled: bool,
+    ) -> Result<(), RpmiError> {
+        let request = [clock_id, enabled as u32];
+        client.rpmi_request(self.channel_id, Self::SET_CONFIG, &request, &mut [])
+    }
+
+    /// Returns whether clock `clock_id` is enabled.
+    #[inline]
+    pub fn is_enabled<const P: usize>(
+        &self,
+        client: &mut MpxyClient<P>,
+        clock_id: u32,
+    ) -> Result<bool, RpmiError> {
+        let mut response = [0];
+        client.rpmi_request(
+            self.channel_id,
+            Self::GET_CONFIG,
+            &[clock_id],
+            &mut response,
+        )?;
+        Ok(response[0] & 1 != 0)
+    }
+
+    /// Set the rate of clock `clock_id` in Hz, rounded as `rounding` to a supported rate.
+    #[inline]
+    pub fn set_rate<const P: usize>(
+        &self,
+        client: &mut MpxyClient<P>,
+        clock_id: u32,
+        rounding: u32,
+        rate: u64,
+    ) -> Result<(), RpmiError> {
+        let request = [clock_id, rounding, rate as u32, (rate >> 32) as u32];
+        client.rpmi_request(self.channel_id, Self::SET_RATE, &request, &mut [])
+    }
+
+    /// Returns the rate of clock `clock_id` in Hz.
+    #[inline]
+    pub fn rate<const P: usize>(
+        &self,
+        client: &mut MpxyClient<P>,
+        clock_id: u32,
+    ) -> Result<u64, RpmiError> {
+        let mut response = [0; 2];
+        client.rpmi_request(self.channel_id, Self::GET_RATE, &[clock_id], &mut response)?;
+        Ok(response[0] as u64 | (response[1] as u64) << 32)
+    }
+}
+
+/// Client of the RPMI CPPC service group on one channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RpmiCppc {
+    channel_id: u32,
+}
+
+impl RpmiCppc {
+    const PROBE_REG: u8 = 0x02;
+    const READ_REG: u8 = 0x03;
+    const WRITE_REG: u8 = 0x04;
+
+    /// Create a client on RPMI channel `channel_id`.
+    #[inline]
+    pub const fn new(channel_id: u32) -> Self {
+        Self { channel_id }
+    }
+
+    /// Returns the width in bits of CPPC register `reg_id` of hart `hart_id`, or 0 if not implemented.
+    #[inline]
+    pub fn probe_reg<const P: usize>(
+        &self,
+        client: &mut MpxyClient<P>,
+        hart_id: u32,
+        reg_id: u32,
+    ) -> Result<u32, RpmiError> {
+        let mut response = [0];
+        client.rpmi_request(
+            self.channel_id,
+            Self::PROBE_REG,
+            &[hart_id, reg_id],
+            &mut response,
+        )?;
+        Ok(response[0])
+    }
+
+    /// Read CPPC register `reg_id` of hart `hart_id`.
+    #[inline]
+    pub fn read_reg<const P: usize>(
+        &self,
+        client: &mut MpxyClient<P>,
+        hart_id: u32,
+        reg_id: u32,
+    ) -> Result<u64, RpmiError> {
+        let mut response = [0; 2];
+        client.rpmi_request(
+            self.channel_id,
+            Self::READ_REG,
+            &[hart_id, reg_id],
+            &mut response,
+        )?;
+        Ok(response[0] as u64 | (response[1] as u64) << 32)
+    }
+
+    /// Write `value` into CPPC register `reg_id` of hart `hart_id`.
+    #[inline]
+    pub fn write_reg<const P: usize>(
+        &self,
+        client: &mut MpxyClient<P>,
+        hart_id: u32,
+        reg_id: u32,
+        value: u64,
+    ) -> Result<(), RpmiError> {
+        let request = [hart_id, reg_id, value as u32, (value >> 32) as u32];
+        client.rpmi_request(self.channel_id, Self::WRITE_REG, &request, &mut [])
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "mock",
+    not(any(target_arch = "riscv32", target_arch = "riscv64"))
+))]
+mod tests {
+    use super::*;
+    use crate::mock::with_handler;
+    use core::cell::Cell;
+    use sbi_spec::{binary::SbiRet, mpxy::*};
+
+    // Channels of the fake MPXY implementation, with IDs from `100`.
+    const CHANNELS: u32 = 20;
+    // Channel IDs returned by one `GET_CHANNEL_IDS` call, less than `CHANNEL_BATCH`.
+    const IDS_PER_CALL: u32 = 8;
+    // The only RPMI clock channel; other odd channels are RPMI base channels.
+    const CLOCK_CHANNEL: u32 = 117;
+
+    fn write(shmem: usize, index: usize, value: u32) {
+        unsafe { (shmem as *mut u32).add(index).write_volatile(value.to_le()) }
+    }
+
+    // Fake MPXY implementation answering every message with `response` words.
+    // Attributes other than protocol and service group IDs read as their own attribute IDs.
+    fn fake_mpxy<'a>(
+        shmem: &'a Cell<usize>,
+        response: &'a [u32],
+    ) -> impl Fn(usize, usize, [usize; 6]) -> SbiRet + 'a {
+        move |extension, function, param| {
+            assert_eq!(extension, EID_MPXY);
+            let base = shmem.get();
+            match function {
+                GET_SHMEM_SIZE => SbiRet::success(PAGE_SIZE),
+                SET_SHMEM => {
+                    shmem.set(param[0]);
+                    SbiRet::success(0)
+                }
+                GET_CHANNEL_IDS => {
+                    let start = param[0] as u32;
+                    if start > CHANNELS {
+                        return SbiRet::invalid_param();
+                    }
+                    let returned = (CHANNELS - start).min(IDS_PER_CALL);
+                    write(base, 0, CHANNELS - start - returned);
+                    write(base, 1, returned);
+                    for i in 0..returned {
+                        write(base, 2 + i as usize, 100 + start + i);
+                    }
+                    SbiRet::success(0)
+                }
+                READ_ATTRIBUTE => {
+                    let (channel, base_id, count) = (param[0] as u32, param[1] as u32, param[2]);
+                    assert!(count <= PAGE_SIZE / 4, "attributes beyond shared memory");
+                    for i in 0..count {
+                        let id = base_id + i as u32;
+                        let value = if id == attr_id::MSG_PROT_ID && channel % 2 == 1 {
+                            protocol_id::RPMI
+                        } else if id == attr_id::MSG_PROT_ID {
+                            protocol_id::VENDOR_START
+                        } else if id == RPMI_ATTR_SERVICEGROUP_ID && channel == CLOCK_CHANNEL {
+                            service_group::CLOCK as u32
+                        } else if id == RPMI_ATTR_SERVICEGROUP_ID {
+                            service_group::BASE as u32
+                        } else {
+                            id
+                        };
+                        write(base, i, value);
+                    }
+                    SbiRet::success(0)
+                }
+                SEND_MESSAGE_WITH_RESPONSE => {
+                    assert!(param[2] <= PAGE_SIZE, "message beyond shared memory");
+                    for (i, &word) in response.iter().enumerate() {
+                        write(base, i, word);
+                    }
+                    SbiRet::success(response.len() * 4)
+                }
+                _ => SbiRet::not_supported(),
+            }
+        }
+    }
+
+    fn with_client<R>(response: &[u32], f: impl FnOnce(&mut MpxyClient<1>) -> R) -> R {
+        let shmem = Cell::new(0);
+        let mut client = MpxyClient::<1>::new();
+        with_handler(&fake_mpxy(&shmem, response), || {
+            unsafe { client.register() }.unwrap();
+            f(&mut client)
+        })
+    }
+
+    #[test]
+    fn channel_ids_paging() {
+        with_client(&[], |client| {
+            let mut ids = client.channel_ids().map(Result::unwrap);
+            assert!(ids.by_ref().eq(100..100 + CHANNELS));
+            assert_eq!(ids.next(), None);
+            assert_eq!(client.channel_id(17), Ok(Some(117)));
+            assert_eq!(client.channel_id(CHANNELS), Ok(None));
+            assert_eq!(client.channel_id(CHANNELS + 1), Err(Error::InvalidParam));
+        });
+    }
+
+    #[test]
+    fn find_rpmi_channel_across_batches() {
+        with_client(&[], |client| {
+            assert_eq!(
+                client.find_rpmi_channel(service_group::CLOCK),
+                Ok(Some(CLOCK_CHANNEL))
+            );
+            assert_eq!(client.find_rpmi_channel(service_group::BASE), Ok(Some(101)));
+            assert_eq!(
+                client.find_rpmi_channel(service_group::SYSTEM_RESET),
+                Ok(None)
+            );
+        });
+    }
+
+    #[test]
+    fn attribute_layout() {
+        with_client(&[], |client| {
+            assert_eq!(
+                client.channel_attributes(101),
+                Ok(ChannelAttributes {
+                    protocol_id: protocol_id::RPMI,
+                    protocol_version: attr_id::MSG_PROT_VERSION,
+                    max_message_len: attr_id::MSG_MAX_LEN,
+                    send_timeout_us: attr_id::MSG_SEND_TIMEOUT,
+                    completion_timeout_us: attr_id::MSG_COMPLETION_TIMEOUT,
+                    capability: attr_id::CHANNEL_CAPABILITY,
+                })
+            );
+            let mut values = [0; 3];
+            client
+                .read_attributes(100, attr_id::MSI_ADDR_LO, &mut values)
+                .unwrap();
+            assert_eq!(
+                values,
+                [
+                    attr_id::MSI_ADDR_LO,
+                    attr_id::MSI_ADDR_HI,
+                    attr_id::MSI_DATA
+                ]
+            );
+            let mut values = [0; PAGE_SIZE / 4 + 1];
+            assert_eq!(
+                client.read_attributes(100, 0, &mut values),
+                Err(Error::InvalidParam)
+            );
+            assert_eq!(
+                client.write_attributes(100, 0, &values),
+                Err(Error::InvalidParam)
+            );
+        });
+    }
+
+    #[test]
+    fn rpmi_request_response() {
+        let clock = RpmiClock::new(CLOCK_CHANNEL);
+        with_client(&[0, 4], |client| {
+            assert_eq!(clock.num_clocks(client), Ok(4));
+            let mut response = [0; PAGE_SIZE / 4];
+            assert_eq!(
+                client.rpmi_request(CLOCK_CHANNEL, 0x02, &[], &mut response),
+                Err(RpmiError::Sbi(Error::InvalidParam))
+            );
+            let request = [0; PAGE_SIZE / 4 + 1];
+            assert_eq!(
+                client.rpmi_request(CLOCK_CHANNEL, 0x02, &request, &mut []),
+                Err(RpmiError::Sbi(Error::InvalidParam))
+            );
+        });
+        // RPMI_ERR_NOT_SUPPORTED
+        with_client(&[-2i32 as u32], |client| {
+            assert_eq!(clock.num_clocks(client), Err(RpmiError::Status(-2)));
+        });
+        // Status word only, missing the number of clocks
+        with_client(&[0], |client| {
+            assert_eq!(clock.num_clocks(client), Err(RpmiError::InvalidResponse));
+        });
+        // Empty response without status word
+        with_client(&[], |client| {
+            assert_eq!(clock.num_clocks(client), Err(RpmiError::InvalidResponse));
+        });
+    }
+}
@@ -1,9 +1,10 @@
 //! Chapter 17. Supervisor Software Events Extension (EID #0x535345 "SSE").
 
 use crate::binary::{sbi_call_0, sbi_call_1, sbi_call_2, sbi_call_3, sbi_call_5};
+use core::mem::{offset_of, size_of};
 use sbi_spec::{
     binary::{SbiRet, SharedPtr},
-    sse::*,
+    sse::{attr_id::INTERRUPTED_SEPC, flags::InterruptedFlags, *},
 };
 
 /// Read a range of event attribute values from a software event.
@@ -281,3 +282,237 @@ pub fn sse_hart_unmask() -> SbiRet {
 pub fn sse_hart_mask() -> SbiRet {
     sbi_call_0(EID_SSE, HART_MASK)
 }
+
+/// Interrupted state of a software event handled by [`sse_register_handler`].
+///
+/// The handler may modify this context; general purpose registers, `sepc` and flags
+/// are restored into the interrupted supervisor state once the handler returns.
+#[repr(C)]
+#[derive(Debug)]
+pub struct SseContext {
+    /// General purpose registers `x0` to `x31` of the interrupted context, `x0` is unused.
+    ///
+    /// Registers `a6` and `a7` are read from `INTERRUPTED_A6` and `INTERRUPTED_A7` event attributes.
+    pub regs: [usize; 32],
+    /// Interrupted `sepc` CSR value, where the interrupted context resumes.
+    pub sepc: usize,
+    /// Interrupted `sstatus` and `hstatus` CSR bits.
+    pub flags: InterruptedFlags,
+    /// ID of the hart handling the software event.
+    pub hart_id: usize,
+    /// ID of the software event being handled.
+    pub event_id: u32,
+}
+
+/// Stack for a software event handler registered by [`sse_register_handler`].
+///
+/// It contains `N` bytes of stack space, and the entry information of the event handler.
+#[repr(C, align(16))]
+pub struct SseStack<const N: usize> {
+    entry: SseEntry,
+    stack: [u8; N],
+}
+
+impl<const N: usize> SseStack<N> {
+    /// Create an empty software event handler stack.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            entry: SseEntry {
+                stack_top: 0,
+                hart_id: 0,
+                handler: |_| {},
+                event_id: 0,
+            },
+            stack: [0; N],
+        }
+    }
+}
+
+impl<const N: usize> Default for SseStack<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Passed to the trampoline through the `ENTRY_ARG` event attribute.
+#[repr(C)]
+struct SseEntry {
+    stack_top: usize,
+    hart_id: usize,
+    handler: fn(&mut SseContext),
+    event_id: u32,
+}
+
+const XLENB: usize = size_of::<usize>();
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+const CONTEXT_SIZE: usize = size_of::<SseContext>().next_multiple_of(16);
+
+// The trampoline relies on these offsets.
+const _: () = assert!(offset_of!(SseContext, regs) == 0);
+const _: () = assert!(offset_of!(SseEntry, stack_top) == 0);
+const _: () = assert!(offset_of!(SseEntry, hart_id) == XLENB);
+
+/// Register a Rust event handler for the software event.
+///
+/// This function installs an assembly trampoline as the `ENTRY_PC` of the software event.
+/// On each event, the trampoline saves interrupted general purpose registers onto `stack`,
+/// reads interrupted `sepc`, flags, `a6` and `a7` using `sse_read_attrs` and calls `handler`
+/// with the resulting [`SseContext`]. Once `handler` returns, modified interrupted state is written back
+/// using `sse_write_attrs`, registers are restored and the event is completed using `sse_complete`.
+/// If interrupted state cannot be read, `handler` is not called and the event is completed directly.
+///
+/// For local events, each hart registering the event should provide its own `stack`.
+///
+/// The return value is the same as [`sse_register`].
+///
+/// # Safety
+///
+/// - The `stack` MUST be mapped at the same virtual and physical address, as interrupted state
+///   is passed through shared memory on this stack.
+/// - The `handler` runs with interrupted `gp`, `tp`, floating point and vector registers,
+///   and MUST NOT rely on or modify them.
+#[doc(alias = "sbi_sse_register")]
+#[inline]
+pub unsafe fn sse_register_handler<const N: usize>(
+    event_id: u32,
+    stack: &'static mut SseStack<N>,
+    handler: fn(&mut SseContext),
+) -> SbiRet {
+    let stack_top = stack.stack.as_ptr_range().end as usize & !0xf;
+    stack.entry = SseEntry {
+        stack_top,
+        hart_id: 0,
+        handler,
+        event_id,
+    };
+    sse_register(
+        event_id,
+        sse_entry as usize,
+        &raw const stack.entry as usize,
+    )
+}
+
+#[cfg_attr(
+    not(any(target_arch = "riscv32", target_arch = "riscv64")),
+    allow(unused)
+)]
+extern "C" fn sse_handle(ctx: &mut SseContext, entry: &SseEntry) {
+    // INTERRUPTED_SEPC, INTERRUPTED_FLAGS, INTERRUPTED_A6 and INTERRUPTED_A7.
+    let mut attrs = [0usize; 4];
+    let output = SharedPtr::new(attrs.as_mut_ptr() as usize, 0);
+    if sse_read_attrs(entry.event_id, INTERRUPTED_SEPC, 4, output).is_err() {
+        return;
+    }
+    ctx.sepc = attrs[0];
+    ctx.flags = InterruptedFlags::from_bits_retain(attrs[1]);
+    ctx.regs[16] = attrs[2];
+    ctx.regs[17] = attrs[3];
+    ctx.hart_id = entry.hart_id;
+    ctx.event_id = entry.event_id;
+    (entry.handler)(ctx);
+    let modified = [ctx.sepc, ctx.flags.bits(), ctx.regs[16], ctx.regs[17]];
+    if modified != attrs {
+        attrs = modified;
+        let input = SharedPtr::new(attrs.as_mut_ptr() as usize, 0);
+        let _ = sse_write_attrs(entry.event_id, INTERRUPTED_SEPC, 4, input);
+    }
+}
+
+// On event entry, `a6` holds the hart ID and `a7` holds the `ENTRY_ARG` event attribute;
+// their interrupted values are saved by the SBI implementation as event attributes.
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+macro_rules! define_sse_entry {
+    ($store:literal, $load:literal) => {
+        #[unsafe(naked)]
+        unsafe extern "C" fn sse_entry() -> ! {
+            core::arch::naked_asm!(
+                concat!($store, " a6, {b}(a7)"),
+                "mv a6, sp",
+                concat!($load, " sp, 0(a7)"),
+                "addi sp, sp, -{size}",
+                concat!($store, " x1, 1*{b}(sp)"),
+                concat!($store, " a6, 2*{b}(sp)"),
+                concat!($store, " x3, 3*{b}(sp)"),
+                concat!($store, " x4, 4*{b}(sp)"),
+                concat!($store, " x5, 5*{b}(sp)"),
+                concat!($store, " x6, 6*{b}(sp)"),
+                concat!($store, " x7, 7*{b}(sp)"),
+                concat!($store, " x8, 8*{b}(sp)"),
+                concat!($store, " x9, 9*{b}(sp)"),
+                concat!($store, " x10, 10*{b}(sp)"),
+                concat!($store, " x11, 11*{b}(sp)"),
+                concat!($store, " x12, 12*{b}(sp)"),
+                concat!($store, " x13, 13*{b}(sp)"),
+                concat!($store, " x14, 14*{b}(sp)"),
+                concat!($store, " x15, 15*{b}(sp)"),
+                concat!($store, " x18, 18*{b}(sp)"),
+                concat!($store, " x19, 19*{b}(sp)"),
+                concat!($store, " x20, 20*{b}(sp)"),
+                concat!($store, " x21, 21*{b}(sp)"),
+                concat!($store, " x22, 22*{b}(sp)"),
+                concat!($store, " x23, 23*{b}(sp)"),
+                concat!($store, " x24, 24*{b}(sp)"),
+                concat!($store, " x25, 25*{b}(sp)"),
+                concat!($store, " x26, 26*{b}(sp)"),
+                concat!($store, " x27, 27*{b}(sp)"),
+                concat!($store, " x28, 28*{b}(sp)"),
+                concat!($store, " x29, 29*{b}(sp)"),
+                concat!($store, " x30, 30*{b}(sp)"),
+                concat!($store, " x31, 31*{b}(sp)"),
+                "mv a0, sp",
+                "mv a1, a7",
+                "call {handle}",
+                concat!($load, " x1, 1*{b}(sp)"),
+                concat!($load, " x3, 3*{b}(sp)"),
+                concat!($load, " x4, 4*{b}(sp)"),
+                concat!($load, " x5, 5*{b}(sp)"),
+                concat!($load, " x6, 6*{b}(sp)"),
+                concat!($load, " x7, 7*{b}(sp)"),
+                concat!($load, " x8, 8*{b}(sp)"),
+                concat!($load, " x9, 9*{b}(sp)"),
+                concat!($load, " x10, 10*{b}(sp)"),
+                concat!($load, " x11, 11*{b}(sp)"),
+                concat!($load, " x12, 12*{b}(sp)"),
+                concat!($load, " x13, 13*{b}(sp)"),
+                concat!($load, " x14, 14*{b}(sp)"),
+                concat!($load, " x15, 15*{b}(sp)"),
+                concat!($load, " x18, 18*{b}(sp)"),
+                concat!($load, " x19, 19*{b}(sp)"),
+                concat!($load, " x20, 20*{b}(sp)"),
+                concat!($load, " x21, 21*{b}(sp)"),
+                concat!($load, " x22, 22*{b}(sp)"),
+                concat!($load, " x23, 23*{b}(sp)"),
+                concat!($load, " x24, 24*{b}(sp)"),
+                concat!($load, " x25, 25*{b}(sp)"),
+                concat!($load, " x26, 26*{b}(sp)"),
+                concat!($load, " x27, 27*{b}(sp)"),
+                concat!($load, " x28, 28*{b}(sp)"),
+                concat!($load, " x29, 29*{b}(sp)"),
+                concat!($load, " x30, 30*{b}(sp)"),
+                concat!($load, " x31, 31*{b}(sp)"),
+                concat!($load, " sp, 2*{b}(sp)"),
+                "li a7, {eid}",
+                "li a6, {fid}",
+                "ecall",
+                "unimp",
+                b = const XLENB,
+                size = const CONTEXT_SIZE,
+                handle = sym sse_handle,
+                eid = const EID_SSE,
+                fid = const COMPLETE,
+            )
+        }
+    };
+}
+
+#[cfg(target_arch = "riscv64")]
+define_sse_entry!("sd", "ld");
+#[cfg(target_arch = "riscv32")]
+define_sse_entry!("sw", "lw");
+
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+unsafe extern "C" fn sse_entry() -> ! {
+    unimplemented!("unsupported architecture")
+}
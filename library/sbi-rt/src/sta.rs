@@ -2,6 +2,10 @@
 
 use crate::binary::sbi_call_3;
 
+use core::{
+    cell::UnsafeCell,
+    sync::atomic::{Ordering, fence},
+};
 use sbi_spec::{
     binary::{SbiRet, SharedPtr},
     sta::{EID_STA, SET_SHMEM},
@@ -66,3 +70,113 @@ pub fn sta_set_shmem(shmem: SharedPtr<[u8; 64]>, flags: usize) -> SbiRet {
         flags,
     )
 }
+
+/// Steal-time accounting shared memory of a virtual hart.
+///
+/// Each virtual hart registers its own structure using [`StealTime::register`],
+/// and then reads the steal-time reported by the SBI implementation.
+///
+/// # Examples
+///
+/// ```no_run
+/// use sbi_rt::StealTime;
+///
+/// static STEAL_TIME: StealTime = StealTime::new();
+/// // Safety: statics in this kernel are mapped at their physical addresses.
+/// unsafe { STEAL_TIME.register() }.expect("register steal-time shared memory");
+///
+/// let mut last = 0;
+/// // on each scheduler tick:
+/// let stolen_ns = STEAL_TIME.steal_delta(&mut last);
+/// ```
+#[repr(C, align(64))]
+pub struct StealTime {
+    sequence: UnsafeCell<u32>,
+    flags: UnsafeCell<u32>,
+    steal: UnsafeCell<u64>,
+    preempted: UnsafeCell<u8>,
+    _pad: [u8; 47],
+}
+
+const _: () = assert!(size_of::<StealTime>() == 64);
+
+// Only written by the SBI implementation for the virtual hart the structure is registered on.
+unsafe impl Sync for StealTime {}
+
+impl StealTime {
+    /// Create a zeroed steal-time shared memory.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            sequence: UnsafeCell::new(0),
+            flags: UnsafeCell::new(0),
+            steal: UnsafeCell::new(0),
+            preempted: UnsafeCell::new(0),
+            _pad: [0; 47],
+        }
+    }
+
+    /// Set this structure as the steal-time shared memory of the calling virtual hart.
+    ///
+    /// The return value is the same as [`sta_set_shmem`].
+    ///
+    /// # Safety
+    ///
+    /// This structure MUST be mapped at the same virtual and physical address.
+    #[inline]
+    pub unsafe fn register(&'static self) -> SbiRet {
+        let phys_addr = self as *const Self as usize;
+        sta_set_shmem(SharedPtr::new(phys_addr, 0), 0)
+    }
+
+    /// Stop steal-time reporting for the calling virtual hart.
+    ///
+    /// The return value is the same as [`sta_set_shmem`].
+    #[inline]
+    pub fn unregister() -> SbiRet {
+        sta_set_shmem(SharedPtr::new(usize::MAX, usize::MAX), 0)
+    }
+
+    /// Read the amount of time in nanoseconds this virtual hart was not idle and scheduled out.
+    ///
+    /// The value is read again if the SBI implementation updates it concurrently,
+    /// as indicated by the `sequence` field.
+    pub fn steal(&self) -> u64 {
+        loop {
+            let sequence = u32::from_le(unsafe { self.sequence.get().read_volatile() });
+            fence(Ordering::Acquire);
+            let steal = u64::from_le(unsafe { self.steal.get().read_volatile() });
+            fence(Ordering::Acquire);
+            if sequence & 1 == 0
+                && sequence == u32::from_le(unsafe { self.sequence.get().read_volatile() })
+            {
+                break steal;
+            }
+        }
+    }
+
+    /// Returns steal-time in nanoseconds since `last`, and updates `last` to the current value.
+    #[inline]
+    pub fn steal_delta(&self, last: &mut u64) -> u64 {
+        let steal = self.steal();
+        let delta = steal.wrapping_sub(*last);
+        *last = steal;
+        delta
+    }
+
+    /// Returns whether the SBI implementation reports this virtual hart as preempted.
+    ///
+    /// This flag is advisory; it can be used for example to check if a lock holder
+    /// has been preempted, and disable optimistic spinning in that case.
+    #[inline]
+    pub fn preempted(&self) -> bool {
+        unsafe { self.preempted.get().read_volatile() != 0 }
+    }
+}
+
+impl Default for StealTime {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -16,18 +16,35 @@ pub const UNAVAILABLE_EXTENSION: usize = 0;
 ///
 /// Declared in §4.1.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 #[repr(transparent)]
 pub struct Version {
     raw: usize,
 }
 
 impl Version {
+    /// RISC-V SBI version 0.1, the legacy interface.
+    pub const V0_1: Version = Version::from_raw(0x0000_0001);
+
+    /// RISC-V SBI version 0.2.
+    pub const V0_2: Version = Version::from_raw(0x0000_0002);
+
+    /// RISC-V SBI version 0.3.
+    pub const V0_3: Version = Version::from_raw(0x0000_0003);
+
     /// RISC-V SBI version 1.0, ratified at Mar 23, 2022.
     pub const V1_0: Version = Version::from_raw(0x0100_0000);
 
     /// RISC-V SBI version 2.0, ratified at Feb 1, 2024.
     pub const V2_0: Version = Version::from_raw(0x0200_0000);
 
+    /// RISC-V SBI version 3.0.
+    pub const V3_0: Version = Version::from_raw(0x0300_0000);
+
     /// Converts raw extension value into Version structure.
     #[inline]
     pub const fn from_raw(raw: usize) -> Self {
@@ -54,6 +71,14 @@ impl core::fmt::Display for Version {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for Version {
+    #[inline]
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=usize}.{=usize}", self.major(), self.minor())
+    }
+}
+
 impl core::cmp::PartialOrd for Version {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
@@ -239,5 +264,11 @@ mod tests {
         assert_eq!(Version::V1_0.minor(), 0);
         assert_eq!(Version::V2_0.major(), 2);
         assert_eq!(Version::V2_0.minor(), 0);
+        assert_eq!(Version::V3_0.major(), 3);
+        assert_eq!(Version::V3_0.minor(), 0);
+        assert_eq!(Version::V0_1.major(), 0);
+        assert_eq!(Version::V0_1.minor(), 1);
+        assert!(Version::V0_1 < Version::V0_2);
+        assert!(Version::V0_3 < Version::V1_0);
     }
 }
@@ -6,14 +6,14 @@ pub use sbi_ret::{Error, SbiRegister, SbiRet, id::*};
 
 // Masks.
 mod mask_commons;
-pub use mask_commons::MaskError;
+pub use mask_commons::{Mask, MaskChunks, MaskError, MaskIds};
 
 mod counter_mask;
 mod hart_mask;
 mod trigger_mask;
-pub use counter_mask::CounterMask;
+pub use counter_mask::{CounterIds, CounterMask};
 pub use hart_mask::{HartIds, HartMask};
-pub use trigger_mask::TriggerMask;
+pub use trigger_mask::{TriggerIds, TriggerMask};
 
 // Pointers.
 mod physical_slice;
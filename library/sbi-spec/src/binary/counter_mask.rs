@@ -1,8 +1,18 @@
-use super::{mask_commons::has_bit, sbi_ret::SbiRegister};
+use super::{
+    mask_commons::{Mask, MaskError, MaskIds, has_bit, valid_bit},
+    sbi_ret::SbiRegister,
+};
+
+/// Iterator structure for `CounterMask`.
+///
+/// It will iterate counter index from low to high.
+pub type CounterIds = MaskIds;
 
 /// Counter index mask structure in SBI function calls for the `PMU` extension §11.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct CounterMask<T = usize> {
     counter_idx_mask: T,
     counter_idx_base: T,
@@ -47,6 +57,71 @@ impl CounterMask<usize> {
             counter,
         )
     }
+
+    /// Insert a counter index into this [CounterMask].
+    ///
+    /// Returns error when `counter` is invalid.
+    #[inline]
+    pub const fn insert(&mut self, counter: usize) -> Result<(), MaskError> {
+        if self.counter_idx_base == Self::IGNORE_MASK {
+            Ok(())
+        } else if valid_bit(self.counter_idx_base, counter) {
+            self.counter_idx_mask |= 1usize << (counter - self.counter_idx_base);
+            Ok(())
+        } else {
+            Err(MaskError::InvalidBit)
+        }
+    }
+
+    /// Remove a counter index from this [CounterMask].
+    ///
+    /// Returns error when `counter` is invalid, or it has been ignored.
+    #[inline]
+    pub const fn remove(&mut self, counter: usize) -> Result<(), MaskError> {
+        if self.counter_idx_base == Self::IGNORE_MASK {
+            Err(MaskError::Ignored)
+        } else if valid_bit(self.counter_idx_base, counter) {
+            self.counter_idx_mask &= !(1usize << (counter - self.counter_idx_base));
+            Ok(())
+        } else {
+            Err(MaskError::InvalidBit)
+        }
+    }
+
+    /// Returns [CounterIds] of self.
+    #[inline]
+    pub const fn iter(&self) -> CounterIds {
+        MaskIds::new(
+            self.counter_idx_mask,
+            self.counter_idx_base,
+            Some(Self::IGNORE_MASK),
+        )
+    }
+}
+
+impl Mask for CounterMask {
+    const IGNORE_BASE: Option<usize> = Some(Self::IGNORE_MASK);
+
+    #[inline]
+    fn from_mask_base(mask: usize, base: usize) -> Self {
+        Self::from_mask_base(mask, base)
+    }
+
+    #[inline]
+    fn into_inner(self) -> (usize, usize) {
+        self.into_inner()
+    }
+}
+
+impl IntoIterator for CounterMask {
+    type Item = usize;
+
+    type IntoIter = CounterIds;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
 #[cfg(test)]
@@ -83,6 +158,21 @@ mod tests {
         assert!(mask.has_bit(usize::MAX));
     }
 
+    #[test]
+    fn rustsbi_counter_ids_iterator() {
+        let mut mask = CounterMask::from_mask_base(0b1010, 8);
+        assert!(mask.iter().eq([9, 11]));
+        assert_eq!(mask.insert(8), Ok(()));
+        assert_eq!(mask.remove(11), Ok(()));
+        assert_eq!(mask.insert(7), Err(MaskError::InvalidBit));
+        assert!(mask.into_iter().eq([8, 9]));
+        assert_eq!(mask.iter().next_back(), Some(9));
+        let mut mask = CounterMask::from_mask_base(0, usize::MAX);
+        assert_eq!(mask.insert(7), Ok(()));
+        assert_eq!(mask.remove(7), Err(MaskError::Ignored));
+        assert!(mask.iter().take(3).eq([0, 1, 2]));
+    }
+
     #[test]
     fn rustsbi_counter_mask_non_usize() {
         assert_eq!(CounterMask::<i32>::IGNORE_MASK, -1);
@@ -1,11 +1,18 @@
 use super::{
-    mask_commons::{MaskError, has_bit, valid_bit},
+    mask_commons::{Mask, MaskError, MaskIds, has_bit, valid_bit},
     sbi_ret::SbiRegister,
 };
 
+/// Iterator structure for `HartMask`.
+///
+/// It will iterate hart id from low to high.
+pub type HartIds = MaskIds;
+
 /// Hart mask structure in SBI function calls.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct HartMask<T = usize> {
     hart_mask: T,
     hart_mask_base: T,
@@ -98,144 +105,35 @@ impl HartMask<usize> {
     /// Returns [HartIds] of self.
     #[inline]
     pub const fn iter(&self) -> HartIds {
-        HartIds {
-            inner: match self.hart_mask_base {
-                Self::IGNORE_MASK => UnvisitedMask::Range(0, usize::MAX),
-                _ => UnvisitedMask::MaskBase(self.hart_mask, self.hart_mask_base),
-            },
-        }
+        MaskIds::new(self.hart_mask, self.hart_mask_base, Some(Self::IGNORE_MASK))
     }
 }
 
-impl IntoIterator for HartMask {
-    type Item = usize;
-
-    type IntoIter = HartIds;
+impl Mask for HartMask {
+    const IGNORE_BASE: Option<usize> = Some(Self::IGNORE_MASK);
 
     #[inline]
-    fn into_iter(self) -> Self::IntoIter {
-        self.iter()
+    fn from_mask_base(mask: usize, base: usize) -> Self {
+        Self::from_mask_base(mask, base)
     }
-}
-
-/// Iterator structure for `HartMask`.
-///
-/// It will iterate hart id from low to high.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-pub struct HartIds {
-    inner: UnvisitedMask,
-}
-
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-enum UnvisitedMask {
-    MaskBase(usize, usize),
-    Range(usize, usize),
-}
-
-impl Iterator for HartIds {
-    type Item = usize;
 
     #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        match &mut self.inner {
-            UnvisitedMask::MaskBase(0, _base) => None,
-            UnvisitedMask::MaskBase(unvisited_mask, base) => {
-                let low_bit = unvisited_mask.trailing_zeros();
-                let hart_id = usize::try_from(low_bit).unwrap() + *base;
-                *unvisited_mask &= !(1usize << low_bit);
-                Some(hart_id)
-            }
-            UnvisitedMask::Range(start, end) => {
-                assert!(start <= end);
-                if *start < *end {
-                    let ans = *start;
-                    *start += 1;
-                    Some(ans)
-                } else {
-                    None
-                }
-            }
-        }
-    }
-
-    #[inline]
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        match self.inner {
-            UnvisitedMask::MaskBase(unvisited_mask, _base) => {
-                let exact_popcnt = usize::try_from(unvisited_mask.count_ones()).unwrap();
-                (exact_popcnt, Some(exact_popcnt))
-            }
-            UnvisitedMask::Range(start, end) => {
-                assert!(start <= end);
-                let exact_num_harts = end - start;
-                (exact_num_harts, Some(exact_num_harts))
-            }
-        }
-    }
-
-    #[inline]
-    fn count(self) -> usize {
-        self.size_hint().0
-    }
-
-    #[inline]
-    fn last(mut self) -> Option<Self::Item> {
-        self.next_back()
-    }
-
-    #[inline]
-    fn min(mut self) -> Option<Self::Item> {
-        self.next()
-    }
-
-    #[inline]
-    fn max(mut self) -> Option<Self::Item> {
-        self.next_back()
+    fn into_inner(self) -> (usize, usize) {
+        self.into_inner()
     }
+}
 
-    #[inline]
-    fn is_sorted(self) -> bool {
-        true
-    }
+impl IntoIterator for HartMask {
+    type Item = usize;
 
-    // TODO: implement fn advance_by once it's stabilized: https://github.com/rust-lang/rust/issues/77404
-    // #[inline]
-    // fn advance_by(&mut self, n: usize) -> Result<(), core::num::NonZero<usize>> { ... }
-}
+    type IntoIter = HartIds;
 
-impl DoubleEndedIterator for HartIds {
     #[inline]
-    fn next_back(&mut self) -> Option<Self::Item> {
-        match &mut self.inner {
-            UnvisitedMask::MaskBase(0, _base) => None,
-            UnvisitedMask::MaskBase(unvisited_mask, base) => {
-                let high_bit = unvisited_mask.leading_zeros();
-                let hart_id = usize::try_from(usize::BITS - high_bit - 1).unwrap() + *base;
-                *unvisited_mask &= !(1usize << (usize::BITS - high_bit - 1));
-                Some(hart_id)
-            }
-            UnvisitedMask::Range(start, end) => {
-                assert!(start <= end);
-                if *start < *end {
-                    let ans = *end;
-                    *end -= 1;
-                    Some(ans)
-                } else {
-                    None
-                }
-            }
-        }
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
-
-    // TODO: implement advance_back_by once stabilized.
-    // #[inline]
-    // fn advance_back_by(&mut self, n: usize) -> Result<(), core::num::NonZero<usize>> { ... }
 }
 
-impl ExactSizeIterator for HartIds {}
-
-impl core::iter::FusedIterator for HartIds {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
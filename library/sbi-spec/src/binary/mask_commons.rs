@@ -1,5 +1,10 @@
 //! Common SBI mask operations and structures.
 
+use core::{
+    iter::{Fuse, FusedIterator},
+    marker::PhantomData,
+};
+
 /// Check if the implementation can contains the provided `bit`.
 #[inline]
 pub(crate) const fn valid_bit(base: usize, bit: usize) -> bool {
@@ -37,9 +42,432 @@ pub(crate) const fn has_bit(mask: usize, base: usize, ignore: usize, bit: usize)
 
 /// Error of mask modification.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum MaskError {
     /// This mask has been ignored.
     Ignored,
     /// Request bit is invalid.
     InvalidBit,
 }
+
+/// Move bits of `mask` relative to base `from` into bits relative to base `to`.
+///
+/// Ids that cannot be represented on base `to` are dropped.
+#[inline]
+const fn rebase(mask: usize, from: usize, to: usize) -> usize {
+    if from >= to {
+        let shift = from - to;
+        if shift >= usize::BITS as usize {
+            0
+        } else {
+            mask << shift
+        }
+    } else {
+        let shift = to - from;
+        if shift >= usize::BITS as usize {
+            0
+        } else {
+            mask >> shift
+        }
+    }
+}
+
+/// Common operations on SBI masks composed of a `mask` and a `base` parameter.
+///
+/// This trait is implemented for [`HartMask`], [`CounterMask`] and [`TriggerMask`] on `usize`,
+/// so that kernels can build and combine masks without handling each type on its own.
+///
+/// [`HartMask`]: super::HartMask
+/// [`CounterMask`]: super::CounterMask
+/// [`TriggerMask`]: super::TriggerMask
+pub trait Mask: Copy {
+    /// Special `base` value to ignore the `mask` and consider all ids as set,
+    /// or `None` if this mask has no such value.
+    const IGNORE_BASE: Option<usize>;
+
+    /// Construct the mask from mask value and base id.
+    fn from_mask_base(mask: usize, base: usize) -> Self;
+
+    /// Returns `mask` and `base` parameters of the mask.
+    fn into_inner(self) -> (usize, usize);
+
+    /// Construct a mask that contains no ids.
+    #[inline]
+    fn empty() -> Self {
+        Self::from_mask_base(0, 0)
+    }
+
+    /// Returns whether the `mask` parameter is ignored, i.e. this mask contains all ids.
+    #[inline]
+    fn is_ignored(self) -> bool {
+        let (_, base) = self.into_inner();
+        Self::IGNORE_BASE == Some(base)
+    }
+
+    /// Returns whether this mask contains no ids.
+    #[inline]
+    fn is_empty(self) -> bool {
+        let (mask, _) = self.into_inner();
+        !self.is_ignored() && mask == 0
+    }
+
+    /// Returns whether this mask contains the provided `id`.
+    #[inline]
+    fn contains(self, id: usize) -> bool {
+        let (mask, base) = self.into_inner();
+        self.is_ignored() || (valid_bit(base, id) && mask & (1 << (id - base)) != 0)
+    }
+
+    /// Returns an iterator over ids in this mask, from low to high.
+    #[inline]
+    fn ids(self) -> MaskIds {
+        let (mask, base) = self.into_inner();
+        MaskIds::new(mask, base, Self::IGNORE_BASE)
+    }
+
+    /// Returns a mask containing ids in either `self` or `other`.
+    ///
+    /// The base of `self` is kept if possible, otherwise the result is based on the lowest id.
+    /// Returns [`MaskError::InvalidBit`] if the ids cannot fit in one mask.
+    fn union(self, other: Self) -> Result<Self, MaskError> {
+        if self.is_ignored() || other.is_empty() {
+            return Ok(self);
+        }
+        if other.is_ignored() || self.is_empty() {
+            return Ok(other);
+        }
+        let (mask, base) = self.into_inner();
+        let (other_mask, other_base) = other.into_inner();
+        // Both masks contain at least one id here.
+        let lo = Ord::min(self.ids().min(), other.ids().min()).unwrap_or(0);
+        let hi = Ord::max(self.ids().max(), other.ids().max()).unwrap_or(0);
+        if hi - lo >= usize::BITS as usize {
+            return Err(MaskError::InvalidBit);
+        }
+        let new_base = if base <= lo && valid_bit(base, hi) {
+            base
+        } else {
+            lo
+        };
+        let new_mask = rebase(mask, base, new_base) | rebase(other_mask, other_base, new_base);
+        Ok(Self::from_mask_base(new_mask, new_base))
+    }
+
+    /// Returns a mask containing ids in both `self` and `other`.
+    #[inline]
+    fn intersection(self, other: Self) -> Self {
+        if self.is_ignored() {
+            return other;
+        }
+        if other.is_ignored() {
+            return self;
+        }
+        let (mask, base) = self.into_inner();
+        let (other_mask, other_base) = other.into_inner();
+        Self::from_mask_base(mask & rebase(other_mask, other_base, base), base)
+    }
+
+    /// Returns a mask containing ids in `self` but not in `other`.
+    ///
+    /// Returns [`MaskError::Ignored`] if `self` is ignored, as the result cannot be represented.
+    #[inline]
+    fn difference(self, other: Self) -> Result<Self, MaskError> {
+        if self.is_ignored() {
+            return Err(MaskError::Ignored);
+        }
+        let (mask, base) = self.into_inner();
+        if other.is_ignored() {
+            return Ok(Self::from_mask_base(0, base));
+        }
+        let (other_mask, other_base) = other.into_inner();
+        Ok(Self::from_mask_base(
+            mask & !rebase(other_mask, other_base, base),
+            base,
+        ))
+    }
+
+    /// Split an arbitrary set of ids into masks, each fits into one SBI call.
+    ///
+    /// Consecutive ids are packed into the same mask as long as they fit;
+    /// ids sorted from low to high produce the fewest masks.
+    #[inline]
+    fn chunks_from_ids<I: IntoIterator<Item = usize>>(ids: I) -> MaskChunks<Self, I::IntoIter> {
+        MaskChunks {
+            ids: ids.into_iter().fuse(),
+            current: None,
+            _mask: PhantomData,
+        }
+    }
+}
+
+/// Iterator of masks returned by [`Mask::chunks_from_ids`].
+#[derive(Debug, Clone)]
+pub struct MaskChunks<M, I> {
+    ids: Fuse<I>,
+    current: Option<(usize, usize)>,
+    _mask: PhantomData<M>,
+}
+
+impl<M: Mask, I: Iterator<Item = usize>> Iterator for MaskChunks<M, I> {
+    type Item = M;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for id in self.ids.by_ref() {
+            match &mut self.current {
+                Some((mask, base)) if valid_bit(*base, id) => *mask |= 1 << (id - *base),
+                current => {
+                    // Base must not be the ignore value, use the highest bit for this id then.
+                    let base = match M::IGNORE_BASE {
+                        Some(ignore) if id == ignore => id - (usize::BITS as usize - 1),
+                        _ => id,
+                    };
+                    let prev = current.replace((1 << (id - base), base));
+                    if let Some((mask, base)) = prev {
+                        return Some(M::from_mask_base(mask, base));
+                    }
+                }
+            }
+        }
+        self.current
+            .take()
+            .map(|(mask, base)| M::from_mask_base(mask, base))
+    }
+}
+
+impl<M: Mask, I: Iterator<Item = usize>> FusedIterator for MaskChunks<M, I> {}
+
+/// Iterator structure over ids in SBI masks.
+///
+/// It will iterate ids from low to high.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MaskIds {
+    inner: UnvisitedMask,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum UnvisitedMask {
+    MaskBase(usize, usize),
+    Range(usize, usize),
+}
+
+impl MaskIds {
+    /// Create an iterator over ids in `mask` starting from `base`.
+    ///
+    /// If `base` equals to the `ignore` value, the iterator yields all ids.
+    #[inline]
+    pub(crate) const fn new(mask: usize, base: usize, ignore: Option<usize>) -> Self {
+        let inner = match ignore {
+            Some(ignore) if base == ignore => UnvisitedMask::Range(0, usize::MAX),
+            _ => UnvisitedMask::MaskBase(mask, base),
+        };
+        Self { inner }
+    }
+}
+
+impl Iterator for MaskIds {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            UnvisitedMask::MaskBase(0, _base) => None,
+            UnvisitedMask::MaskBase(unvisited_mask, base) => {
+                let low_bit = unvisited_mask.trailing_zeros();
+                let id = usize::try_from(low_bit).unwrap() + *base;
+                *unvisited_mask &= !(1usize << low_bit);
+                Some(id)
+            }
+            UnvisitedMask::Range(start, end) => {
+                assert!(start <= end);
+                if *start < *end {
+                    let ans = *start;
+                    *start += 1;
+                    Some(ans)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.inner {
+            UnvisitedMask::MaskBase(unvisited_mask, _base) => {
+                let exact_popcnt = usize::try_from(unvisited_mask.count_ones()).unwrap();
+                (exact_popcnt, Some(exact_popcnt))
+            }
+            UnvisitedMask::Range(start, end) => {
+                assert!(start <= end);
+                let exact_num = end - start;
+                (exact_num, Some(exact_num))
+            }
+        }
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.size_hint().0
+    }
+
+    #[inline]
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    #[inline]
+    fn min(mut self) -> Option<Self::Item> {
+        self.next()
+    }
+
+    #[inline]
+    fn max(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    #[inline]
+    fn is_sorted(self) -> bool {
+        true
+    }
+
+    // TODO: implement fn advance_by once it's stabilized: https://github.com/rust-lang/rust/issues/77404
+    // #[inline]
+    // fn advance_by(&mut self, n: usize) -> Result<(), core::num::NonZero<usize>> { ... }
+}
+
+impl DoubleEndedIterator for MaskIds {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            UnvisitedMask::MaskBase(0, _base) => None,
+            UnvisitedMask::MaskBase(unvisited_mask, base) => {
+                let high_bit = unvisited_mask.leading_zeros();
+                let id = usize::try_from(usize::BITS - high_bit - 1).unwrap() + *base;
+                *unvisited_mask &= !(1usize << (usize::BITS - high_bit - 1));
+                Some(id)
+            }
+            UnvisitedMask::Range(start, end) => {
+                assert!(start <= end);
+                if *start < *end {
+                    let ans = *end;
+                    *end -= 1;
+                    Some(ans)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    // TODO: implement advance_back_by once stabilized.
+    // #[inline]
+    // fn advance_back_by(&mut self, n: usize) -> Result<(), core::num::NonZero<usize>> { ... }
+}
+
+impl ExactSizeIterator for MaskIds {}
+
+impl FusedIterator for MaskIds {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary::{CounterMask, HartMask, TriggerMask};
+
+    #[test]
+    fn rustsbi_mask_union() {
+        let a = HartMask::from_mask_base(0b1, 4);
+        let b = HartMask::from_mask_base(0b1, 10);
+        assert_eq!(a.union(b), Ok(HartMask::from_mask_base(0b1000001, 4)));
+        // base of `self` is kept when the result fits.
+        let a = HartMask::from_mask_base(0b100, 2);
+        let b = HartMask::from_mask_base(0b1, 6);
+        assert_eq!(a.union(b), Ok(HartMask::from_mask_base(0b10100, 2)));
+        // otherwise it is based on the lowest id.
+        assert_eq!(b.union(a), Ok(HartMask::from_mask_base(0b101, 4)));
+        let far = HartMask::from_mask_base(0b1, 4 + usize::BITS as usize);
+        assert_eq!(
+            HartMask::from_mask_base(0b1, 4).union(far),
+            Err(MaskError::InvalidBit)
+        );
+        assert_eq!(a.union(HartMask::empty()), Ok(a));
+        assert_eq!(HartMask::empty().union(a), Ok(a));
+        assert!(a.union(HartMask::all()).unwrap().is_ignored());
+        assert!(HartMask::all().union(a).unwrap().is_ignored());
+
+        let a = CounterMask::from_mask_base(0b11, 0);
+        let b = CounterMask::from_mask_base(0b110, 1);
+        assert!(a.union(b).unwrap().ids().eq([0, 1, 2, 3]));
+        // `usize::MAX` is a normal id for trigger masks.
+        let a = TriggerMask::from_mask_base(0b1, usize::MAX);
+        let b = TriggerMask::from_mask_base(0b1, usize::MAX - 1);
+        assert!(a.union(b).unwrap().ids().eq([usize::MAX - 1, usize::MAX]));
+    }
+
+    #[test]
+    fn rustsbi_mask_intersection_difference() {
+        let a = HartMask::from_mask_base(0b1111, 8);
+        let b = HartMask::from_mask_base(0b1111, 10);
+        assert_eq!(a.intersection(b), HartMask::from_mask_base(0b1100, 8));
+        assert_eq!(b.intersection(a), HartMask::from_mask_base(0b11, 10));
+        assert_eq!(a.intersection(HartMask::all()), a);
+        assert_eq!(HartMask::all().intersection(a), a);
+        let far = HartMask::from_mask_base(0b1, 8 + usize::BITS as usize);
+        assert!(a.intersection(far).is_empty());
+
+        assert_eq!(a.difference(b), Ok(HartMask::from_mask_base(0b11, 8)));
+        assert_eq!(b.difference(a), Ok(HartMask::from_mask_base(0b1100, 10)));
+        assert_eq!(a.difference(far), Ok(a));
+        assert!(a.difference(HartMask::all()).unwrap().is_empty());
+        assert_eq!(HartMask::all().difference(a), Err(MaskError::Ignored));
+
+        let a = CounterMask::from_mask_base(0b101, 3);
+        assert!(a.contains(3));
+        assert!(!a.contains(4));
+        assert!(CounterMask::from_mask_base(0, usize::MAX).contains(4));
+        assert!(!a.is_ignored());
+        assert!(!a.is_empty());
+    }
+
+    #[test]
+    fn rustsbi_mask_chunks_from_ids() {
+        let bits = usize::BITS as usize;
+        let mut chunks = HartMask::chunks_from_ids([0, 3, bits - 1, bits, bits + 2, 1000]);
+        assert_eq!(
+            chunks.next(),
+            Some(HartMask::from_mask_base(0b1001 | 1 << (bits - 1), 0))
+        );
+        assert_eq!(chunks.next(), Some(HartMask::from_mask_base(0b101, bits)));
+        assert_eq!(chunks.next(), Some(HartMask::from_mask_base(0b1, 1000)));
+        assert_eq!(chunks.next(), None);
+        assert_eq!(chunks.next(), None);
+
+        assert_eq!(HartMask::chunks_from_ids([]).next(), None);
+        // Unsorted ids still produce correct, but more, chunks.
+        let ids = [5, 3, 4, 200, 6];
+        let chunks = HartMask::chunks_from_ids(ids);
+        let mut collected = [0; 5];
+        let mut count = 0;
+        for mask in chunks {
+            assert!(!mask.is_empty());
+            for id in mask {
+                collected[count] = id;
+                count += 1;
+            }
+        }
+        assert_eq!(collected, [5, 3, 4, 200, 6]);
+
+        // `usize::MAX` must not become the base of a hart mask, as it ignores the mask.
+        let mut chunks = HartMask::chunks_from_ids([usize::MAX]);
+        let mask = chunks.next().unwrap();
+        assert!(!mask.is_ignored());
+        assert!(mask.ids().eq([usize::MAX]));
+        assert_eq!(chunks.next(), None);
+        // ... but it could be the base of trigger masks.
+        let mut chunks = TriggerMask::chunks_from_ids([usize::MAX]);
+        assert_eq!(
+            chunks.next(),
+            Some(TriggerMask::from_mask_base(0b1, usize::MAX))
+        );
+    }
+}
@@ -16,10 +16,16 @@ use core::marker::PhantomData;
 /// should provide continuous physical memory, wrapping its reference using this structure
 /// before passing into SBI runtime.
 #[derive(Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct Physical<P> {
     num_bytes: usize,
     phys_addr_lo: usize,
     phys_addr_hi: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
     _marker: PhantomData<P>,
 }
 
@@ -53,3 +59,16 @@ impl<P> Physical<P> {
         self.phys_addr_hi
     }
 }
+
+#[cfg(feature = "defmt")]
+impl<P> defmt::Format for Physical<P> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Physical {{ num_bytes: {=usize}, phys_addr_lo: {=usize:#x}, phys_addr_hi: {=usize:#x} }}",
+            self.num_bytes,
+            self.phys_addr_lo,
+            self.phys_addr_hi
+        )
+    }
+}
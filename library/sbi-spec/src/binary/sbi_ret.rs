@@ -9,6 +9,8 @@
 /// extern C. The `repr(C)` is set in case that some users want to use
 /// this structure in FFI code.
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(C)]
 pub struct SbiRet<T = usize> {
     /// Error number.
@@ -204,8 +206,23 @@ impl<T: SbiRegister + core::fmt::LowerHex> core::fmt::Debug for SbiRet<T> {
     }
 }
 
+/// Formats the SBI return value with C language name of its error code.
+///
+/// For example, a successful `SbiRet` with value `0x10` is displayed as `SBI_SUCCESS(0x10)`,
+/// and `SbiRet::denied_locked()` is displayed as `SBI_ERR_DENIED_LOCKED`.
+impl<T: SbiRegister + core::fmt::LowerHex> core::fmt::Display for SbiRet<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match T::into_result(*self) {
+            Ok(value) => write!(f, "SBI_SUCCESS({:#x})", value),
+            Err(err) => write!(f, "{}", err),
+        }
+    }
+}
+
 /// RISC-V SBI error in enumeration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error<T = usize> {
     /// Error for SBI call failed for unknown reasons.
     Failed,
@@ -239,6 +256,41 @@ pub enum Error<T = usize> {
     Custom(T),
 }
 
+impl<T> Error<T> {
+    /// Returns the C language name of this error code, e.g. `SBI_ERR_DENIED_LOCKED`.
+    ///
+    /// Returns `None` for custom error codes.
+    #[inline]
+    pub const fn name(&self) -> Option<&'static str> {
+        match self {
+            Error::Failed => Some("SBI_ERR_FAILED"),
+            Error::NotSupported => Some("SBI_ERR_NOT_SUPPORTED"),
+            Error::InvalidParam => Some("SBI_ERR_INVALID_PARAM"),
+            Error::Denied => Some("SBI_ERR_DENIED"),
+            Error::InvalidAddress => Some("SBI_ERR_INVALID_ADDRESS"),
+            Error::AlreadyAvailable => Some("SBI_ERR_ALREADY_AVAILABLE"),
+            Error::AlreadyStarted => Some("SBI_ERR_ALREADY_STARTED"),
+            Error::AlreadyStopped => Some("SBI_ERR_ALREADY_STOPPED"),
+            Error::NoShmem => Some("SBI_ERR_NO_SHMEM"),
+            Error::InvalidState => Some("SBI_ERR_INVALID_STATE"),
+            Error::BadRange => Some("SBI_ERR_BAD_RANGE"),
+            Error::Timeout => Some("SBI_ERR_TIMEOUT"),
+            Error::Io => Some("SBI_ERR_IO"),
+            Error::DeniedLocked => Some("SBI_ERR_DENIED_LOCKED"),
+            Error::Custom(_) => None,
+        }
+    }
+}
+
+impl<T: core::fmt::LowerHex> core::fmt::Display for Error<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Custom(code) => write!(f, "SBI_ERR_CUSTOM({:#x})", code),
+            _ => f.write_str(self.name().unwrap_or_default()),
+        }
+    }
+}
+
 impl<T: SbiRegister> SbiRet<T> {
     /// Returns success SBI state with given `value`.
     #[inline]
@@ -1143,4 +1195,30 @@ mod tests {
         assert_eq!(SbiRet::io(), SbiRet { value: 0, error: usize::MAX - 13 + 1 });
         assert_eq!(SbiRet::denied_locked(), SbiRet { value: 0, error: usize::MAX - 14 + 1 });
     }
+
+    #[test]
+    fn rustsbi_sbi_ret_display() {
+        extern crate alloc;
+        use alloc::string::ToString;
+
+        assert_eq!(
+            SbiRet::<usize>::success(0x10).to_string(),
+            "SBI_SUCCESS(0x10)"
+        );
+        assert_eq!(SbiRet::<usize>::failed().to_string(), "SBI_ERR_FAILED");
+        assert_eq!(
+            SbiRet::<usize>::invalid_param().to_string(),
+            "SBI_ERR_INVALID_PARAM"
+        );
+        assert_eq!(
+            SbiRet::<usize>::denied_locked().to_string(),
+            "SBI_ERR_DENIED_LOCKED"
+        );
+        assert_eq!(
+            SbiRet::<u32>::from(Error::Custom(0xffff_fc18)).to_string(),
+            "SBI_ERR_CUSTOM(0xfffffc18)"
+        );
+        assert_eq!(Error::<usize>::NoShmem.name(), Some("SBI_ERR_NO_SHMEM"));
+        assert_eq!(Error::Custom(1usize).name(), None);
+    }
 }
@@ -49,9 +49,15 @@ use core::marker::PhantomData;
 /// which have memory physical addresses wider than `XLEN` bits.
 // FIXME: should constrain with `T: Thin` once ptr_metadata feature is stabled;
 // RISC-V SBI does not provide an approach to pass pointer metadata by SBI calls.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct SharedPtr<T> {
     phys_addr_lo: usize,
     phys_addr_hi: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
     _marker: PhantomData<*mut T>,
 }
 
@@ -89,3 +95,15 @@ impl<T> Clone for SharedPtr<T> {
 }
 
 impl<T> Copy for SharedPtr<T> {}
+
+#[cfg(feature = "defmt")]
+impl<T> defmt::Format for SharedPtr<T> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "SharedPtr {{ phys_addr_lo: {=usize:#x}, phys_addr_hi: {=usize:#x} }}",
+            self.phys_addr_lo,
+            self.phys_addr_hi
+        )
+    }
+}
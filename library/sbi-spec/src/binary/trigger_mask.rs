@@ -1,8 +1,18 @@
-use super::sbi_ret::SbiRegister;
+use super::{
+    mask_commons::{Mask, MaskError, MaskIds, valid_bit},
+    sbi_ret::SbiRegister,
+};
+
+/// Iterator structure for `TriggerMask`.
+///
+/// It will iterate trigger index from low to high.
+pub type TriggerIds = MaskIds;
 
 /// Debug trigger mask structure for the `DBTR` extension §19.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct TriggerMask<T = usize> {
     trig_idx_base: T,
     trig_idx_mask: T,
@@ -27,3 +37,99 @@ impl<T: SbiRegister> TriggerMask<T> {
         (self.trig_idx_mask, self.trig_idx_base)
     }
 }
+
+// FIXME: implement for T: SbiRegister once we can implement this using const traits.
+// Ref: https://rust-lang.github.io/rust-project-goals/2024h2/const-traits.html
+impl TriggerMask<usize> {
+    /// Returns whether the [TriggerMask] contains the provided `trigger`.
+    ///
+    /// Unlike other masks, the `DBTR` extension has no special value to ignore the `mask`.
+    #[inline]
+    pub const fn has_bit(self, trigger: usize) -> bool {
+        valid_bit(self.trig_idx_base, trigger)
+            && self.trig_idx_mask & (1 << (trigger - self.trig_idx_base)) != 0
+    }
+
+    /// Insert a trigger index into this [TriggerMask].
+    ///
+    /// Returns error when `trigger` is invalid.
+    #[inline]
+    pub const fn insert(&mut self, trigger: usize) -> Result<(), MaskError> {
+        if valid_bit(self.trig_idx_base, trigger) {
+            self.trig_idx_mask |= 1usize << (trigger - self.trig_idx_base);
+            Ok(())
+        } else {
+            Err(MaskError::InvalidBit)
+        }
+    }
+
+    /// Remove a trigger index from this [TriggerMask].
+    ///
+    /// Returns error when `trigger` is invalid.
+    #[inline]
+    pub const fn remove(&mut self, trigger: usize) -> Result<(), MaskError> {
+        if valid_bit(self.trig_idx_base, trigger) {
+            self.trig_idx_mask &= !(1usize << (trigger - self.trig_idx_base));
+            Ok(())
+        } else {
+            Err(MaskError::InvalidBit)
+        }
+    }
+
+    /// Returns [TriggerIds] of self.
+    #[inline]
+    pub const fn iter(&self) -> TriggerIds {
+        MaskIds::new(self.trig_idx_mask, self.trig_idx_base, None)
+    }
+}
+
+impl Mask for TriggerMask {
+    const IGNORE_BASE: Option<usize> = None;
+
+    #[inline]
+    fn from_mask_base(mask: usize, base: usize) -> Self {
+        Self::from_mask_base(mask, base)
+    }
+
+    #[inline]
+    fn into_inner(self) -> (usize, usize) {
+        self.into_inner()
+    }
+}
+
+impl IntoIterator for TriggerMask {
+    type Item = usize;
+
+    type IntoIter = TriggerIds;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rustsbi_trigger_mask() {
+        let mut mask = TriggerMask::from_mask_base(0b101, 3);
+        assert!(!mask.has_bit(2));
+        assert!(mask.has_bit(3));
+        assert!(!mask.has_bit(4));
+        assert!(mask.has_bit(5));
+        assert!(!mask.has_bit(3 + usize::BITS as usize));
+        // `DBTR` has no ignored mask, `usize::MAX` is a normal base.
+        let max = TriggerMask::from_mask_base(0b1, usize::MAX);
+        assert!(max.has_bit(usize::MAX));
+        assert!(!max.has_bit(0));
+        assert!(!Mask::is_ignored(max));
+
+        assert_eq!(mask.insert(4), Ok(()));
+        assert_eq!(mask.remove(5), Ok(()));
+        assert_eq!(mask.insert(2), Err(MaskError::InvalidBit));
+        assert!(mask.iter().eq([3, 4]));
+        assert!(max.into_iter().eq([usize::MAX]));
+    }
+}
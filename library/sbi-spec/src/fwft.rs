@@ -57,10 +57,13 @@ pub mod flags {
 
     bitflags! {
         #[derive(Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         /// Declared in Table 94.
         pub struct SetFlags: usize {
             /// If provided, once set, the feature value can no longer be modified.
             const LOCK = 1 << 0;
         }
     }
+
+    crate::impl_defmt_for_flags!(SetFlags);
 }
@@ -55,6 +55,11 @@ pub mod dbtr;
 // §20
 pub mod mpxy;
 
+// Specification versions of extensions and functions.
+pub mod version;
+// Names of extensions and functions.
+pub mod names;
+
 /// Converts SBI EID from str.
 const fn eid_from_str(name: &str) -> i32 {
     match *name.as_bytes() {
@@ -66,6 +71,22 @@ const fn eid_from_str(name: &str) -> i32 {
     }
 }
 
+/// Implements `defmt::Format` for `bitflags` structures by their raw bits.
+macro_rules! impl_defmt_for_flags {
+    ($($ty:ident),+) => {
+        $(
+            #[cfg(feature = "defmt")]
+            impl defmt::Format for $ty {
+                #[inline]
+                fn format(&self, f: defmt::Formatter) {
+                    defmt::write!(f, "{=str}({=usize:#x})", stringify!($ty), self.bits())
+                }
+            }
+        )+
+    };
+}
+pub(crate) use impl_defmt_for_flags;
+
 /// Checks during compilation, and provides an item list for developers.
 #[cfg(test)]
 mod tests {
@@ -321,6 +342,10 @@ mod tests {
         const_assert_eq!(2, feature_id::SYNC_SRET);
         const_assert_eq!(3, feature_id::AUTOSWAP_CSR);
 
+        const_assert_eq!(0x0800, shmem_offset::HFENCE);
+        const_assert_eq!(0x1000 - 0x80, shmem_offset::DIRTY_BITMAP);
+        const_assert_eq!(7, hfence_type::VVMA_ASID_ALL);
+
         const_assert_eq!(8192, shmem_size::RV32);
         const_assert_eq!(12288, shmem_size::RV64);
         const_assert_eq!(20480, shmem_size::RV128);
@@ -363,6 +388,13 @@ mod tests {
         const_assert_eq!(7, INJECT);
         const_assert_eq!(8, HART_UNMASK);
         const_assert_eq!(9, HART_MASK);
+        const_assert_eq!(0xffff_8000, event_id::GLOBAL_SOFTWARE_INJECTED);
+        const_assert_eq!(
+            event_id::GLOBAL_BIT,
+            event_id::GLOBAL_HIGH_PRIO_RAS ^ event_id::LOCAL_HIGH_PRIO_RAS
+        );
+        const_assert_eq!(6, attr_id::INTERRUPTED_SEPC);
+        const_assert_eq!(9, attr_id::INTERRUPTED_A7);
     }
     // §18
     #[test]
@@ -399,5 +431,9 @@ mod tests {
         const_assert_eq!(5, SEND_MESSAGE_WITH_RESPONSE);
         const_assert_eq!(6, SEND_MESSAGE_WITHOUT_RESPONSE);
         const_assert_eq!(7, GET_NOTIFICATION_EVENTS);
+        const_assert_eq!(0, protocol_id::RPMI);
+        const_assert_eq!(5, attr_id::CHANNEL_CAPABILITY);
+        const_assert_eq!(0xB, attr_id::EVENTS_STATE_CONTROL);
+        const_assert_eq!(0x20, channel_capability::GET_NOTIFICATION_EVENTS);
     }
 }
@@ -48,3 +48,77 @@ mod fid {
     #[doc(alias = "SBI_EXT_MPXY_GET_NOTIFICATION_EVENTS")]
     pub const GET_NOTIFICATION_EVENTS: usize = 7;
 }
+
+/// Message protocol identifiers.
+///
+/// Declared in §20.1.
+pub mod protocol_id {
+    /// RISC-V Platform Management Interface (RPMI) message protocol.
+    #[doc(alias = "SBI_MPXY_MSGPROTO_RPMI_ID")]
+    pub const RPMI: u32 = 0x0000_0000;
+    /// Start of vendor specific message protocol identifiers.
+    pub const VENDOR_START: u32 = 0x8000_0000;
+}
+
+/// Message channel attribute identifiers.
+///
+/// Declared in §20.3.
+pub mod attr_id {
+    /// Message protocol identifier of the channel.
+    #[doc(alias = "SBI_MPXY_ATTR_MSG_PROT_ID")]
+    pub const MSG_PROT_ID: u32 = 0x0000_0000;
+    /// Message protocol version of the channel.
+    #[doc(alias = "SBI_MPXY_ATTR_MSG_PROT_VER")]
+    pub const MSG_PROT_VERSION: u32 = 0x0000_0001;
+    /// Maximum message data size in bytes.
+    #[doc(alias = "SBI_MPXY_ATTR_MSG_MAX_LEN")]
+    pub const MSG_MAX_LEN: u32 = 0x0000_0002;
+    /// Timeout in microseconds for sending a message.
+    #[doc(alias = "SBI_MPXY_ATTR_MSG_SEND_TIMEOUT")]
+    pub const MSG_SEND_TIMEOUT: u32 = 0x0000_0003;
+    /// Timeout in microseconds for a message to complete with its response.
+    #[doc(alias = "SBI_MPXY_ATTR_MSG_COMPLETION_TIMEOUT")]
+    pub const MSG_COMPLETION_TIMEOUT: u32 = 0x0000_0004;
+    /// Channel capabilities, see [`channel_capability`](super::channel_capability).
+    #[doc(alias = "SBI_MPXY_ATTR_CHANNEL_CAPABILITY")]
+    pub const CHANNEL_CAPABILITY: u32 = 0x0000_0005;
+    /// Software event ID used for notifications of the channel.
+    #[doc(alias = "SBI_MPXY_ATTR_SSE_EVENT_ID")]
+    pub const SSE_EVENT_ID: u32 = 0x0000_0006;
+    /// Enable or disable MSI based notifications.
+    #[doc(alias = "SBI_MPXY_ATTR_MSI_CONTROL")]
+    pub const MSI_CONTROL: u32 = 0x0000_0007;
+    /// Lower 32 bits of the MSI address.
+    #[doc(alias = "SBI_MPXY_ATTR_MSI_ADDR_LO")]
+    pub const MSI_ADDR_LO: u32 = 0x0000_0008;
+    /// Upper 32 bits of the MSI address.
+    #[doc(alias = "SBI_MPXY_ATTR_MSI_ADDR_HI")]
+    pub const MSI_ADDR_HI: u32 = 0x0000_0009;
+    /// MSI data value.
+    #[doc(alias = "SBI_MPXY_ATTR_MSI_DATA")]
+    pub const MSI_DATA: u32 = 0x0000_000A;
+    /// Enable or disable reporting of notification event state.
+    #[doc(alias = "SBI_MPXY_ATTR_EVENTS_STATE_CONTROL")]
+    pub const EVENTS_STATE_CONTROL: u32 = 0x0000_000B;
+    /// Start of message protocol specific attribute identifiers.
+    #[doc(alias = "SBI_MPXY_ATTR_MSGPROTO_ATTR_START")]
+    pub const MSG_PROT_ATTR_START: u32 = 0x8000_0000;
+}
+
+/// Bits of the `CHANNEL_CAPABILITY` attribute.
+///
+/// Declared in §20.3.
+pub mod channel_capability {
+    /// The channel supports MSI based notifications.
+    pub const MSI: u32 = 1 << 0;
+    /// The channel supports SSE based notifications.
+    pub const SSE: u32 = 1 << 1;
+    /// The channel supports reporting notification event state.
+    pub const EVENTS_STATE: u32 = 1 << 2;
+    /// The channel supports sending messages with response.
+    pub const SEND_WITH_RESPONSE: u32 = 1 << 3;
+    /// The channel supports sending messages without response.
+    pub const SEND_WITHOUT_RESPONSE: u32 = 1 << 4;
+    /// The channel supports getting notification events.
+    pub const GET_NOTIFICATION_EVENTS: u32 = 1 << 5;
+}
@@ -73,3 +73,41 @@ pub mod shmem_size {
     /// Size of NACL shared memory on RV128 platforms.
     pub const RV128: usize = 4096 + 1024 * size_of::<u128>();
 }
+
+/// Offsets of areas in the scratch space of NACL shared memory.
+///
+/// Declared in §15.
+pub mod shmem_offset {
+    /// Nested SRET context, holding general purpose registers `x1` to `x31` at index 1 to 31.
+    pub const SRET: usize = 0x0000;
+    /// Nested auto-swap CSR context.
+    pub const AUTOSWAP: usize = 0x0200;
+    /// Nested HFENCE entries.
+    pub const HFENCE: usize = 0x0800;
+    /// CSR dirty bitmap, one bit for each entry in the CSR space.
+    pub const DIRTY_BITMAP: usize = 0x0F80;
+    /// CSR space, which follows the scratch space.
+    pub const CSR: usize = 0x1000;
+}
+
+/// Type of nested HFENCE entries in the `config` field.
+///
+/// Declared in §15.
+pub mod hfence_type {
+    /// HFENCE.GVMA by guest physical address.
+    pub const GVMA: usize = 0x0;
+    /// HFENCE.GVMA for all guest physical addresses.
+    pub const GVMA_ALL: usize = 0x1;
+    /// HFENCE.GVMA by guest physical address for a VMID.
+    pub const GVMA_VMID: usize = 0x2;
+    /// HFENCE.GVMA for all guest physical addresses of a VMID.
+    pub const GVMA_VMID_ALL: usize = 0x3;
+    /// HFENCE.VVMA by guest virtual address.
+    pub const VVMA: usize = 0x4;
+    /// HFENCE.VVMA for all guest virtual addresses.
+    pub const VVMA_ALL: usize = 0x5;
+    /// HFENCE.VVMA by guest virtual address for an ASID.
+    pub const VVMA_ASID: usize = 0x6;
+    /// HFENCE.VVMA for all guest virtual addresses of an ASID.
+    pub const VVMA_ASID_ALL: usize = 0x7;
+}
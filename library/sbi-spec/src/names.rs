@@ -0,0 +1,316 @@
+//! Human-readable names of SBI extensions and functions.
+//!
+//! Names follow the C language aliases in the RISC-V SBI specification with their
+//! `SBI_EXT_` prefixes removed, e.g. extension `SBI_EXT_HSM` is named `HSM`,
+//! and function `SBI_EXT_HSM_HART_START` is named `HART_START`.
+//! [`CallName`] combines both of them into forms like `HSM.HART_START` for logging.
+//!
+//! Legacy extensions share a common extension name `LEGACY`, and are available
+//! only if feature `legacy` is enabled.
+
+use crate::*;
+
+/// Returns the name of a standard SBI extension.
+///
+/// Returns `None` if `eid` is not a standard extension known by this crate.
+///
+/// # Examples
+///
+/// ```
+/// use sbi_spec::{hsm, names::extension_name};
+/// assert_eq!(extension_name(hsm::EID_HSM), Some("HSM"));
+/// assert_eq!(extension_name(0x0A00_0000), None);
+/// ```
+pub const fn extension_name(eid: usize) -> Option<&'static str> {
+    let name = match eid {
+        #[cfg(feature = "legacy")]
+        legacy::LEGACY_SET_TIMER..=legacy::LEGACY_SHUTDOWN => "LEGACY",
+        base::EID_BASE => "BASE",
+        time::EID_TIME => "TIME",
+        spi::EID_SPI => "IPI",
+        rfnc::EID_RFNC => "RFENCE",
+        hsm::EID_HSM => "HSM",
+        srst::EID_SRST => "SRST",
+        pmu::EID_PMU => "PMU",
+        dbcn::EID_DBCN => "DBCN",
+        susp::EID_SUSP => "SUSP",
+        cppc::EID_CPPC => "CPPC",
+        nacl::EID_NACL => "NACL",
+        sta::EID_STA => "STA",
+        sse::EID_SSE => "SSE",
+        fwft::EID_FWFT => "FWFT",
+        dbtr::EID_DBTR => "DBTR",
+        mpxy::EID_MPXY => "MPXY",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Returns the name of a function in a standard SBI extension.
+///
+/// Legacy extensions do not use function IDs; for them, `fid` is ignored.
+///
+/// Returns `None` if `eid` or `fid` is not a standard function known by this crate.
+///
+/// # Examples
+///
+/// ```
+/// use sbi_spec::{hsm, names::function_name};
+/// assert_eq!(function_name(hsm::EID_HSM, hsm::HART_START), Some("HART_START"));
+/// assert_eq!(function_name(hsm::EID_HSM, 0x100), None);
+/// ```
+pub const fn function_name(eid: usize, fid: usize) -> Option<&'static str> {
+    let name = match eid {
+        #[cfg(feature = "legacy")]
+        legacy::LEGACY_SET_TIMER..=legacy::LEGACY_SHUTDOWN => match eid {
+            legacy::LEGACY_SET_TIMER => "SET_TIMER",
+            legacy::LEGACY_CONSOLE_PUTCHAR => "CONSOLE_PUTCHAR",
+            legacy::LEGACY_CONSOLE_GETCHAR => "CONSOLE_GETCHAR",
+            legacy::LEGACY_CLEAR_IPI => "CLEAR_IPI",
+            legacy::LEGACY_SEND_IPI => "SEND_IPI",
+            legacy::LEGACY_REMOTE_FENCE_I => "REMOTE_FENCE_I",
+            legacy::LEGACY_REMOTE_SFENCE_VMA => "REMOTE_SFENCE_VMA",
+            legacy::LEGACY_REMOTE_SFENCE_VMA_ASID => "REMOTE_SFENCE_VMA_ASID",
+            legacy::LEGACY_SHUTDOWN => "SHUTDOWN",
+            _ => return None,
+        },
+        base::EID_BASE => match fid {
+            base::GET_SBI_SPEC_VERSION => "GET_SPEC_VERSION",
+            base::GET_SBI_IMPL_ID => "GET_IMP_ID",
+            base::GET_SBI_IMPL_VERSION => "GET_IMP_VERSION",
+            base::PROBE_EXTENSION => "PROBE_EXT",
+            base::GET_MVENDORID => "GET_MVENDORID",
+            base::GET_MARCHID => "GET_MARCHID",
+            base::GET_MIMPID => "GET_MIMPID",
+            _ => return None,
+        },
+        time::EID_TIME => match fid {
+            time::SET_TIMER => "SET_TIMER",
+            _ => return None,
+        },
+        spi::EID_SPI => match fid {
+            spi::SEND_IPI => "SEND_IPI",
+            _ => return None,
+        },
+        rfnc::EID_RFNC => match fid {
+            rfnc::REMOTE_FENCE_I => "REMOTE_FENCE_I",
+            rfnc::REMOTE_SFENCE_VMA => "REMOTE_SFENCE_VMA",
+            rfnc::REMOTE_SFENCE_VMA_ASID => "REMOTE_SFENCE_VMA_ASID",
+            rfnc::REMOTE_HFENCE_GVMA_VMID => "REMOTE_HFENCE_GVMA_VMID",
+            rfnc::REMOTE_HFENCE_GVMA => "REMOTE_HFENCE_GVMA",
+            rfnc::REMOTE_HFENCE_VVMA_ASID => "REMOTE_HFENCE_VVMA_ASID",
+            rfnc::REMOTE_HFENCE_VVMA => "REMOTE_HFENCE_VVMA",
+            _ => return None,
+        },
+        hsm::EID_HSM => match fid {
+            hsm::HART_START => "HART_START",
+            hsm::HART_STOP => "HART_STOP",
+            hsm::HART_GET_STATUS => "HART_GET_STATUS",
+            hsm::HART_SUSPEND => "HART_SUSPEND",
+            _ => return None,
+        },
+        srst::EID_SRST => match fid {
+            srst::SYSTEM_RESET => "RESET",
+            _ => return None,
+        },
+        pmu::EID_PMU => match fid {
+            pmu::NUM_COUNTERS => "NUM_COUNTERS",
+            pmu::COUNTER_GET_INFO => "COUNTER_GET_INFO",
+            pmu::COUNTER_CONFIG_MATCHING => "COUNTER_CFG_MATCH",
+            pmu::COUNTER_START => "COUNTER_START",
+            pmu::COUNTER_STOP => "COUNTER_STOP",
+            pmu::COUNTER_FW_READ => "COUNTER_FW_READ",
+            pmu::COUNTER_FW_READ_HI => "COUNTER_FW_READ_HI",
+            pmu::SNAPSHOT_SET_SHMEM => "SNAPSHOT_SET_SHMEM",
+            pmu::EVENT_GET_INFO => "EVENT_GET_INFO",
+            _ => return None,
+        },
+        dbcn::EID_DBCN => match fid {
+            dbcn::CONSOLE_WRITE => "CONSOLE_WRITE",
+            dbcn::CONSOLE_READ => "CONSOLE_READ",
+            dbcn::CONSOLE_WRITE_BYTE => "CONSOLE_WRITE_BYTE",
+            _ => return None,
+        },
+        susp::EID_SUSP => match fid {
+            susp::SUSPEND => "SUSPEND",
+            _ => return None,
+        },
+        cppc::EID_CPPC => match fid {
+            cppc::PROBE => "PROBE",
+            cppc::READ => "READ",
+            cppc::READ_HI => "READ_HI",
+            cppc::WRITE => "WRITE",
+            _ => return None,
+        },
+        nacl::EID_NACL => match fid {
+            nacl::PROBE_FEATURE => "PROBE_FEATURE",
+            nacl::SET_SHMEM => "SET_SHMEM",
+            nacl::SYNC_CSR => "SYNC_CSR",
+            nacl::SYNC_HFENCE => "SYNC_HFENCE",
+            nacl::SYNC_SRET => "SYNC_SRET",
+            _ => return None,
+        },
+        sta::EID_STA => match fid {
+            sta::SET_SHMEM => "STEAL_TIME_SET_SHMEM",
+            _ => return None,
+        },
+        sse::EID_SSE => match fid {
+            sse::READ_ATTRS => "READ_ATTR",
+            sse::WRITE_ATTRS => "WRITE_ATTR",
+            sse::REGISTER => "REGISTER",
+            sse::UNREGISTER => "UNREGISTER",
+            sse::ENABLE => "ENABLE",
+            sse::DISABLE => "DISABLE",
+            sse::COMPLETE => "COMPLETE",
+            sse::INJECT => "INJECT",
+            sse::HART_UNMASK => "HART_UNMASK",
+            sse::HART_MASK => "HART_MASK",
+            _ => return None,
+        },
+        fwft::EID_FWFT => match fid {
+            fwft::SET => "SET",
+            fwft::GET => "GET",
+            _ => return None,
+        },
+        dbtr::EID_DBTR => match fid {
+            dbtr::NUM_TRIGGERS => "NUM_TRIGGERS",
+            dbtr::SET_SHMEM => "SET_SHMEM",
+            dbtr::READ_TRIGGERS => "TRIGGER_READ",
+            dbtr::INSTALL_TRIGGERS => "TRIGGER_INSTALL",
+            dbtr::UPDATE_TRIGGERS => "TRIGGER_UPDATE",
+            dbtr::UNINSTALL_TRIGGERS => "TRIGGER_UNINSTALL",
+            dbtr::ENABLE_TRIGGERS => "TRIGGER_ENABLE",
+            dbtr::DISABLE_TRIGGERS => "TRIGGER_DISABLE",
+            _ => return None,
+        },
+        mpxy::EID_MPXY => match fid {
+            mpxy::GET_SHMEM_SIZE => "GET_SHMEM_SIZE",
+            mpxy::SET_SHMEM => "SET_SHMEM",
+            mpxy::GET_CHANNEL_IDS => "GET_CHANNEL_IDS",
+            mpxy::READ_ATTRIBUTE => "READ_ATTRS",
+            mpxy::WRITE_ATTRIBUTE => "WRITE_ATTRS",
+            mpxy::SEND_MESSAGE_WITH_RESPONSE => "SEND_MSG_WITH_RESP",
+            mpxy::SEND_MESSAGE_WITHOUT_RESPONSE => "SEND_MSG_WITHOUT_RESP",
+            mpxy::GET_NOTIFICATION_EVENTS => "GET_NOTIFICATION_EVENTS",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Displays an SBI call by names of its extension and function.
+///
+/// Known calls are displayed like `HSM.HART_START`. Unknown extension or function IDs
+/// fall back to hexadecimal numbers, e.g. `HSM.0x100` or `0x9000000.0x0`.
+///
+/// # Examples
+///
+/// ```
+/// use sbi_spec::{hsm, names::CallName};
+/// let name = CallName::new(hsm::EID_HSM, hsm::HART_START);
+/// assert_eq!(format!("{name}"), "HSM.HART_START");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallName {
+    /// Extension ID.
+    pub eid: usize,
+    /// Function ID.
+    pub fid: usize,
+}
+
+impl CallName {
+    /// Creates a call name from extension and function IDs.
+    #[inline]
+    pub const fn new(eid: usize, fid: usize) -> Self {
+        Self { eid, fid }
+    }
+}
+
+impl core::fmt::Display for CallName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match extension_name(self.eid) {
+            Some(name) => f.write_str(name)?,
+            None => write!(f, "{:#x}", self.eid)?,
+        }
+        match function_name(self.eid, self.fid) {
+            Some(name) => write!(f, ".{}", name),
+            None => write!(f, ".{:#x}", self.fid),
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for CallName {
+    fn format(&self, f: defmt::Formatter) {
+        match (extension_name(self.eid), function_name(self.eid, self.fid)) {
+            (Some(ext), Some(func)) => defmt::write!(f, "{=str}.{=str}", ext, func),
+            (Some(ext), None) => defmt::write!(f, "{=str}.{=usize:#x}", ext, self.fid),
+            _ => defmt::write!(f, "{=usize:#x}.{=usize:#x}", self.eid, self.fid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_of_extensions() {
+        assert_eq!(extension_name(base::EID_BASE), Some("BASE"));
+        assert_eq!(extension_name(spi::EID_SPI), Some("IPI"));
+        assert_eq!(extension_name(rfnc::EID_RFNC), Some("RFENCE"));
+        assert_eq!(extension_name(mpxy::EID_MPXY), Some("MPXY"));
+        assert_eq!(extension_name(0x0A00_0000), None);
+        #[cfg(feature = "legacy")]
+        assert_eq!(extension_name(legacy::LEGACY_SHUTDOWN), Some("LEGACY"));
+        #[cfg(not(feature = "legacy"))]
+        assert_eq!(extension_name(0x08), None);
+    }
+
+    #[test]
+    fn names_of_functions() {
+        assert_eq!(
+            function_name(base::EID_BASE, base::PROBE_EXTENSION),
+            Some("PROBE_EXT")
+        );
+        assert_eq!(
+            function_name(pmu::EID_PMU, pmu::SNAPSHOT_SET_SHMEM),
+            Some("SNAPSHOT_SET_SHMEM")
+        );
+        assert_eq!(function_name(fwft::EID_FWFT, fwft::GET), Some("GET"));
+        assert_eq!(function_name(fwft::EID_FWFT, 2), None);
+        assert_eq!(function_name(0x0A00_0000, 0), None);
+        #[cfg(feature = "legacy")]
+        assert_eq!(
+            function_name(legacy::LEGACY_CONSOLE_PUTCHAR, 0x1234),
+            Some("CONSOLE_PUTCHAR")
+        );
+    }
+
+    #[test]
+    fn names_cover_version_table() {
+        for f in version::FUNCTIONS {
+            assert!(function_name(f.eid, f.fid).is_some());
+        }
+        for e in version::EXTENSIONS {
+            assert!(extension_name(e.eid).is_some());
+        }
+    }
+
+    #[test]
+    fn call_name_display() {
+        extern crate alloc;
+        use alloc::string::ToString;
+
+        let name = CallName::new(hsm::EID_HSM, hsm::HART_START);
+        assert_eq!(name.to_string(), "HSM.HART_START");
+        let name = CallName::new(dbcn::EID_DBCN, dbcn::CONSOLE_WRITE_BYTE);
+        assert_eq!(name.to_string(), "DBCN.CONSOLE_WRITE_BYTE");
+        let name = CallName::new(hsm::EID_HSM, 0x100);
+        assert_eq!(name.to_string(), "HSM.0x100");
+        let name = CallName::new(0x0900_0000, 0);
+        assert_eq!(name.to_string(), "0x9000000.0x0");
+    }
+}
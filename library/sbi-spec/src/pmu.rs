@@ -204,6 +204,397 @@ pub mod firmware_event {
     pub const PLATFORM: usize = 65535;
 }
 
+/// PMU event index, the identifier of an event to be counted.
+///
+/// An event index is a 20-bit wide number encoded as follows:
+///
+/// - Bits `[19:16]`: event type, see module [`event_type`];
+/// - Bits `[15:0]`: event code, whose meaning depends on the event type.
+///
+/// For hardware cache events, the event code is further encoded by [`CacheEvent`].
+///
+/// Declared in §11.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[repr(transparent)]
+pub struct EventIdx {
+    raw: usize,
+}
+
+impl EventIdx {
+    const TYPE_SHIFT: usize = 16;
+    const TYPE_MASK: usize = 0xF;
+    const CODE_MASK: usize = 0xFFFF;
+
+    /// Converts raw `event_idx` value into an event index.
+    #[inline]
+    pub const fn from_raw(raw: usize) -> Self {
+        Self { raw }
+    }
+
+    /// Creates an event index from event type and event code.
+    #[inline]
+    pub const fn new(event_type: usize, event_code: usize) -> Self {
+        Self {
+            raw: ((event_type & Self::TYPE_MASK) << Self::TYPE_SHIFT)
+                | (event_code & Self::CODE_MASK),
+        }
+    }
+
+    /// Creates an event index for a hardware general event, see module [`hardware_event`].
+    #[inline]
+    pub const fn hardware_general(event_code: usize) -> Self {
+        Self::new(event_type::HARDWARE_GENERAL, event_code)
+    }
+
+    /// Creates an event index for a hardware cache event.
+    #[inline]
+    pub const fn hardware_cache(event: CacheEvent) -> Self {
+        Self::new(event_type::HARDWARE_CACHE, event.code())
+    }
+
+    /// Creates an event index for hardware raw events.
+    ///
+    /// The raw event value is passed to the SBI implementation separately as `event_data`.
+    #[inline]
+    pub const fn hardware_raw() -> Self {
+        Self::new(event_type::HARDWARE_RAW, 0)
+    }
+
+    /// Creates an event index for hardware raw events v2.
+    ///
+    /// The raw event value is passed to the SBI implementation separately as `event_data`.
+    #[inline]
+    pub const fn hardware_raw_v2() -> Self {
+        Self::new(event_type::HARDWARE_RAW_V2, 0)
+    }
+
+    /// Creates an event index for a firmware event, see module [`firmware_event`].
+    #[inline]
+    pub const fn firmware(event_code: usize) -> Self {
+        Self::new(event_type::FIRMWARE, event_code)
+    }
+
+    /// Returns the raw `event_idx` value.
+    #[inline]
+    pub const fn raw(self) -> usize {
+        self.raw
+    }
+
+    /// Returns the event type in bits `[19:16]`.
+    #[inline]
+    pub const fn event_type(self) -> usize {
+        (self.raw >> Self::TYPE_SHIFT) & Self::TYPE_MASK
+    }
+
+    /// Returns the event code in bits `[15:0]`.
+    #[inline]
+    pub const fn event_code(self) -> usize {
+        self.raw & Self::CODE_MASK
+    }
+
+    /// Returns the cache event structure if this is a hardware cache event.
+    #[inline]
+    pub const fn cache_event(self) -> Option<CacheEvent> {
+        if self.is_cache_event() {
+            Some(CacheEvent::from_code(self.event_code()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cache ID in bits `[15:3]`, used by hardware cache events.
+    #[inline]
+    pub const fn cache_id(self) -> usize {
+        CacheEvent::from_code(self.event_code()).cache_id()
+    }
+
+    /// Returns the cache operation ID in bits `[2:1]`, used by hardware cache events.
+    #[inline]
+    pub const fn cache_op_id(self) -> usize {
+        CacheEvent::from_code(self.event_code()).op_id()
+    }
+
+    /// Returns the cache result ID in bit `[0]`, used by hardware cache events.
+    #[inline]
+    pub const fn cache_result_id(self) -> usize {
+        CacheEvent::from_code(self.event_code()).result_id()
+    }
+
+    /// Checks if this is a hardware general event.
+    #[inline]
+    pub const fn is_general_event(self) -> bool {
+        self.event_type() == event_type::HARDWARE_GENERAL
+    }
+
+    /// Checks if this is a hardware cache event.
+    #[inline]
+    pub const fn is_cache_event(self) -> bool {
+        self.event_type() == event_type::HARDWARE_CACHE
+    }
+
+    /// Checks if this is a hardware raw event.
+    #[inline]
+    pub const fn is_raw_event_v1(self) -> bool {
+        self.event_type() == event_type::HARDWARE_RAW
+    }
+
+    /// Checks if this is a hardware raw event v2.
+    #[inline]
+    pub const fn is_raw_event_v2(self) -> bool {
+        self.event_type() == event_type::HARDWARE_RAW_V2
+    }
+
+    /// Checks if this is a hardware raw event of either version.
+    #[inline]
+    pub const fn is_raw_event(self) -> bool {
+        self.is_raw_event_v1() || self.is_raw_event_v2()
+    }
+
+    /// Checks if this is a firmware event.
+    #[inline]
+    pub const fn is_firmware_event(self) -> bool {
+        self.event_type() == event_type::FIRMWARE
+    }
+
+    /// Checks if the event type is defined, and the event code is in range of its type.
+    ///
+    /// Firmware events accept any event code, as platform specific firmware events
+    /// are defined by the SBI implementation.
+    #[inline]
+    pub const fn is_valid(self) -> bool {
+        match self.event_type() {
+            event_type::HARDWARE_GENERAL => self.event_code() <= hardware_event::REF_CPU_CYCLES,
+            event_type::HARDWARE_CACHE => CacheEvent::from_code(self.event_code()).is_valid(),
+            event_type::HARDWARE_RAW | event_type::HARDWARE_RAW_V2 => self.event_code() == 0,
+            event_type::FIRMWARE => true,
+            _ => false,
+        }
+    }
+}
+
+/// Hardware cache event, encoded in the event code of a hardware cache event index.
+///
+/// The event code is encoded as follows:
+///
+/// - Bits `[15:3]`: cache ID, see module [`cache_event`];
+/// - Bits `[2:1]`: cache operation ID, see module [`cache_operation`];
+/// - Bit `[0]`: cache result ID, see module [`cache_result`].
+///
+/// # Examples
+///
+/// ```
+/// use sbi_spec::pmu::{CacheEvent, EventIdx, cache_event, cache_operation, cache_result};
+/// let event = CacheEvent::new(cache_event::DTLB, cache_operation::READ, cache_result::MISS);
+/// assert_eq!(EventIdx::hardware_cache(event).raw(), 0x10019);
+/// ```
+///
+/// Declared in §11.2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CacheEvent {
+    cache_id: usize,
+    op_id: usize,
+    result_id: usize,
+}
+
+impl CacheEvent {
+    const CACHE_ID_MASK: usize = 0x1FFF;
+    const OP_ID_MASK: usize = 0x3;
+    const RESULT_ID_MASK: usize = 0x1;
+
+    /// Creates a hardware cache event from cache ID, operation ID and result ID.
+    ///
+    /// Values out of range of their bit fields are truncated.
+    #[inline]
+    pub const fn new(cache_id: usize, op_id: usize, result_id: usize) -> Self {
+        Self {
+            cache_id: cache_id & Self::CACHE_ID_MASK,
+            op_id: op_id & Self::OP_ID_MASK,
+            result_id: result_id & Self::RESULT_ID_MASK,
+        }
+    }
+
+    /// Decodes a hardware cache event from the event code.
+    #[inline]
+    pub const fn from_code(event_code: usize) -> Self {
+        Self::new(event_code >> 3, event_code >> 1, event_code)
+    }
+
+    /// Returns a copy of this event with the given cache ID.
+    #[inline]
+    pub const fn with_cache_id(self, cache_id: usize) -> Self {
+        Self::new(cache_id, self.op_id, self.result_id)
+    }
+
+    /// Returns a copy of this event with the given cache operation ID.
+    #[inline]
+    pub const fn with_op_id(self, op_id: usize) -> Self {
+        Self::new(self.cache_id, op_id, self.result_id)
+    }
+
+    /// Returns a copy of this event with the given cache result ID.
+    #[inline]
+    pub const fn with_result_id(self, result_id: usize) -> Self {
+        Self::new(self.cache_id, self.op_id, result_id)
+    }
+
+    /// Returns the encoded event code.
+    #[inline]
+    pub const fn code(self) -> usize {
+        (self.cache_id << 3) | (self.op_id << 1) | self.result_id
+    }
+
+    /// Returns the cache ID.
+    #[inline]
+    pub const fn cache_id(self) -> usize {
+        self.cache_id
+    }
+
+    /// Returns the cache operation ID.
+    #[inline]
+    pub const fn op_id(self) -> usize {
+        self.op_id
+    }
+
+    /// Returns the cache result ID.
+    #[inline]
+    pub const fn result_id(self) -> usize {
+        self.result_id
+    }
+
+    /// Checks if cache ID and operation ID are defined by the RISC-V SBI specification.
+    #[inline]
+    pub const fn is_valid(self) -> bool {
+        self.cache_id <= cache_event::NODE && self.op_id <= cache_operation::PREFETCH
+    }
+}
+
+/// Type of a PMU counter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CounterType {
+    /// Hardware counter, readable through its CSR.
+    Hardware,
+    /// Firmware counter, readable through SBI call `COUNTER_FW_READ`.
+    Firmware,
+}
+
+/// Counter information returned by `COUNTER_GET_INFO`.
+///
+/// The counter information is encoded as follows:
+///
+/// - Bits `[11:0]`: CSR number of a hardware counter;
+/// - Bits `[17:12]`: width of a hardware counter, one less than its number of bits;
+/// - Bit `[XLEN-1]`: counter type, `0` for hardware counters and `1` for firmware counters.
+///
+/// Other bits are reserved. CSR number and width are only meaningful for hardware counters.
+///
+/// Declared in §11.7.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[repr(transparent)]
+pub struct CounterInfo {
+    raw: usize,
+}
+
+impl CounterInfo {
+    const CSR_MASK: usize = 0xFFF;
+    const WIDTH_SHIFT: usize = 12;
+    const WIDTH_MASK: usize = 0x3F;
+    const FIRMWARE_FLAG: usize = 1 << (usize::BITS - 1);
+
+    /// Converts raw counter information value into the structure.
+    #[inline]
+    pub const fn from_raw(raw: usize) -> Self {
+        Self { raw }
+    }
+
+    /// Creates counter information for a hardware counter.
+    ///
+    /// `width` is one less than the number of bits in the counter CSR, e.g. `63` for
+    /// a 64-bit counter.
+    #[inline]
+    pub const fn hardware(csr: u16, width: u8) -> Self {
+        Self {
+            raw: ((csr as usize) & Self::CSR_MASK)
+                | (((width as usize) & Self::WIDTH_MASK) << Self::WIDTH_SHIFT),
+        }
+    }
+
+    /// Creates counter information for a firmware counter.
+    #[inline]
+    pub const fn firmware() -> Self {
+        Self {
+            raw: Self::FIRMWARE_FLAG,
+        }
+    }
+
+    /// Returns the raw counter information value.
+    #[inline]
+    pub const fn raw(self) -> usize {
+        self.raw
+    }
+
+    /// Returns the CSR number of the hardware counter.
+    #[inline]
+    pub const fn csr(self) -> u16 {
+        (self.raw & Self::CSR_MASK) as u16
+    }
+
+    /// Returns the width of the hardware counter, i.e. one less than its number of bits.
+    #[inline]
+    pub const fn width(self) -> u8 {
+        ((self.raw >> Self::WIDTH_SHIFT) & Self::WIDTH_MASK) as u8
+    }
+
+    /// Returns the type of the counter.
+    #[inline]
+    pub const fn counter_type(self) -> CounterType {
+        if self.is_firmware() {
+            CounterType::Firmware
+        } else {
+            CounterType::Hardware
+        }
+    }
+
+    /// Checks if the counter is a firmware counter.
+    #[inline]
+    pub const fn is_firmware(self) -> bool {
+        self.raw & Self::FIRMWARE_FLAG != 0
+    }
+
+    /// Checks if the counter is a hardware counter.
+    #[inline]
+    pub const fn is_hardware(self) -> bool {
+        !self.is_firmware()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for EventIdx {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "EventIdx({=usize:#x})", self.raw)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for CounterInfo {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "CounterInfo({=usize:#x})", self.raw)
+    }
+}
+
 /// Size of shared memory on PMU extension set by supervisor software for current hart.
 pub mod shmem_size {
     /// Size of PMU snapshot shared memory.
@@ -222,6 +613,7 @@ pub mod flags {
 
     bitflags! {
         #[derive(Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         /// Declared in Table 37.
         pub struct CounterCfgFlags: usize {
             /// Skip the counter matching.
@@ -245,6 +637,7 @@ pub mod flags {
 
     bitflags! {
         #[derive(Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         /// Declared in Table 39.
         pub struct CounterStartFlags: usize {
             /// Set the value of counters based on the initial_value parameter.
@@ -256,6 +649,7 @@ pub mod flags {
 
     bitflags! {
         #[derive(Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         /// Declared in Table 41.
         pub struct CounterStopFlags: usize {
             /// Reset the counter to event mapping.
@@ -264,4 +658,93 @@ pub mod flags {
             const TAKE_SNAPSHOT = 1 << 1;
         }
     }
+
+    crate::impl_defmt_for_flags!(CounterCfgFlags, CounterStartFlags, CounterStopFlags);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_idx_round_trip() {
+        let event = EventIdx::hardware_general(hardware_event::INSTRUCTIONS);
+        assert_eq!(event.raw(), 0x2);
+        assert!(event.is_general_event());
+        assert_eq!(EventIdx::from_raw(event.raw()), event);
+
+        let event = EventIdx::firmware(firmware_event::IPI_SENT);
+        assert_eq!(event.raw(), 0xf0006);
+        assert!(event.is_firmware_event());
+        assert_eq!(event.event_type(), event_type::FIRMWARE);
+        assert_eq!(event.event_code(), firmware_event::IPI_SENT);
+
+        let event = EventIdx::new(event_type::HARDWARE_RAW_V2, 0);
+        assert_eq!(event, EventIdx::hardware_raw_v2());
+        assert!(event.is_raw_event() && event.is_raw_event_v2() && !event.is_raw_event_v1());
+        assert!(EventIdx::hardware_raw().is_raw_event_v1());
+
+        for raw in 0..(1 << 20) {
+            let event = EventIdx::from_raw(raw);
+            assert_eq!(EventIdx::new(event.event_type(), event.event_code()), event);
+        }
+    }
+
+    #[test]
+    fn cache_event_round_trip() {
+        let dtlb_read_miss =
+            CacheEvent::new(cache_event::DTLB, cache_operation::READ, cache_result::MISS);
+        let event = EventIdx::hardware_cache(dtlb_read_miss);
+        assert_eq!(event.raw(), 0x10019);
+        assert_eq!(event.cache_event(), Some(dtlb_read_miss));
+        assert_eq!(event.cache_id(), cache_event::DTLB);
+        assert_eq!(event.cache_op_id(), cache_operation::READ);
+        assert_eq!(event.cache_result_id(), cache_result::MISS);
+
+        let itlb_read_miss = dtlb_read_miss.with_cache_id(cache_event::ITLB);
+        assert_eq!(EventIdx::hardware_cache(itlb_read_miss).raw(), 0x10021);
+        let dtlb_write_miss = dtlb_read_miss.with_op_id(cache_operation::WRITE);
+        assert_eq!(EventIdx::hardware_cache(dtlb_write_miss).raw(), 0x1001b);
+        let dtlb_write_access = dtlb_write_miss.with_result_id(cache_result::ACCESS);
+        assert_eq!(dtlb_write_access.code(), 0x1a);
+
+        for code in 0..=0xFFFF {
+            assert_eq!(CacheEvent::from_code(code).code(), code);
+        }
+        assert_eq!(EventIdx::hardware_general(1).cache_event(), None);
+    }
+
+    #[test]
+    fn event_idx_validity() {
+        assert!(EventIdx::hardware_general(hardware_event::REF_CPU_CYCLES).is_valid());
+        assert!(!EventIdx::hardware_general(hardware_event::REF_CPU_CYCLES + 1).is_valid());
+        let l1d = CacheEvent::new(cache_event::L1D, cache_operation::PREFETCH, 0);
+        assert!(EventIdx::hardware_cache(l1d).is_valid());
+        assert!(!EventIdx::hardware_cache(l1d.with_cache_id(cache_event::NODE + 1)).is_valid());
+        assert!(!EventIdx::hardware_cache(l1d.with_op_id(3)).is_valid());
+        assert!(EventIdx::hardware_raw().is_valid());
+        assert!(!EventIdx::new(event_type::HARDWARE_RAW, 1).is_valid());
+        assert!(EventIdx::firmware(firmware_event::PLATFORM).is_valid());
+        assert!(!EventIdx::new(4, 0).is_valid());
+    }
+
+    #[test]
+    fn counter_info_round_trip() {
+        let info = CounterInfo::hardware(0xC00, 63);
+        assert_eq!(info.raw(), 0x3FC00);
+        assert_eq!(info.csr(), 0xC00);
+        assert_eq!(info.width(), 63);
+        assert!(info.is_hardware());
+        assert_eq!(info.counter_type(), CounterType::Hardware);
+        assert_eq!(CounterInfo::from_raw(info.raw()), info);
+
+        let info = CounterInfo::firmware();
+        assert_eq!(info.raw(), 1 << (usize::BITS - 1));
+        assert!(info.is_firmware());
+        assert_eq!(info.counter_type(), CounterType::Firmware);
+
+        let info = CounterInfo::hardware(0x1FFF, 0xFF);
+        assert_eq!(info.csr(), 0xFFF);
+        assert_eq!(info.width(), 0x3F);
+    }
 }
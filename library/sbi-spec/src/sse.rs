@@ -58,3 +58,103 @@ mod fid {
     #[doc(alias = "SBI_EXT_SSE_HART_MASK")]
     pub const HART_MASK: usize = 9;
 }
+
+/// Software event identifiers.
+///
+/// Declared in Table 84 at §17.1.
+pub mod event_id {
+    /// Local high priority RAS event.
+    #[doc(alias = "SBI_SSE_EVENT_LOCAL_HIGH_PRIO_RAS")]
+    pub const LOCAL_HIGH_PRIO_RAS: u32 = 0x0000_0000;
+    /// Local double trap event.
+    #[doc(alias = "SBI_SSE_EVENT_LOCAL_DOUBLE_TRAP")]
+    pub const LOCAL_DOUBLE_TRAP: u32 = 0x0000_0001;
+    /// Global high priority RAS event.
+    #[doc(alias = "SBI_SSE_EVENT_GLOBAL_HIGH_PRIO_RAS")]
+    pub const GLOBAL_HIGH_PRIO_RAS: u32 = 0x0000_8000;
+    /// Local PMU overflow event.
+    #[doc(alias = "SBI_SSE_EVENT_LOCAL_PMU_OVERFLOW")]
+    pub const LOCAL_PMU_OVERFLOW: u32 = 0x0001_0000;
+    /// Local low priority RAS event.
+    #[doc(alias = "SBI_SSE_EVENT_LOCAL_LOW_PRIO_RAS")]
+    pub const LOCAL_LOW_PRIO_RAS: u32 = 0x0010_0000;
+    /// Global low priority RAS event.
+    #[doc(alias = "SBI_SSE_EVENT_GLOBAL_LOW_PRIO_RAS")]
+    pub const GLOBAL_LOW_PRIO_RAS: u32 = 0x0010_8000;
+    /// Local software injected event.
+    #[doc(alias = "SBI_SSE_EVENT_LOCAL_SOFTWARE_INJECTED")]
+    pub const LOCAL_SOFTWARE_INJECTED: u32 = 0xffff_0000;
+    /// Global software injected event.
+    #[doc(alias = "SBI_SSE_EVENT_GLOBAL_SOFTWARE_INJECTED")]
+    pub const GLOBAL_SOFTWARE_INJECTED: u32 = 0xffff_8000;
+
+    /// Bit in event ID indicating a global event.
+    pub const GLOBAL_BIT: u32 = 1 << 15;
+    /// Bit in event ID indicating a platform specific event.
+    pub const PLATFORM_BIT: u32 = 1 << 14;
+}
+
+/// Software event attribute identifiers.
+///
+/// Declared in Table 86 at §17.4.
+pub mod attr_id {
+    /// Status of the software event, read-only.
+    #[doc(alias = "SBI_SSE_ATTR_STATUS")]
+    pub const STATUS: u32 = 0x0000_0000;
+    /// Software event priority.
+    #[doc(alias = "SBI_SSE_ATTR_PRIORITY")]
+    pub const PRIORITY: u32 = 0x0000_0001;
+    /// Additional configuration of the software event.
+    #[doc(alias = "SBI_SSE_ATTR_CONFIG")]
+    pub const CONFIG: u32 = 0x0000_0002;
+    /// Hart ID of the preferred hart for global events.
+    #[doc(alias = "SBI_SSE_ATTR_PREFERRED_HART")]
+    pub const PREFERRED_HART: u32 = 0x0000_0003;
+    /// Entry program counter value for handling the software event, read-only.
+    #[doc(alias = "SBI_SSE_ATTR_ENTRY_PC")]
+    pub const ENTRY_PC: u32 = 0x0000_0004;
+    /// Entry argument value for handling the software event, read-only.
+    #[doc(alias = "SBI_SSE_ATTR_ENTRY_ARG")]
+    pub const ENTRY_ARG: u32 = 0x0000_0005;
+    /// Interrupted `sepc` CSR value.
+    #[doc(alias = "SBI_SSE_ATTR_INTERRUPTED_SEPC")]
+    pub const INTERRUPTED_SEPC: u32 = 0x0000_0006;
+    /// Interrupted flags, see [`InterruptedFlags`](super::flags::InterruptedFlags).
+    #[doc(alias = "SBI_SSE_ATTR_INTERRUPTED_FLAGS")]
+    pub const INTERRUPTED_FLAGS: u32 = 0x0000_0007;
+    /// Interrupted `A6` GPR value.
+    #[doc(alias = "SBI_SSE_ATTR_INTERRUPTED_A6")]
+    pub const INTERRUPTED_A6: u32 = 0x0000_0008;
+    /// Interrupted `A7` GPR value.
+    #[doc(alias = "SBI_SSE_ATTR_INTERRUPTED_A7")]
+    pub const INTERRUPTED_A7: u32 = 0x0000_0009;
+}
+
+/// Software event attribute flags.
+///
+/// Declared in §17.4.
+pub mod flags {
+    use bitflags::bitflags;
+
+    bitflags! {
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        /// Value of the `INTERRUPTED_FLAGS` event attribute.
+        pub struct InterruptedFlags: usize {
+            /// Interrupted `sstatus.SPP` CSR bit value.
+            const SSTATUS_SPP = 1 << 0;
+            /// Interrupted `sstatus.SPIE` CSR bit value.
+            const SSTATUS_SPIE = 1 << 1;
+            /// Interrupted `hstatus.SPV` CSR bit value.
+            const HSTATUS_SPV = 1 << 2;
+            /// Interrupted `hstatus.SPVP` CSR bit value.
+            const HSTATUS_SPVP = 1 << 3;
+            /// Interrupted `sstatus.SPELP` CSR bit value.
+            const SSTATUS_SPELP = 1 << 4;
+            /// Interrupted `sstatus.SDT` CSR bit value.
+            const SSTATUS_SDT = 1 << 5;
+        }
+    }
+
+    crate::impl_defmt_for_flags!(InterruptedFlags);
+}
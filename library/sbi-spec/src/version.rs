@@ -0,0 +1,347 @@
+//! SBI specification versions in which extensions and functions are introduced.
+//!
+//! An SBI implementation advertises its specification version through
+//! [`GET_SBI_SPEC_VERSION`](crate::base::GET_SBI_SPEC_VERSION). Tables in this module
+//! record the minimum [`Version`] required by every standard extension and function,
+//! so that implementations can refuse calls above the version they advertise, and
+//! supervisors can skip functions the current environment does not provide.
+//!
+//! Extensions or functions not defined by the RISC-V SBI specification (e.g. vendor
+//! or firmware specific extensions) are absent from these tables.
+
+use crate::base::Version;
+
+/// Minimum SBI specification version of a standard extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExtensionVersion {
+    /// Extension ID.
+    pub eid: usize,
+    /// Version in which the extension is introduced.
+    pub since: Version,
+}
+
+/// Minimum SBI specification version of a standard function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FunctionVersion {
+    /// Extension ID.
+    pub eid: usize,
+    /// Function ID.
+    pub fid: usize,
+    /// Version in which the function is introduced.
+    pub since: Version,
+}
+
+const fn ext(eid: usize, since: Version) -> ExtensionVersion {
+    ExtensionVersion { eid, since }
+}
+
+const fn func(eid: usize, fid: usize, since: Version) -> FunctionVersion {
+    FunctionVersion { eid, fid, since }
+}
+
+/// Minimum specification versions of all standard extensions.
+///
+/// Legacy extensions are included only if feature `legacy` is enabled.
+pub const EXTENSIONS: &[ExtensionVersion] = {
+    use crate::*;
+    &[
+        #[cfg(feature = "legacy")]
+        ext(legacy::LEGACY_SET_TIMER, Version::V0_1),
+        #[cfg(feature = "legacy")]
+        ext(legacy::LEGACY_CONSOLE_PUTCHAR, Version::V0_1),
+        #[cfg(feature = "legacy")]
+        ext(legacy::LEGACY_CONSOLE_GETCHAR, Version::V0_1),
+        #[cfg(feature = "legacy")]
+        ext(legacy::LEGACY_CLEAR_IPI, Version::V0_1),
+        #[cfg(feature = "legacy")]
+        ext(legacy::LEGACY_SEND_IPI, Version::V0_1),
+        #[cfg(feature = "legacy")]
+        ext(legacy::LEGACY_REMOTE_FENCE_I, Version::V0_1),
+        #[cfg(feature = "legacy")]
+        ext(legacy::LEGACY_REMOTE_SFENCE_VMA, Version::V0_1),
+        #[cfg(feature = "legacy")]
+        ext(legacy::LEGACY_REMOTE_SFENCE_VMA_ASID, Version::V0_1),
+        #[cfg(feature = "legacy")]
+        ext(legacy::LEGACY_SHUTDOWN, Version::V0_1),
+        ext(base::EID_BASE, Version::V0_2),
+        ext(time::EID_TIME, Version::V0_2),
+        ext(spi::EID_SPI, Version::V0_2),
+        ext(rfnc::EID_RFNC, Version::V0_2),
+        ext(hsm::EID_HSM, Version::V0_2),
+        ext(srst::EID_SRST, Version::V0_3),
+        ext(pmu::EID_PMU, Version::V0_3),
+        ext(dbcn::EID_DBCN, Version::V2_0),
+        ext(susp::EID_SUSP, Version::V2_0),
+        ext(cppc::EID_CPPC, Version::V2_0),
+        ext(nacl::EID_NACL, Version::V2_0),
+        ext(sta::EID_STA, Version::V2_0),
+        ext(sse::EID_SSE, Version::V3_0),
+        ext(fwft::EID_FWFT, Version::V3_0),
+        ext(dbtr::EID_DBTR, Version::V3_0),
+        ext(mpxy::EID_MPXY, Version::V3_0),
+    ]
+};
+
+/// Minimum specification versions of all standard functions.
+///
+/// Legacy extensions do not use function IDs, thus they are not listed in this table.
+pub const FUNCTIONS: &[FunctionVersion] = {
+    use crate::*;
+    &[
+        // §4
+        func(base::EID_BASE, base::GET_SBI_SPEC_VERSION, Version::V0_2),
+        func(base::EID_BASE, base::GET_SBI_IMPL_ID, Version::V0_2),
+        func(base::EID_BASE, base::GET_SBI_IMPL_VERSION, Version::V0_2),
+        func(base::EID_BASE, base::PROBE_EXTENSION, Version::V0_2),
+        func(base::EID_BASE, base::GET_MVENDORID, Version::V0_2),
+        func(base::EID_BASE, base::GET_MARCHID, Version::V0_2),
+        func(base::EID_BASE, base::GET_MIMPID, Version::V0_2),
+        // §6
+        func(time::EID_TIME, time::SET_TIMER, Version::V0_2),
+        // §7
+        func(spi::EID_SPI, spi::SEND_IPI, Version::V0_2),
+        // §8
+        func(rfnc::EID_RFNC, rfnc::REMOTE_FENCE_I, Version::V0_2),
+        func(rfnc::EID_RFNC, rfnc::REMOTE_SFENCE_VMA, Version::V0_2),
+        func(rfnc::EID_RFNC, rfnc::REMOTE_SFENCE_VMA_ASID, Version::V0_2),
+        func(rfnc::EID_RFNC, rfnc::REMOTE_HFENCE_GVMA_VMID, Version::V0_2),
+        func(rfnc::EID_RFNC, rfnc::REMOTE_HFENCE_GVMA, Version::V0_2),
+        func(rfnc::EID_RFNC, rfnc::REMOTE_HFENCE_VVMA_ASID, Version::V0_2),
+        func(rfnc::EID_RFNC, rfnc::REMOTE_HFENCE_VVMA, Version::V0_2),
+        // §9
+        func(hsm::EID_HSM, hsm::HART_START, Version::V0_2),
+        func(hsm::EID_HSM, hsm::HART_STOP, Version::V0_2),
+        func(hsm::EID_HSM, hsm::HART_GET_STATUS, Version::V0_2),
+        func(hsm::EID_HSM, hsm::HART_SUSPEND, Version::V0_3),
+        // §10
+        func(srst::EID_SRST, srst::SYSTEM_RESET, Version::V0_3),
+        // §11
+        func(pmu::EID_PMU, pmu::NUM_COUNTERS, Version::V0_3),
+        func(pmu::EID_PMU, pmu::COUNTER_GET_INFO, Version::V0_3),
+        func(pmu::EID_PMU, pmu::COUNTER_CONFIG_MATCHING, Version::V0_3),
+        func(pmu::EID_PMU, pmu::COUNTER_START, Version::V0_3),
+        func(pmu::EID_PMU, pmu::COUNTER_STOP, Version::V0_3),
+        func(pmu::EID_PMU, pmu::COUNTER_FW_READ, Version::V0_3),
+        func(pmu::EID_PMU, pmu::COUNTER_FW_READ_HI, Version::V2_0),
+        func(pmu::EID_PMU, pmu::SNAPSHOT_SET_SHMEM, Version::V2_0),
+        func(pmu::EID_PMU, pmu::EVENT_GET_INFO, Version::V3_0),
+        // §12
+        func(dbcn::EID_DBCN, dbcn::CONSOLE_WRITE, Version::V2_0),
+        func(dbcn::EID_DBCN, dbcn::CONSOLE_READ, Version::V2_0),
+        func(dbcn::EID_DBCN, dbcn::CONSOLE_WRITE_BYTE, Version::V2_0),
+        // §13
+        func(susp::EID_SUSP, susp::SUSPEND, Version::V2_0),
+        // §14
+        func(cppc::EID_CPPC, cppc::PROBE, Version::V2_0),
+        func(cppc::EID_CPPC, cppc::READ, Version::V2_0),
+        func(cppc::EID_CPPC, cppc::READ_HI, Version::V2_0),
+        func(cppc::EID_CPPC, cppc::WRITE, Version::V2_0),
+        // §15
+        func(nacl::EID_NACL, nacl::PROBE_FEATURE, Version::V2_0),
+        func(nacl::EID_NACL, nacl::SET_SHMEM, Version::V2_0),
+        func(nacl::EID_NACL, nacl::SYNC_CSR, Version::V2_0),
+        func(nacl::EID_NACL, nacl::SYNC_HFENCE, Version::V2_0),
+        func(nacl::EID_NACL, nacl::SYNC_SRET, Version::V2_0),
+        // §16
+        func(sta::EID_STA, sta::SET_SHMEM, Version::V2_0),
+        // §17
+        func(sse::EID_SSE, sse::READ_ATTRS, Version::V3_0),
+        func(sse::EID_SSE, sse::WRITE_ATTRS, Version::V3_0),
+        func(sse::EID_SSE, sse::REGISTER, Version::V3_0),
+        func(sse::EID_SSE, sse::UNREGISTER, Version::V3_0),
+        func(sse::EID_SSE, sse::ENABLE, Version::V3_0),
+        func(sse::EID_SSE, sse::DISABLE, Version::V3_0),
+        func(sse::EID_SSE, sse::COMPLETE, Version::V3_0),
+        func(sse::EID_SSE, sse::INJECT, Version::V3_0),
+        func(sse::EID_SSE, sse::HART_UNMASK, Version::V3_0),
+        func(sse::EID_SSE, sse::HART_MASK, Version::V3_0),
+        // §18
+        func(fwft::EID_FWFT, fwft::SET, Version::V3_0),
+        func(fwft::EID_FWFT, fwft::GET, Version::V3_0),
+        // §19
+        func(dbtr::EID_DBTR, dbtr::NUM_TRIGGERS, Version::V3_0),
+        func(dbtr::EID_DBTR, dbtr::SET_SHMEM, Version::V3_0),
+        func(dbtr::EID_DBTR, dbtr::READ_TRIGGERS, Version::V3_0),
+        func(dbtr::EID_DBTR, dbtr::INSTALL_TRIGGERS, Version::V3_0),
+        func(dbtr::EID_DBTR, dbtr::UPDATE_TRIGGERS, Version::V3_0),
+        func(dbtr::EID_DBTR, dbtr::UNINSTALL_TRIGGERS, Version::V3_0),
+        func(dbtr::EID_DBTR, dbtr::ENABLE_TRIGGERS, Version::V3_0),
+        func(dbtr::EID_DBTR, dbtr::DISABLE_TRIGGERS, Version::V3_0),
+        // §20
+        func(mpxy::EID_MPXY, mpxy::GET_SHMEM_SIZE, Version::V3_0),
+        func(mpxy::EID_MPXY, mpxy::SET_SHMEM, Version::V3_0),
+        func(mpxy::EID_MPXY, mpxy::GET_CHANNEL_IDS, Version::V3_0),
+        func(mpxy::EID_MPXY, mpxy::READ_ATTRIBUTE, Version::V3_0),
+        func(mpxy::EID_MPXY, mpxy::WRITE_ATTRIBUTE, Version::V3_0),
+        func(
+            mpxy::EID_MPXY,
+            mpxy::SEND_MESSAGE_WITH_RESPONSE,
+            Version::V3_0,
+        ),
+        func(
+            mpxy::EID_MPXY,
+            mpxy::SEND_MESSAGE_WITHOUT_RESPONSE,
+            Version::V3_0,
+        ),
+        func(mpxy::EID_MPXY, mpxy::GET_NOTIFICATION_EVENTS, Version::V3_0),
+    ]
+};
+
+/// Returns the specification version in which the given extension is introduced.
+///
+/// Returns `None` if `eid` is not a standard extension known by this crate.
+#[inline]
+pub const fn extension_since(eid: usize) -> Option<Version> {
+    let mut i = 0;
+    while i < EXTENSIONS.len() {
+        if EXTENSIONS[i].eid == eid {
+            return Some(EXTENSIONS[i].since);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Returns the specification version in which the given function is introduced.
+///
+/// Legacy extensions do not distinguish function IDs; for them, the version of the
+/// extension itself is returned regardless of `fid`.
+///
+/// Returns `None` if `eid` or `fid` is not a standard function known by this crate.
+#[inline]
+pub const fn function_since(eid: usize, fid: usize) -> Option<Version> {
+    let mut i = 0;
+    while i < FUNCTIONS.len() {
+        if FUNCTIONS[i].eid == eid && FUNCTIONS[i].fid == fid {
+            return Some(FUNCTIONS[i].since);
+        }
+        i += 1;
+    }
+    if is_legacy(eid) {
+        return extension_since(eid);
+    }
+    None
+}
+
+/// Checks if the given function is provided under specification version `version`.
+///
+/// Returns `None` if the function is not a standard function known by this crate;
+/// in this case, whether it is available can only be determined by probing.
+#[inline]
+pub const fn is_function_supported(version: Version, eid: usize, fid: usize) -> Option<bool> {
+    match function_since(eid, fid) {
+        Some(since) => Some(!is_lower(version, since)),
+        None => None,
+    }
+}
+
+/// Checks if the given extension is provided under specification version `version`.
+///
+/// Returns `None` if the extension is not a standard extension known by this crate.
+#[inline]
+pub const fn is_extension_supported(version: Version, eid: usize) -> Option<bool> {
+    match extension_since(eid) {
+        Some(since) => Some(!is_lower(version, since)),
+        None => None,
+    }
+}
+
+#[inline]
+const fn is_legacy(eid: usize) -> bool {
+    eid <= 0x0F
+}
+
+// `Ord` methods are not usable in const context.
+#[inline]
+const fn is_lower(a: Version, b: Version) -> bool {
+    a.major() < b.major() || (a.major() == b.major() && a.minor() < b.minor())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{base::Version, dbcn, hsm, mpxy, pmu, sse, time};
+
+    #[test]
+    fn extension_versions() {
+        assert_eq!(extension_since(time::EID_TIME), Some(Version::V0_2));
+        assert_eq!(extension_since(dbcn::EID_DBCN), Some(Version::V2_0));
+        assert_eq!(extension_since(sse::EID_SSE), Some(Version::V3_0));
+        assert_eq!(extension_since(0x0900_0000), None);
+        #[cfg(feature = "legacy")]
+        assert_eq!(
+            extension_since(crate::legacy::LEGACY_SET_TIMER),
+            Some(Version::V0_1)
+        );
+        #[cfg(not(feature = "legacy"))]
+        assert_eq!(extension_since(0), None);
+    }
+
+    #[test]
+    fn function_versions() {
+        assert_eq!(
+            function_since(pmu::EID_PMU, pmu::COUNTER_START),
+            Some(Version::V0_3)
+        );
+        assert_eq!(
+            function_since(pmu::EID_PMU, pmu::SNAPSHOT_SET_SHMEM),
+            Some(Version::V2_0)
+        );
+        assert_eq!(
+            function_since(pmu::EID_PMU, pmu::EVENT_GET_INFO),
+            Some(Version::V3_0)
+        );
+        assert_eq!(
+            function_since(hsm::EID_HSM, hsm::HART_SUSPEND),
+            Some(Version::V0_3)
+        );
+        assert_eq!(function_since(pmu::EID_PMU, 0x100), None);
+        assert_eq!(function_since(0x0900_0000, 0), None);
+    }
+
+    #[test]
+    fn function_supported() {
+        let eid = mpxy::EID_MPXY;
+        let fid = mpxy::SEND_MESSAGE_WITH_RESPONSE;
+        assert_eq!(is_function_supported(Version::V2_0, eid, fid), Some(false));
+        assert_eq!(is_function_supported(Version::V3_0, eid, fid), Some(true));
+        assert_eq!(
+            is_function_supported(Version::from_raw(0x0300_0001), eid, fid),
+            Some(true)
+        );
+        assert_eq!(is_function_supported(Version::V3_0, eid, 0x100), None);
+        assert_eq!(
+            is_extension_supported(Version::V1_0, pmu::EID_PMU),
+            Some(true)
+        );
+        assert_eq!(
+            is_extension_supported(Version::V1_0, sse::EID_SSE),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn tables_consistent() {
+        // every function is introduced no earlier than its extension.
+        for f in FUNCTIONS {
+            let since = extension_since(f.eid).expect("function of unknown extension");
+            assert!(
+                f.since >= since,
+                "{:x}.{:x} older than its extension",
+                f.eid,
+                f.fid
+            );
+        }
+        // no duplicated entries.
+        for (i, a) in FUNCTIONS.iter().enumerate() {
+            for b in &FUNCTIONS[i + 1..] {
+                assert!(a.eid != b.eid || a.fid != b.fid);
+            }
+        }
+        for (i, a) in EXTENSIONS.iter().enumerate() {
+            for b in &EXTENSIONS[i + 1..] {
+                assert_ne!(a.eid, b.eid);
+            }
+        }
+    }
+}
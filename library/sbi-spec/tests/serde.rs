@@ -0,0 +1,20 @@
+//! Serialization of SBI structures with the `serde` feature.
+//!
+//! Kept out of unit tests, for `serde_json` implements `PartialEq<Value>` for integers
+//! and would break type inference of integer comparisons in the library.
+#![cfg(feature = "serde")]
+
+use sbi_spec::binary::{Error, SbiRet};
+
+#[test]
+fn sbi_ret_serde() {
+    let ret = SbiRet::success(0x10);
+    let json = serde_json::to_string(&ret).unwrap();
+    assert_eq!(json, r#"{"error":0,"value":16}"#);
+    assert_eq!(serde_json::from_str::<SbiRet>(&json).unwrap(), ret);
+
+    let err = Error::<usize>::DeniedLocked;
+    let json = serde_json::to_string(&err).unwrap();
+    assert_eq!(json, r#""DeniedLocked""#);
+    assert_eq!(serde_json::from_str::<Error>(&json).unwrap(), err);
+}
@@ -0,0 +1,50 @@
+use std::{env, path::PathBuf};
+
+fn main() {
+    // 仅独立测试镜像需要链接脚本
+    if env::var_os("CARGO_FEATURE_STANDALONE").is_none() {
+        return;
+    }
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    let ld = &out.join("sbi-testing.ld");
+
+    std::fs::write(ld, LINKER_SCRIPT).unwrap();
+
+    println!("cargo:rustc-link-arg-bins=-T{}", ld.display());
+    println!("cargo:rustc-link-search={}", out.display());
+}
+
+const LINKER_SCRIPT: &[u8] = b"OUTPUT_ARCH(riscv)
+ENTRY(_start)
+SECTIONS {
+    . = 0x80200000;
+    istart = .;
+    .head.text : ALIGN(8) {
+        KEEP(*(.head.text))
+    }
+    .text : ALIGN(8) {
+        *(.text.entry)
+        *(.text .text.*)
+    }
+    .rodata : ALIGN(8) {
+        *(.rodata .rodata.*)
+        *(.srodata .srodata.*)
+    }
+    .data : ALIGN(8) {
+        *(.data .data.*)
+        *(.sdata .sdata.*)
+    }
+    .bss (NOLOAD) : ALIGN(8) {
+        *(.bss.uninit)
+        . = ALIGN(8);
+        sbss = .;
+        *(.bss .bss.*)
+        *(.sbss .sbss.*)
+        . = ALIGN(8);
+        ebss = .;
+    }
+    iend = .;
+    /DISCARD/ : {
+        *(.eh_frame)
+    }
+}";
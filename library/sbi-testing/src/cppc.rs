@@ -0,0 +1,146 @@
+//! CPPC extension test suite.
+
+use sbi::SbiRet;
+
+/// CPPC extension test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for CPPC extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for a register is implemented, and its value.
+    Register {
+        /// The CPPC register ID.
+        reg_id: u32,
+        /// Width of the register in bits.
+        width: usize,
+        /// Value read from the register.
+        value: u64,
+    },
+    /// Test process for a register is not implemented.
+    RegisterNotImplemented(u32),
+    /// Test failed for probing a standard register returned an error.
+    ProbeFailed {
+        /// The CPPC register ID.
+        reg_id: u32,
+        /// The `SbiRet` value for the failed SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for reading an implemented register returned an error.
+    ReadFailed {
+        /// The CPPC register ID.
+        reg_id: u32,
+        /// The `SbiRet` value for the failed SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for reserved register has been rejected.
+    ReservedRegisterRejected,
+    /// Test failed for reserved register is not rejected with `SBI_ERR_INVALID_PARAM`.
+    ReservedRegisterAccepted(SbiRet),
+    /// Test process for writing a read-only register has been denied.
+    ReadOnlyDenied(u32),
+    /// Test failed for writing a read-only register is not denied with `SBI_ERR_DENIED`.
+    ReadOnlyNotDenied {
+        /// The CPPC register ID.
+        reg_id: u32,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// All test cases on CPPC extension has passed.
+    Pass,
+}
+
+/// Test CPPC extension.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(mut f: impl FnMut(Case)) {
+    // 不支持 CPPC 扩展
+    if sbi::probe_extension(sbi::Cppc).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    // 保留的寄存器
+    let ret = sbi::cppc_probe(RESERVED_REGISTER);
+    if ret == SbiRet::invalid_param() {
+        f(Case::ReservedRegisterRejected);
+    } else {
+        f(Case::ReservedRegisterAccepted(ret));
+        return;
+    }
+    // 探测并读取所有标准寄存器
+    for (reg_id, read_only) in REGISTERS {
+        let ret = sbi::cppc_probe(reg_id);
+        let width = match ret.ok() {
+            Some(0) => {
+                f(Case::RegisterNotImplemented(reg_id));
+                continue;
+            }
+            Some(width) => width,
+            None => {
+                f(Case::ProbeFailed { reg_id, ret });
+                return;
+            }
+        };
+        let ret = sbi::cppc_read(reg_id);
+        let Some(lo) = ret.ok() else {
+            f(Case::ReadFailed { reg_id, ret });
+            return;
+        };
+        let mut value = lo as u64;
+        if width > usize::BITS as usize {
+            let ret = sbi::cppc_read_hi(reg_id);
+            let Some(hi) = ret.ok() else {
+                f(Case::ReadFailed { reg_id, ret });
+                return;
+            };
+            value |= (hi as u64) << 32;
+        }
+        f(Case::Register {
+            reg_id,
+            width,
+            value,
+        });
+        // 只读寄存器不能写入
+        if read_only {
+            let ret = sbi::cppc_write(reg_id, value);
+            if ret == SbiRet::denied() {
+                f(Case::ReadOnlyDenied(reg_id));
+            } else {
+                f(Case::ReadOnlyNotDenied { reg_id, ret });
+                return;
+            }
+        }
+    }
+    f(Case::Pass);
+}
+
+/// 第一个保留的寄存器
+const RESERVED_REGISTER: u32 = 0x0000_0015;
+
+/// 标准寄存器及其是否只读，声明于 §14
+const REGISTERS: [(u32, bool); 22] = [
+    (0x0000_0000, true),  // HighestPerformance
+    (0x0000_0001, true),  // NominalPerformance
+    (0x0000_0002, true),  // LowestNonlinearPerformance
+    (0x0000_0003, true),  // LowestPerformance
+    (0x0000_0004, true),  // GuaranteedPerformanceRegister
+    (0x0000_0005, false), // DesiredPerformanceRegister
+    (0x0000_0006, false), // MinimumPerformanceRegister
+    (0x0000_0007, false), // MaximumPerformanceRegister
+    (0x0000_0008, false), // PerformanceReductionToleranceRegister
+    (0x0000_0009, false), // TimeWindowRegister
+    (0x0000_000A, true),  // CounterWraparoundTime
+    (0x0000_000B, true),  // ReferencePerformanceCounterRegister
+    (0x0000_000C, true),  // DeliveredPerformanceCounterRegister
+    (0x0000_000D, false), // PerformanceLimitedRegister
+    (0x0000_000E, false), // CPPCEnableRegister
+    (0x0000_000F, false), // AutonomousSelectionEnable
+    (0x0000_0010, false), // AutonomousActivityWindowRegister
+    (0x0000_0011, false), // EnergyPerformancePreferenceRegister
+    (0x0000_0012, true),  // ReferencePerformance
+    (0x0000_0013, true),  // LowestFrequency
+    (0x0000_0014, true),  // NominalFrequency
+    (0x8000_0000, true),  // TransitionLatency
+];
@@ -0,0 +1,109 @@
+//! Debug triggers extension test suite.
+
+use sbi::{Breakpoint, Mcontrol6, SbiRet, TriggerModes, TriggerShmem};
+use sbi_spec::binary::Error;
+
+/// Debug triggers extension test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for debug triggers extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for number of debug triggers.
+    NumTriggers {
+        /// Number of all debug triggers.
+        total: usize,
+        /// Number of debug triggers supporting `mcontrol6` type.
+        mcontrol6: usize,
+    },
+    /// Test process for reading triggers without shared memory has been rejected.
+    NoShmemRejected,
+    /// Test failed for reading triggers without shared memory is not rejected with `SBI_ERR_NO_SHMEM`.
+    NoShmemAccepted(SbiRet),
+    /// Test failed for can't set debug trigger shared memory.
+    RegisterFailed(Error),
+    /// Test process for reading out-of-range triggers has been rejected.
+    OutOfRangeRejected,
+    /// Test failed for reading out-of-range triggers is not rejected with `SBI_ERR_BAD_RANGE`.
+    OutOfRangeAccepted(SbiRet),
+    /// Test process for no `mcontrol6` trigger is available, installing triggers is not tested.
+    NoMcontrol6Trigger,
+    /// Test process for a breakpoint has been installed, enabled, disabled and uninstalled.
+    Breakpoint(usize),
+    /// Test failed for a breakpoint operation returned an error.
+    BreakpointFailed(Error),
+    /// Test failed for can't disable debug trigger shared memory.
+    UnregisterFailed(Error),
+    /// All test cases on debug triggers extension has passed.
+    Pass,
+}
+
+/// Test debug triggers extension.
+///
+/// Memory must be identically mapped on the current hart.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(mut f: impl FnMut(Case)) {
+    // 不支持 DBTR 扩展
+    if sbi::probe_extension(sbi::Dbtr).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    let total = sbi::debug_num_triggers(0);
+    let mcontrol6 = sbi::debug_num_triggers(Mcontrol6::TYPE << (usize::BITS - 4));
+    f(Case::NumTriggers { total, mcontrol6 });
+    // 未设置共享内存时不能读取
+    let ret = sbi::debug_read_triggers(0, 1);
+    if ret == SbiRet::no_shmem() {
+        f(Case::NoShmemRejected);
+    } else {
+        f(Case::NoShmemAccepted(ret));
+        return;
+    }
+    let shmem = unsafe { &mut *core::ptr::addr_of_mut!(SHMEM) };
+    if let Err(error) = unsafe { shmem.register() } {
+        f(Case::RegisterFailed(error));
+        return;
+    }
+    // 越界读取
+    let ret = sbi::debug_read_triggers(total, 1);
+    if ret == SbiRet::bad_range() {
+        f(Case::OutOfRangeRejected);
+    } else {
+        let _ = TriggerShmem::<1>::unregister();
+        f(Case::OutOfRangeAccepted(ret));
+        return;
+    }
+    // 在不会执行的函数上安装断点
+    if mcontrol6 == 0 {
+        f(Case::NoMcontrol6Trigger);
+    } else {
+        let result =
+            Breakpoint::install(shmem, never_called as usize, TriggerModes::S).and_then(|bp| {
+                bp.enable()?;
+                bp.disable()?;
+                Ok(bp.trig_idx())
+            });
+        match result {
+            Ok(trig_idx) => f(Case::Breakpoint(trig_idx)),
+            Err(error) => {
+                let _ = TriggerShmem::<1>::unregister();
+                f(Case::BreakpointFailed(error));
+                return;
+            }
+        }
+    }
+    if let Err(error) = TriggerShmem::<1>::unregister() {
+        f(Case::UnregisterFailed(error));
+        return;
+    }
+    f(Case::Pass);
+}
+
+static mut SHMEM: TriggerShmem<1> = TriggerShmem::new();
+
+/// 断点目标，不会被调用
+#[inline(never)]
+extern "C" fn never_called() {}
@@ -0,0 +1,410 @@
+//! Minimal flattened device tree parser for standalone test image.
+
+use core::ops::Range;
+
+/// Board information provided by the device tree.
+#[derive(Clone, Debug)]
+pub struct BoardInfo {
+    /// Mask of enabled harts relative to `hart_mask_base`.
+    pub hart_mask: usize,
+    /// Lowest enabled hart ID.
+    pub hart_mask_base: usize,
+    /// Number of enabled harts, including those not covered by `hart_mask`.
+    pub hart_count: usize,
+    /// Frequency of `time` CSR in Hz.
+    pub frequency: u64,
+    /// Physical address range of the first memory node.
+    pub memory: Range<usize>,
+}
+
+impl BoardInfo {
+    /// Parse device tree blob at physical address `dtb`.
+    ///
+    /// Returns `None` if the blob is not a valid device tree.
+    ///
+    /// # Safety
+    ///
+    /// `dtb` must be identically mapped and point to a readable device tree blob.
+    pub unsafe fn parse(dtb: usize) -> Option<Self> {
+        if dtb == 0 || !dtb.is_multiple_of(4) {
+            return None;
+        }
+        let header = |i: usize| unsafe { u32::from_be((dtb as *const u32).add(i).read()) } as usize;
+        if header(0) != MAGIC || header(5) < 16 {
+            return None;
+        }
+        let total = header(1);
+        let blob = unsafe { core::slice::from_raw_parts(dtb as *const u8, total) };
+        let structs = blob.get(header(2)..)?;
+        let strings = blob.get(header(3)..)?;
+        Walker::default().walk(structs, strings)
+    }
+}
+
+const MAGIC: usize = 0xd00d_feed;
+
+const FDT_BEGIN_NODE: u32 = 1;
+const FDT_END_NODE: u32 = 2;
+const FDT_PROP: u32 = 3;
+const FDT_NOP: u32 = 4;
+const FDT_END: u32 = 9;
+
+/// 关心的节点种类
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Node {
+    Root,
+    Cpus,
+    Cpu,
+    Memory,
+    Other,
+}
+
+/// 遍历状态
+struct Walker {
+    path: [Node; 16],
+    depth: usize,
+    address_cells: usize,
+    size_cells: usize,
+    cpu_address_cells: usize,
+    cpu: Cpu,
+    harts: [usize; 64],
+    hart_count: usize,
+    frequency: u64,
+    cpu_frequency: Option<u64>,
+    memory: Option<Range<usize>>,
+}
+
+/// 正在遍历的 cpu 节点
+#[derive(Clone, Copy, Default)]
+struct Cpu {
+    hartid: Option<usize>,
+    disabled: bool,
+    frequency: Option<u64>,
+}
+
+impl Default for Walker {
+    fn default() -> Self {
+        Self {
+            path: [Node::Other; 16],
+            depth: 0,
+            // 规范规定的默认值
+            address_cells: 2,
+            size_cells: 1,
+            cpu_address_cells: 1,
+            cpu: Cpu::default(),
+            harts: [0; 64],
+            hart_count: 0,
+            frequency: 0,
+            cpu_frequency: None,
+            memory: None,
+        }
+    }
+}
+
+impl Walker {
+    fn walk(mut self, structs: &[u8], strings: &[u8]) -> Option<BoardInfo> {
+        let mut pos = 0;
+        loop {
+            let token = be32(structs, pos)?;
+            pos += 4;
+            match token {
+                FDT_BEGIN_NODE => {
+                    let name = cstr(structs.get(pos..)?)?;
+                    pos = align4(pos + name.len() + 1);
+                    // 新节点的父节点是当前节点
+                    let node = match (self.depth, self.current()) {
+                        (0, _) => Node::Root,
+                        (1, _) if name == b"cpus" => Node::Cpus,
+                        (1, _) if name == b"memory" || name.starts_with(b"memory@") => Node::Memory,
+                        (2, Node::Cpus) if name.starts_with(b"cpu@") => {
+                            self.cpu = Cpu::default();
+                            Node::Cpu
+                        }
+                        _ => Node::Other,
+                    };
+                    if let Some(slot) = self.path.get_mut(self.depth) {
+                        *slot = node;
+                    }
+                    self.depth += 1;
+                }
+                FDT_END_NODE => {
+                    if self.current() == Node::Cpu {
+                        self.end_cpu();
+                    }
+                    self.depth = self.depth.checked_sub(1)?;
+                }
+                FDT_PROP => {
+                    let len = be32(structs, pos)? as usize;
+                    let name = cstr(strings.get(be32(structs, pos + 4)? as usize..)?)?;
+                    let value = structs.get(pos + 8..pos + 8 + len)?;
+                    pos = align4(pos + 8 + len);
+                    self.property(name, value);
+                }
+                FDT_NOP => {}
+                FDT_END => break,
+                _ => return None,
+            }
+        }
+        if self.frequency == 0 {
+            self.frequency = self.cpu_frequency.unwrap_or(0);
+        }
+        let harts = &self.harts[..self.hart_count.min(self.harts.len())];
+        let hart_mask_base = harts.iter().copied().min().unwrap_or(0);
+        let hart_mask = harts
+            .iter()
+            .filter(|&&id| id - hart_mask_base < usize::BITS as usize)
+            .fold(0, |mask, id| mask | (1 << (id - hart_mask_base)));
+        Some(BoardInfo {
+            hart_mask,
+            hart_mask_base,
+            hart_count: self.hart_count,
+            frequency: self.frequency,
+            memory: self.memory.unwrap_or(0..0),
+        })
+    }
+
+    fn current(&self) -> Node {
+        self.depth
+            .checked_sub(1)
+            .and_then(|i| self.path.get(i))
+            .copied()
+            .unwrap_or(Node::Other)
+    }
+
+    fn property(&mut self, name: &[u8], value: &[u8]) {
+        match (self.current(), name) {
+            (Node::Root, b"#address-cells") => self.address_cells = cells(value, 1),
+            (Node::Root, b"#size-cells") => self.size_cells = cells(value, 1),
+            (Node::Cpus, b"#address-cells") => self.cpu_address_cells = cells(value, 1),
+            (Node::Cpus, b"timebase-frequency") => self.frequency = cells(value, 2) as u64,
+            (Node::Cpu, b"timebase-frequency") => self.cpu.frequency = Some(cells(value, 2) as u64),
+            (Node::Cpu, b"reg") => self.cpu.hartid = Some(cells(value, self.cpu_address_cells)),
+            (Node::Cpu, b"status") => self.cpu.disabled = !matches!(value, b"okay\0" | b"ok\0"),
+            (Node::Memory, b"reg") if self.memory.is_none() => {
+                if let Some((start, size)) = value.split_at_checked(self.address_cells * 4) {
+                    let start = cells(start, self.address_cells);
+                    // 范围溢出时忽略此节点
+                    if let Some(end) = start.checked_add(cells(size, self.size_cells)) {
+                        self.memory = Some(start..end);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end_cpu(&mut self) {
+        // cpus 节点未给出时钟频率时，使用首个给出频率的 cpu 节点
+        if self.cpu_frequency.is_none() {
+            self.cpu_frequency = self.cpu.frequency;
+        }
+        if let (Some(hartid), false) = (self.cpu.hartid, self.cpu.disabled) {
+            if let Some(slot) = self.harts.get_mut(self.hart_count) {
+                *slot = hartid;
+            }
+            self.hart_count += 1;
+        }
+    }
+}
+
+/// 读取大端 32 位数
+#[inline]
+fn be32(bytes: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        bytes.get(pos..pos + 4)?.try_into().ok()?,
+    ))
+}
+
+/// 按大端读取 `n` 个 32 位单元组成的数，长度不足时读取全部
+fn cells(value: &[u8], n: usize) -> usize {
+    value.chunks_exact(4).take(n).fold(0u64, |acc, c| {
+        acc << 32 | u32::from_be_bytes([c[0], c[1], c[2], c[3]]) as u64
+    }) as usize
+}
+
+/// 以 0 结尾的字符串，不含结尾
+#[inline]
+fn cstr(bytes: &[u8]) -> Option<&[u8]> {
+    bytes.iter().position(|&b| b == 0).map(|len| &bytes[..len])
+}
+
+#[inline]
+const fn align4(pos: usize) -> usize {
+    (pos + 3) & !3
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::vec::Vec;
+
+    /// 构造测试用设备树
+    #[derive(Default)]
+    struct Fixture {
+        structs: Vec<u8>,
+        strings: Vec<u8>,
+    }
+
+    impl Fixture {
+        fn token(&mut self, token: u32) -> &mut Self {
+            self.structs.extend_from_slice(&token.to_be_bytes());
+            self
+        }
+
+        fn pad(&mut self) {
+            self.structs.resize(align4(self.structs.len()), 0);
+        }
+
+        fn begin(&mut self, name: &str) -> &mut Self {
+            self.token(FDT_BEGIN_NODE);
+            self.structs.extend_from_slice(name.as_bytes());
+            self.structs.push(0);
+            self.pad();
+            self
+        }
+
+        fn end(&mut self) -> &mut Self {
+            self.token(FDT_END_NODE)
+        }
+
+        fn prop(&mut self, name: &str, value: &[u8]) -> &mut Self {
+            let offset = self.strings.len() as u32;
+            self.strings.extend_from_slice(name.as_bytes());
+            self.strings.push(0);
+            self.token(FDT_PROP).token(value.len() as u32).token(offset);
+            self.structs.extend_from_slice(value);
+            self.pad();
+            self
+        }
+
+        fn cells(&mut self, name: &str, cells: &[u32]) -> &mut Self {
+            let value: Vec<u8> = cells.iter().flat_map(|c| c.to_be_bytes()).collect();
+            self.prop(name, &value)
+        }
+
+        fn cpu(&mut self, hartid: u32, status: &str) -> &mut Self {
+            self.begin(&std::format!("cpu@{hartid}"))
+                .cells("reg", &[hartid])
+                .prop("status", std::format!("{status}\0").as_bytes())
+                .end()
+        }
+
+        /// 生成 4 字节对齐的设备树
+        fn build(&mut self) -> Vec<u32> {
+            self.token(FDT_END);
+            // 头部 40 字节，随后是空的保留内存表
+            let off_structs = 40 + 16;
+            let off_strings = off_structs + self.structs.len();
+            let total = off_strings + self.strings.len();
+            let header = [
+                MAGIC,
+                total,
+                off_structs,
+                off_strings,
+                40,
+                17,
+                16,
+                0,
+                self.strings.len(),
+                self.structs.len(),
+            ];
+            let mut blob: Vec<u8> = header
+                .iter()
+                .flat_map(|&x| (x as u32).to_be_bytes())
+                .collect();
+            blob.resize(off_structs, 0);
+            blob.extend_from_slice(&self.structs);
+            blob.extend_from_slice(&self.strings);
+            blob.resize(align4(blob.len()), 0);
+            blob.chunks(4)
+                .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+                .collect()
+        }
+    }
+
+    fn parse(blob: &[u32]) -> Option<BoardInfo> {
+        unsafe { BoardInfo::parse(blob.as_ptr() as usize) }
+    }
+
+    #[test]
+    fn qemu_virt() {
+        let blob = Fixture::default()
+            .begin("")
+            .cells("#address-cells", &[2])
+            .cells("#size-cells", &[2])
+            .begin("cpus")
+            .cells("#address-cells", &[1])
+            .cells("#size-cells", &[0])
+            .cells("timebase-frequency", &[10_000_000])
+            .cpu(0, "okay")
+            .cpu(1, "okay")
+            .cpu(2, "disabled")
+            .cpu(3, "okay")
+            .end()
+            .begin("memory@80000000")
+            .prop("device_type", b"memory\0")
+            .cells("reg", &[0, 0x8000_0000, 0, 0x800_0000])
+            .end()
+            .end()
+            .build();
+        let board = parse(&blob).unwrap();
+        assert_eq!(board.hart_mask, 0b1011);
+        assert_eq!(board.hart_mask_base, 0);
+        assert_eq!(board.hart_count, 3);
+        assert_eq!(board.frequency, 10_000_000);
+        assert_eq!(board.memory, 0x8000_0000..0x8800_0000);
+    }
+
+    #[test]
+    fn hart_mask_base_and_cpu_frequency() {
+        let blob = Fixture::default()
+            .begin("")
+            .begin("cpus")
+            .begin("cpu@4")
+            .cells("reg", &[4])
+            .cells("timebase-frequency", &[1_000_000])
+            .end()
+            .cpu(5, "okay")
+            .end()
+            .begin("memory@80000000")
+            .cells("reg", &[0, 0x8000_0000, 0x1000])
+            .end()
+            .end()
+            .build();
+        let board = parse(&blob).unwrap();
+        assert_eq!(board.hart_mask, 0b11);
+        assert_eq!(board.hart_mask_base, 4);
+        assert_eq!(board.hart_count, 2);
+        assert_eq!(board.frequency, 1_000_000);
+        // 默认 #address-cells = 2，#size-cells = 1
+        assert_eq!(board.memory, 0x8000_0000..0x8000_1000);
+    }
+
+    #[test]
+    fn memory_overflow_ignored() {
+        let blob = Fixture::default()
+            .begin("")
+            .cells("#address-cells", &[2])
+            .cells("#size-cells", &[2])
+            .begin("memory@ffffffffffff0000")
+            .cells("reg", &[0xffff_ffff, 0xffff_0000, 0, 0x10_0000])
+            .end()
+            .begin("memory@80000000")
+            .cells("reg", &[0, 0x8000_0000, 0, 0x1000])
+            .end()
+            .end()
+            .build();
+        assert_eq!(parse(&blob).unwrap().memory, 0x8000_0000..0x8000_1000);
+    }
+
+    #[test]
+    fn invalid_blob() {
+        let mut blob = Fixture::default().begin("").end().build();
+        assert!(parse(&blob).is_some());
+        blob[0] = 0;
+        assert!(parse(&blob).is_none());
+        assert!(unsafe { BoardInfo::parse(0) }.is_none());
+    }
+}
@@ -0,0 +1,368 @@
+//! Negative test suite sending randomized malformed SBI calls.
+//!
+//! Calls are generated by a deterministic pseudo-random number generator,
+//! the same seed always produces the same sequence of calls.
+
+use core::ops::Range;
+use sbi::SbiRet;
+use sbi_spec::{
+    base::{self, EID_BASE},
+    binary::Error,
+    cppc::EID_CPPC,
+    dbcn::{self, EID_DBCN},
+    dbtr::{self, EID_DBTR},
+    fwft::EID_FWFT,
+    hsm::{self, EID_HSM},
+    mpxy::{self, EID_MPXY},
+    nacl::{self, EID_NACL},
+    pmu::{self, EID_PMU},
+    rfnc::EID_RFNC,
+    spi::{self, EID_SPI},
+    srst::EID_SRST,
+    sse::{self, EID_SSE},
+    sta::{self, EID_STA},
+    susp::EID_SUSP,
+    time::EID_TIME,
+};
+
+/// A raw SBI call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Call {
+    /// Extension ID in `a7`.
+    pub eid: usize,
+    /// Function ID in `a6`.
+    pub fid: usize,
+    /// Arguments in `a0` to `a5`.
+    pub args: [usize; 6],
+}
+
+/// Fuzzing test cases.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Case {
+    /// Test begin.
+    Begin,
+    /// Test process for calls to an extension are being generated.
+    Extension(usize),
+    /// Test process for an SBI call is to be sent.
+    ///
+    /// If the SBI implementation hangs or crashes, the last call reported is the cause.
+    Call(Call),
+    /// Test failed for an SBI call returned an error code not defined by the specification.
+    IllegalError {
+        /// The SBI call sent.
+        call: Call,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for an unknown function is not rejected with `SBI_ERR_NOT_SUPPORTED`.
+    UnknownFunctionAccepted {
+        /// The SBI call sent.
+        call: Call,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for a malformed SBI call has succeeded.
+    MalformedAccepted {
+        /// The SBI call sent.
+        call: Call,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for base extension returns different values after fuzzing.
+    BaseChanged {
+        /// SBI specification version, implementation ID and implementation version before fuzzing.
+        before: [usize; 3],
+        /// SBI specification version, implementation ID and implementation version after fuzzing.
+        after: [usize; 3],
+    },
+    /// All test cases on fuzzing has passed.
+    Pass,
+}
+
+/// Send `rounds` randomized malformed calls generated from `seed` to each extension the SBI implementation provides.
+///
+/// `protected` is the physical address range of SBI implementation memory,
+/// which must not be read or written on behalf of the supervisor.
+/// Memory must be identically mapped on the current hart.
+///
+/// Calls that may stop, suspend or reset harts or the system are never generated with valid parameters.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(seed: u64, rounds: usize, protected: Range<usize>, mut f: impl FnMut(Case)) {
+    f(Case::Begin);
+    let before = base_values();
+    let mut rng = Rng::new(seed);
+    let mut pass = true;
+    for eid in EXTENSIONS {
+        if !probe(eid) {
+            continue;
+        }
+        f(Case::Extension(eid));
+        for _ in 0..rounds {
+            let (call, expect) = generate(eid, &mut rng, &protected);
+            f(Case::Call(call));
+            let ret = ecall(&call);
+            if ret.is_ok() && SHMEM_FUNCTIONS.contains(&(call.eid, call.fid)) {
+                // 错误地接受了共享内存，立即禁用
+                let _ = ecall(&Call {
+                    args: [usize::MAX, usize::MAX, 0, 0, 0, 0],
+                    ..call
+                });
+            }
+            if let Err(Error::Custom(_)) = ret.into_result() {
+                f(Case::IllegalError { call, ret });
+                pass = false;
+            } else if expect == Expect::NotSupported && ret != SbiRet::not_supported() {
+                f(Case::UnknownFunctionAccepted { call, ret });
+                pass = false;
+            } else if expect == Expect::Error && ret.is_ok() {
+                f(Case::MalformedAccepted { call, ret });
+                pass = false;
+            }
+        }
+    }
+    // 基础扩展的返回值不应被破坏
+    let after = base_values();
+    if before != after {
+        f(Case::BaseChanged { before, after });
+        return;
+    }
+    if pass {
+        f(Case::Pass);
+    }
+}
+
+/// 所有标准扩展，不包括遗留扩展
+const EXTENSIONS: [usize; 16] = [
+    EID_BASE, EID_TIME, EID_SPI, EID_RFNC, EID_HSM, EID_SRST, EID_PMU, EID_DBCN, EID_SUSP,
+    EID_CPPC, EID_NACL, EID_STA, EID_SSE, EID_FWFT, EID_DBTR, EID_MPXY,
+];
+
+/// 设置共享内存的函数，参数均为 `(lo, hi, flags)`，地址全 1 时禁用共享内存
+const SHMEM_FUNCTIONS: [(usize, usize); 5] = [
+    (EID_PMU, pmu::SNAPSHOT_SET_SHMEM),
+    (EID_NACL, nacl::SET_SHMEM),
+    (EID_STA, sta::SET_SHMEM),
+    (EID_DBTR, dbtr::SET_SHMEM),
+    (EID_MPXY, mpxy::SET_SHMEM),
+];
+
+/// 对调用结果的期望
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    /// 未知函数，必须返回 `SBI_ERR_NOT_SUPPORTED`
+    NotSupported,
+    /// 错误的参数，必须返回错误
+    Error,
+}
+
+/// 为扩展 `eid` 生成一个错误的调用
+fn generate(eid: usize, rng: &mut Rng, protected: &Range<usize>) -> (Call, Expect) {
+    let mut args = [0; 6];
+    args.fill_with(|| rng.random());
+    // 一半的调用使用未知的函数号
+    if rng.coin() {
+        let fid = 0x8000_0000 | (rng.random() & 0xffff_ffff);
+        return (Call { eid, fid, args }, Expect::NotSupported);
+    }
+    let fid = match eid {
+        EID_HSM if rng.coin() => {
+            args[0] = bad_hart(rng);
+            hsm::HART_START
+        }
+        EID_HSM => {
+            args[0] = bad_hart(rng);
+            hsm::HART_GET_STATUS
+        }
+        EID_SPI => {
+            args[0] |= 1;
+            args[1] = bad_hart(rng);
+            spi::SEND_IPI
+        }
+        EID_RFNC => {
+            args[0] |= 1;
+            args[1] = bad_hart(rng);
+            rng.random() % 7
+        }
+        EID_SSE if rng.coin() => {
+            args[0] = sse::event_id::LOCAL_SOFTWARE_INJECTED as usize;
+            args[1] = bad_hart(rng);
+            sse::INJECT
+        }
+        EID_SSE => {
+            args[0] = sse::event_id::LOCAL_SOFTWARE_INJECTED as usize;
+            args[1] = sse::attr_id::STATUS as usize;
+            args[2] = 1 + rng.random() % 10;
+            [args[3], args[4]] = bad_address(rng, protected, size_of::<usize>());
+            sse::READ_ATTRS
+        }
+        EID_DBCN => {
+            args[0] = 1 + rng.random() % 64;
+            [args[1], args[2]] = bad_address(rng, protected, 1);
+            if rng.coin() {
+                dbcn::CONSOLE_WRITE
+            } else {
+                dbcn::CONSOLE_READ
+            }
+        }
+        _ => match SHMEM_FUNCTIONS.iter().find(|(e, _)| *e == eid) {
+            Some(&(_, fid)) => {
+                [args[0], args[1]] = bad_address(rng, protected, PAGE_SIZE);
+                args[2] = 0;
+                fid
+            }
+            // 没有可以构造错误参数的函数，使用未知的函数号
+            None => {
+                let fid = 0x8000_0000 | (rng.random() & 0xffff_ffff);
+                return (Call { eid, fid, args }, Expect::NotSupported);
+            }
+        },
+    };
+    (Call { eid, fid, args }, Expect::Error)
+}
+
+const PAGE_SIZE: usize = 4096;
+
+/// 不存在的 hart 编号，也不是表示忽略掩码的全 1
+fn bad_hart(rng: &mut Rng) -> usize {
+    if rng.coin() {
+        (1 << (usize::BITS / 2)) + (rng.random() >> (usize::BITS / 2 + 1))
+    } else {
+        usize::MAX - 1 - rng.random() % 64
+    }
+}
+
+/// SBI 实现所在或不存在的物理地址 `(lo, hi)`，一半按 `align` 对齐
+fn bad_address(rng: &mut Rng, protected: &Range<usize>, align: usize) -> [usize; 2] {
+    let len = protected.end.saturating_sub(protected.start);
+    if len == 0 || rng.random().is_multiple_of(4) {
+        return [rng.random(), rng.random() | 1];
+    }
+    let mut addr = protected.start + rng.random() % len;
+    if rng.coin() {
+        addr = (addr & !(align - 1)).max(protected.start);
+    }
+    [addr, 0]
+}
+
+/// 探测扩展是否存在
+fn probe(eid: usize) -> bool {
+    let ret = ecall(&Call {
+        eid: EID_BASE,
+        fid: base::PROBE_EXTENSION,
+        args: [eid, 0, 0, 0, 0, 0],
+    });
+    ret.value != base::UNAVAILABLE_EXTENSION
+}
+
+/// 读取规范版本、实现编号和实现版本
+fn base_values() -> [usize; 3] {
+    [
+        base::GET_SBI_SPEC_VERSION,
+        base::GET_SBI_IMPL_ID,
+        base::GET_SBI_IMPL_VERSION,
+    ]
+    .map(|fid| {
+        ecall(&Call {
+            eid: EID_BASE,
+            fid,
+            args: [0; 6],
+        })
+        .value
+    })
+}
+
+/// 发送任意的 SBI 调用
+#[inline(always)]
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+fn ecall(call: &Call) -> SbiRet {
+    let [a0, a1, a2, a3, a4, a5] = call.args;
+    let (error, value);
+    unsafe {
+        core::arch::asm!(
+            "ecall",
+            inlateout("a0") a0 => error,
+            inlateout("a1") a1 => value,
+            in("a2") a2,
+            in("a3") a3,
+            in("a4") a4,
+            in("a5") a5,
+            in("a6") call.fid,
+            in("a7") call.eid,
+        )
+    };
+    SbiRet { error, value }
+}
+
+#[inline(always)]
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+fn ecall(_call: &Call) -> SbiRet {
+    unimplemented!("unsupported architecture")
+}
+
+/// xorshift64* 伪随机数发生器
+struct Rng(u64);
+
+impl Rng {
+    const fn new(seed: u64) -> Self {
+        // 状态不能为 0
+        Self(if seed == 0 {
+            0x9e37_79b9_7f4a_7c15
+        } else {
+            seed
+        })
+    }
+
+    fn random(&mut self) -> usize {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d) as usize
+    }
+
+    /// 等概率返回真或假
+    fn coin(&mut self) -> bool {
+        self.random() & 1 == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::vec::Vec;
+
+    // 与 `test` 相同的顺序为所有扩展生成调用
+    fn cases(seed: u64) -> Vec<Case> {
+        let protected = 0x8000_0000..0x8020_0000;
+        let mut rng = Rng::new(seed);
+        let mut cases = Vec::new();
+        for eid in EXTENSIONS {
+            cases.push(Case::Extension(eid));
+            for _ in 0..64 {
+                cases.push(Case::Call(generate(eid, &mut rng, &protected).0));
+            }
+        }
+        cases
+    }
+
+    #[test]
+    fn same_seed_same_cases() {
+        assert_eq!(cases(0x1234), cases(0x1234));
+        assert_eq!(cases(0), cases(0));
+        assert_ne!(cases(0x1234), cases(0x4321));
+    }
+
+    #[test]
+    fn bad_hart_range() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1024 {
+            let hartid = bad_hart(&mut rng);
+            assert!(hartid >= 1 << (usize::BITS / 2));
+            assert_ne!(hartid, usize::MAX);
+        }
+    }
+}
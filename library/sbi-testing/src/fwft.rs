@@ -0,0 +1,149 @@
+//! Firmware features extension test suite.
+
+use sbi::SbiRet;
+use sbi_spec::fwft::{feature_type, flags::SetFlags};
+
+/// Firmware features extension test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for firmware features extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for value of a supported feature.
+    Feature {
+        /// The feature ID.
+        feature: u32,
+        /// Current value of the feature.
+        value: usize,
+    },
+    /// Test process for a feature not supported on this platform.
+    FeatureNotSupported(u32),
+    /// Test failed for getting a standard feature returned an unexpected error.
+    GetFailed {
+        /// The feature ID.
+        feature: u32,
+        /// The `SbiRet` value for the failed SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for reserved feature has been denied.
+    ReservedFeatureDenied,
+    /// Test failed for reserved feature is not denied with `SBI_ERR_DENIED`.
+    ReservedFeatureNotDenied(SbiRet),
+    /// Test process for setting a feature with reserved flags has been rejected.
+    InvalidFlagsRejected,
+    /// Test failed for setting a feature with reserved flags is not rejected with `SBI_ERR_INVALID_PARAM`.
+    InvalidFlagsAccepted(SbiRet),
+    /// Test process for setting a feature to its current value.
+    SetCurrentValue(u32),
+    /// Test failed for setting a feature to its current value returned an error,
+    /// or the value has changed.
+    SetFailed {
+        /// The feature ID.
+        feature: u32,
+        /// The `SbiRet` value for the failed SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for setting a locked feature has been denied.
+    LockedDenied(u32),
+    /// Test failed for setting a locked feature is not denied with `SBI_ERR_DENIED_LOCKED`.
+    LockNotEnforced {
+        /// The feature ID.
+        feature: u32,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for no feature is supported, setting features is not tested.
+    NoFeatureSupported,
+    /// All test cases on firmware features extension has passed.
+    Pass,
+}
+
+/// Test firmware features extension.
+///
+/// If `lock` is true, the first supported feature is locked at its current value until the system is reset,
+/// which can't be undone by later tests or by the supervisor.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(lock: bool, mut f: impl FnMut(Case)) {
+    // 不支持 FWFT 扩展
+    if sbi::probe_extension(sbi::Fwft).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    // 保留的特性
+    let ret = sbi::fwft_get(RESERVED_FEATURE);
+    if ret == SbiRet::denied() {
+        f(Case::ReservedFeatureDenied);
+    } else {
+        f(Case::ReservedFeatureNotDenied(ret));
+        return;
+    }
+    // 读取所有标准特性
+    let mut supported = None;
+    for feature in FEATURES {
+        let ret = sbi::fwft_get(feature);
+        if let Some(value) = ret.ok() {
+            f(Case::Feature { feature, value });
+            supported = supported.or(Some((feature, value)));
+        } else if ret == SbiRet::not_supported() {
+            f(Case::FeatureNotSupported(feature));
+        } else {
+            f(Case::GetFailed { feature, ret });
+            return;
+        }
+    }
+    let Some((feature, value)) = supported else {
+        f(Case::NoFeatureSupported);
+        f(Case::Pass);
+        return;
+    };
+    // 保留的标志位
+    let ret = sbi::fwft_set(feature, value, RESERVED_FLAGS);
+    if ret == SbiRet::invalid_param() {
+        f(Case::InvalidFlagsRejected);
+    } else {
+        f(Case::InvalidFlagsAccepted(ret));
+        return;
+    }
+    // 设置为当前值
+    let ret = sbi::fwft_set(feature, value, 0);
+    if ret.is_err() || sbi::fwft_get(feature) != SbiRet::success(value) {
+        f(Case::SetFailed { feature, ret });
+        return;
+    }
+    f(Case::SetCurrentValue(feature));
+    if !lock {
+        f(Case::Pass);
+        return;
+    }
+    // 锁定后不能再设置
+    let ret = sbi::fwft_set(feature, value, SetFlags::LOCK.bits());
+    if ret.is_err() {
+        f(Case::SetFailed { feature, ret });
+        return;
+    }
+    let ret = sbi::fwft_set(feature, value, 0);
+    if ret == SbiRet::denied_locked() {
+        f(Case::LockedDenied(feature));
+    } else {
+        f(Case::LockNotEnforced { feature, ret });
+        return;
+    }
+    f(Case::Pass);
+}
+
+const FEATURES: [u32; 6] = [
+    feature_type::MISALIGNED_EXC_DELEG as _,
+    feature_type::LANDING_PAD as _,
+    feature_type::SHADOW_STACK as _,
+    feature_type::DOUBLE_TRAP as _,
+    feature_type::PTE_AD_HW_UPDATING as _,
+    feature_type::POINTER_MASKING_PMLEN as _,
+];
+
+/// 最后一个保留的标准特性
+const RESERVED_FEATURE: u32 = 0x3fff_ffff;
+/// 保留的设置标志位
+const RESERVED_FLAGS: usize = !SetFlags::all().bits();
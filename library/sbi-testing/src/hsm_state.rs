@@ -0,0 +1,698 @@
+//! Hart state monitor extension state machine conformance test suite.
+
+use core::{
+    arch::{asm, naked_asm},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+use riscv::register::time;
+use sbi::{HartMask, SbiRet};
+use sbi_spec::hsm::{
+    EID_HSM, HART_SUSPEND, hart_state,
+    suspend_type::{NON_RETENTIVE, RETENTIVE},
+};
+
+/// Hart state monitor state machine test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for hart state monitor extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test failed for `hart_get_status` on an invalid hart ID has not returned `SBI_ERR_INVALID_PARAM`.
+    InvalidHartStatus {
+        /// The invalid hart ID.
+        hartid: usize,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for `hart_start` on an invalid hart ID has not returned `SBI_ERR_INVALID_PARAM`.
+    InvalidHartStart {
+        /// The invalid hart ID.
+        hartid: usize,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for `hart_get_status` on the current hart has not returned `STARTED`.
+    CurrentHartNotStarted(SbiRet),
+    /// Test failed for `hart_start` on a started hart has not returned `SBI_ERR_ALREADY_AVAILABLE`.
+    StartedHartRestarted {
+        /// The started hart ID.
+        hartid: usize,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for a hart is not stopped before test and is not tested.
+    HartStartedBeforeTest(usize),
+    /// Test failed for no other harts are available to be tested.
+    NoStoppedHart,
+    /// Test failed for can't start target hart with [`SbiRet`] error.
+    HartStartFailed {
+        /// The target hart ID that has failed to start.
+        hartid: usize,
+        /// The `SbiRet` value for the failed hart start SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for target hart to be tested has started.
+    HartStarted(usize),
+    /// Test failed for `hart_get_status` on the target hart has returned an error.
+    StatusFailed {
+        /// The target hart ID.
+        hartid: usize,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for a pending state of target hart has been observed.
+    PendingObserved {
+        /// The target hart ID.
+        hartid: usize,
+        /// The pending hart state.
+        state: usize,
+    },
+    /// Test failed for target hart has made a transition not allowed by the specification.
+    IllegalTransition {
+        /// The target hart ID.
+        hartid: usize,
+        /// The hart state before transition.
+        from: usize,
+        /// The hart state after transition.
+        to: usize,
+    },
+    /// Test failed for target hart has not finished a transition in time.
+    Timeout {
+        /// The target hart ID.
+        hartid: usize,
+        /// The last hart state observed.
+        state: usize,
+    },
+    /// Test failed for `hart_suspend` with a reserved suspend type has not returned `SBI_ERR_INVALID_PARAM`.
+    ReservedSuspendAccepted {
+        /// The reserved suspend type.
+        suspend_type: u32,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for `hart_suspend` with a default suspend type has failed.
+    SuspendFailed {
+        /// The default suspend type.
+        suspend_type: u32,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for target hart has resumed from a default suspend type.
+    SuspendResumed(u32),
+    /// Test failed for registers other than `a0` and `a1` have been clobbered by retentive suspend.
+    ///
+    /// The returned value is the bitwise difference of all clobbered registers.
+    RetentiveClobbered(usize),
+    /// Test failed for target hart has resumed from non-retentive suspend with unexpected register states.
+    NonRetentiveState {
+        /// Value of `a0` on resume, must be the hart ID.
+        a0: usize,
+        /// Value of `a1` on resume, must be the opaque parameter.
+        a1: usize,
+        /// Value of `satp` on resume, must be zero.
+        satp: usize,
+        /// Value of `sstatus` on resume, `SIE` bit must be cleared.
+        sstatus: usize,
+    },
+    /// Test process for `hart_suspend` with a platform-specific suspend type has returned.
+    PlatformSuspend {
+        /// The platform-specific suspend type.
+        suspend_type: u32,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for `hart_suspend` with a platform-specific suspend type has returned an error code not allowed.
+    PlatformSuspendIllegal {
+        /// The platform-specific suspend type.
+        suspend_type: u32,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for `hart_stop` has returned on target hart.
+    StopReturned {
+        /// The target hart ID.
+        hartid: usize,
+        /// The `SbiRet` value for the SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for target hart to be tested has stopped.
+    HartStopped(usize),
+    /// Test failed for a stopped hart has left `STOPPED` state on an inter-processor interrupt.
+    StoppedHartWoken {
+        /// The target hart ID.
+        hartid: usize,
+        /// The hart state observed.
+        state: usize,
+    },
+    /// All test cases on hart state monitor state machine has passed.
+    Pass,
+}
+
+/// Test transitions and error codes of hart state monitor extension on given harts.
+///
+/// The first stopped hart other than the current one is tested.
+/// `timeout` is the time in `time` ticks to wait for each transition.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(
+    primary_hart_id: usize,
+    mut hart_mask: usize,
+    hart_mask_base: usize,
+    timeout: u64,
+    mut f: impl FnMut(Case),
+) {
+    // 不支持 HSM 扩展
+    if sbi::probe_extension(sbi::Hsm).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    let mut pass = true;
+    // 无效的核编号
+    for hartid in INVALID_HARTS {
+        let ret = sbi::hart_get_status(hartid);
+        if ret != SbiRet::invalid_param() {
+            f(Case::InvalidHartStatus { hartid, ret });
+            pass = false;
+        }
+        let ret = sbi::hart_start(hartid, test_entry as _, 0);
+        if ret != SbiRet::invalid_param() {
+            f(Case::InvalidHartStart { hartid, ret });
+            pass = false;
+        }
+    }
+    // 当前核已经启动
+    let ret = sbi::hart_get_status(primary_hart_id);
+    if ret != SbiRet::success(hart_state::STARTED) {
+        f(Case::CurrentHartNotStarted(ret));
+        pass = false;
+    }
+    let ret = sbi::hart_start(primary_hart_id, test_entry as _, 0);
+    if ret != SbiRet::already_available() {
+        f(Case::StartedHartRestarted {
+            hartid: primary_hart_id,
+            ret,
+        });
+        pass = false;
+    }
+    // 找到第一个停止的副核
+    let mut target = None;
+    let mut hartid = hart_mask_base;
+    while hart_mask != 0 && target.is_none() {
+        let distance = hart_mask.trailing_zeros();
+        hartid += distance as usize;
+        if hartid != primary_hart_id {
+            if sbi::hart_get_status(hartid) == SbiRet::success(hart_state::STOPPED) {
+                target = Some(hartid);
+            } else {
+                f(Case::HartStartedBeforeTest(hartid));
+            }
+        }
+        hart_mask >>= distance;
+        hart_mask >>= 1;
+        hartid += 1;
+    }
+    let Some(hartid) = target else {
+        f(Case::NoStoppedHart);
+        return;
+    };
+    if test_hart(hartid, timeout, &mut f) && pass {
+        f(Case::Pass);
+    }
+}
+
+/// 不存在的核编号
+const INVALID_HARTS: [usize; 2] = [usize::MAX, usize::MAX - 1];
+
+/// 保留的休眠类型
+const RESERVED_SUSPEND_TYPES: [u32; 4] = [0x0000_0001, 0x0fff_ffff, 0x8000_0001, 0x8fff_ffff];
+/// 平台自定义的休眠类型
+const PLATFORM_SUSPEND_TYPES: [u32; 2] = [0x1000_0000, 0x9000_0000];
+
+/// 测试一个副核的全部状态转换
+fn test_hart(hartid: usize, timeout: u64, f: &mut impl FnMut(Case)) -> bool {
+    let slot = slot();
+    let opaque = slot.reset(hartid) as usize;
+    // 启动
+    if !start(hartid, opaque, timeout, f) {
+        return false;
+    }
+    let mut pass = true;
+    // 已启动的核不能再启动
+    let ret = sbi::hart_start(hartid, test_entry as _, opaque);
+    if ret != SbiRet::already_available() {
+        f(Case::StartedHartRestarted { hartid, ret });
+        pass = false;
+    }
+    // 保留的休眠类型
+    for suspend_type in RESERVED_SUSPEND_TYPES {
+        let Some(ret) = suspend(hartid, suspend_type, timeout, f) else {
+            return false;
+        };
+        if ret != SbiRet::invalid_param() {
+            f(Case::ReservedSuspendAccepted { suspend_type, ret });
+            pass = false;
+        }
+    }
+    // 默认的休眠类型
+    for suspend_type in [RETENTIVE, NON_RETENTIVE] {
+        let Some(ret) = suspend(hartid, suspend_type, timeout, f) else {
+            return false;
+        };
+        if ret.is_err() {
+            f(Case::SuspendFailed { suspend_type, ret });
+            pass = false;
+            continue;
+        }
+        f(Case::SuspendResumed(suspend_type));
+        let clobbered = slot.clobbered.load(Ordering::Acquire);
+        if suspend_type == RETENTIVE && clobbered != 0 {
+            f(Case::RetentiveClobbered(clobbered));
+            pass = false;
+        }
+        let [a0, a1, satp, sstatus] = slot.resumed.each_ref().map(|r| r.load(Ordering::Acquire));
+        if suspend_type == NON_RETENTIVE
+            && (a0 != hartid || a1 != opaque || satp != 0 || sstatus & SIE != 0)
+        {
+            f(Case::NonRetentiveState {
+                a0,
+                a1,
+                satp,
+                sstatus,
+            });
+            pass = false;
+        }
+    }
+    // 平台自定义的休眠类型，未实现时返回无效参数
+    for suspend_type in PLATFORM_SUSPEND_TYPES {
+        let Some(ret) = suspend(hartid, suspend_type, timeout, f) else {
+            return false;
+        };
+        if ret.is_ok() || ret == SbiRet::invalid_param() || ret == SbiRet::not_supported() {
+            f(Case::PlatformSuspend { suspend_type, ret });
+        } else {
+            f(Case::PlatformSuspendIllegal { suspend_type, ret });
+            pass = false;
+        }
+    }
+    // 停止，不响应核间中断，再次启动并停止
+    if !stop(hartid, timeout, f) {
+        return false;
+    }
+    sbi::send_ipi(HartMask::from_mask_base(1, hartid));
+    let begin = time::read64();
+    while time::read64() - begin < timeout {
+        let ret = sbi::hart_get_status(hartid);
+        if ret != SbiRet::success(hart_state::STOPPED) {
+            f(Case::StoppedHartWoken {
+                hartid,
+                state: ret.value,
+            });
+            return false;
+        }
+    }
+    let opaque = slot.reset(hartid) as usize;
+    start(hartid, opaque, timeout, f) && stop(hartid, timeout, f) && pass
+}
+
+/// 启动副核并等待进入测试入口
+fn start(hartid: usize, opaque: usize, timeout: u64, f: &mut impl FnMut(Case)) -> bool {
+    let ret = sbi::hart_start(hartid, test_entry as _, opaque);
+    if ret.is_err() {
+        f(Case::HartStartFailed { hartid, ret });
+        return false;
+    }
+    const PATH: [usize; 2] = [hart_state::START_PENDING, hart_state::STARTED];
+    let ready = |state| state == hart_state::STARTED && slot().ready.load(Ordering::Acquire);
+    if watch(hartid, hart_state::STOPPED, &PATH, timeout, ready, f).is_none() {
+        return false;
+    }
+    f(Case::HartStarted(hartid));
+    true
+}
+
+/// 令副核停止并等待
+fn stop(hartid: usize, timeout: u64, f: &mut impl FnMut(Case)) -> bool {
+    slot().command.store(COMMAND_STOP, Ordering::Release);
+    const PATH: [usize; 3] = [
+        hart_state::STARTED,
+        hart_state::STOP_PENDING,
+        hart_state::STOPPED,
+    ];
+    let stopped = |state| state == hart_state::STOPPED || slot().idle();
+    if watch(hartid, hart_state::STARTED, &PATH, timeout, stopped, f).is_none() {
+        return false;
+    }
+    // 停止调用不应返回
+    if slot().idle() {
+        f(Case::StopReturned {
+            hartid,
+            ret: slot().ret(),
+        });
+        return false;
+    }
+    f(Case::HartStopped(hartid));
+    true
+}
+
+/// 令副核以 `suspend_type` 休眠，进入休眠后以核间中断唤醒，返回休眠调用的结果
+fn suspend(
+    hartid: usize,
+    suspend_type: u32,
+    timeout: u64,
+    f: &mut impl FnMut(Case),
+) -> Option<SbiRet> {
+    let slot = slot();
+    slot.argument
+        .store(suspend_type as usize, Ordering::Relaxed);
+    slot.command.store(COMMAND_SUSPEND, Ordering::Release);
+    const SUSPEND: [usize; 3] = [
+        hart_state::STARTED,
+        hart_state::SUSPEND_PENDING,
+        hart_state::SUSPENDED,
+    ];
+    let suspended = |state| state == hart_state::SUSPENDED || slot.idle();
+    if watch(hartid, hart_state::STARTED, &SUSPEND, timeout, suspended, f)? == hart_state::SUSPENDED
+    {
+        sbi::send_ipi(HartMask::from_mask_base(1, hartid));
+        const RESUME: [usize; 3] = [
+            hart_state::SUSPENDED,
+            hart_state::RESUME_PENDING,
+            hart_state::STARTED,
+        ];
+        let resumed = |state| state == hart_state::STARTED && slot.idle();
+        watch(hartid, hart_state::SUSPENDED, &RESUME, timeout, resumed, f)?;
+    }
+    Some(slot.ret())
+}
+
+/// 观察副核状态直到 `until` 成立，状态从 `from` 开始，只能沿 `path` 向后转换
+fn watch(
+    hartid: usize,
+    from: usize,
+    path: &[usize],
+    timeout: u64,
+    until: impl Fn(usize) -> bool,
+    f: &mut impl FnMut(Case),
+) -> Option<usize> {
+    let begin = time::read64();
+    let mut last = from;
+    let mut position = None;
+    loop {
+        let ret = sbi::hart_get_status(hartid);
+        if ret.is_err() {
+            f(Case::StatusFailed { hartid, ret });
+            return None;
+        }
+        let state = ret.value;
+        if position.is_none() || last != state {
+            match path.iter().position(|s| *s == state) {
+                Some(to) if position.is_none_or(|from| from <= to) => position = Some(to),
+                _ => {
+                    f(Case::IllegalTransition {
+                        hartid,
+                        from: last,
+                        to: state,
+                    });
+                    return None;
+                }
+            }
+            if matches!(
+                state,
+                hart_state::START_PENDING
+                    | hart_state::STOP_PENDING
+                    | hart_state::SUSPEND_PENDING
+                    | hart_state::RESUME_PENDING
+            ) {
+                f(Case::PendingObserved { hartid, state });
+            }
+            last = state;
+        }
+        if until(state) {
+            return Some(state);
+        }
+        if time::read64() - begin > timeout {
+            f(Case::Timeout { hartid, state });
+            return None;
+        }
+        core::hint::spin_loop();
+    }
+}
+
+const COMMAND_IDLE: usize = 0;
+const COMMAND_SUSPEND: usize = 1;
+const COMMAND_STOP: usize = 2;
+
+const SIE: usize = 1 << 1;
+const SSIP: usize = 1 << 1;
+
+static mut SLOT: Slot = Slot::ZERO;
+
+#[inline]
+fn slot() -> &'static Slot {
+    unsafe { &*core::ptr::addr_of!(SLOT) }
+}
+
+/// 副核的命令、结果和栈
+#[repr(C, align(2048))]
+struct Slot {
+    hartid: AtomicUsize,
+    ready: AtomicBool,
+    command: AtomicUsize,
+    argument: AtomicUsize,
+    error: AtomicUsize,
+    value: AtomicUsize,
+    clobbered: AtomicUsize,
+    /// 从不可恢复休眠恢复时的 `a0`、`a1`、`satp` 和 `sstatus`
+    resumed: [AtomicUsize; 4],
+    stack: [u8; 2048 - 88],
+}
+
+impl Slot {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: Self = Self {
+        hartid: AtomicUsize::new(0),
+        ready: AtomicBool::new(false),
+        command: AtomicUsize::new(COMMAND_IDLE),
+        argument: AtomicUsize::new(0),
+        error: AtomicUsize::new(0),
+        value: AtomicUsize::new(0),
+        clobbered: AtomicUsize::new(0),
+        resumed: [const { AtomicUsize::new(0) }; 4],
+        stack: [0; 2048 - 88],
+    };
+
+    #[inline]
+    fn reset(&self, hartid: usize) -> *const Slot {
+        self.hartid.store(hartid, Ordering::Relaxed);
+        self.ready.store(false, Ordering::Relaxed);
+        self.command.store(COMMAND_IDLE, Ordering::Relaxed);
+        self as _
+    }
+
+    #[inline]
+    fn idle(&self) -> bool {
+        self.command.load(Ordering::Acquire) == COMMAND_IDLE
+    }
+
+    #[inline]
+    fn ret(&self) -> SbiRet {
+        SbiRet {
+            error: self.error.load(Ordering::Acquire),
+            value: self.value.load(Ordering::Acquire),
+        }
+    }
+
+    /// 副核写入命令的结果
+    #[inline]
+    fn finish(&self, ret: SbiRet, clobbered: usize) {
+        self.error.store(ret.error, Ordering::Relaxed);
+        self.value.store(ret.value, Ordering::Relaxed);
+        self.clobbered.store(clobbered, Ordering::Relaxed);
+        self.command.store(COMMAND_IDLE, Ordering::Release);
+    }
+}
+
+/// 副核入口，启动和从不可恢复休眠恢复时进入
+#[unsafe(naked)]
+unsafe extern "C" fn test_entry(hartid: usize, opaque: *const Slot) -> ! {
+    naked_asm!(
+        "csrw sie, zero",     // 关中断
+        "addi sp, a1, 2047",  // 设置栈
+        "andi sp, sp, -16",
+        "j    {rust_main}",   // 进入 rust
+        rust_main = sym rust_main,
+    )
+}
+
+/// 副核执行主核发出的命令
+extern "C" fn rust_main(hartid: usize, opaque: *const Slot) -> ! {
+    let slot = unsafe { &*opaque };
+    if slot.command.load(Ordering::Acquire) == COMMAND_SUSPEND {
+        // 从不可恢复休眠恢复
+        let (satp, sstatus): (usize, usize);
+        unsafe { asm!("csrr {}, satp", "csrr {}, sstatus", out(reg) satp, out(reg) sstatus) };
+        for (r, value) in slot
+            .resumed
+            .iter()
+            .zip([hartid, opaque as usize, satp, sstatus])
+        {
+            r.store(value, Ordering::Relaxed);
+        }
+        unsafe { clear_ssip() };
+        slot.finish(SbiRet::success(0), 0);
+    } else {
+        slot.ready.store(true, Ordering::Release);
+    }
+    loop {
+        match slot.command.load(Ordering::Acquire) {
+            COMMAND_SUSPEND => {
+                let suspend_type = slot.argument.load(Ordering::Relaxed) as u32;
+                let mut clobbered = 0;
+                let ret = if suspend_type & NON_RETENTIVE == 0 {
+                    unsafe { suspend_retentive(suspend_type as usize, &mut clobbered) }
+                } else {
+                    unsafe { suspend_raw(suspend_type as usize, test_entry as _, opaque as _) }
+                };
+                unsafe { clear_ssip() };
+                slot.finish(ret, clobbered);
+            }
+            COMMAND_STOP => {
+                let ret = sbi::hart_stop();
+                slot.finish(ret, 0);
+            }
+            _ => core::hint::spin_loop(),
+        }
+    }
+}
+
+/// 填充寄存器的值
+const PATTERN: usize = 0x5a5a_5a5a_5a5a_0000;
+
+/// 可恢复休眠，检查 `s2`-`s11` 和 `t3`-`t6` 是否被修改，将修改的位写入 `clobbered`
+#[unsafe(naked)]
+unsafe extern "C" fn suspend_retentive(suspend_type: usize, clobbered: *mut usize) -> SbiRet {
+    naked_asm!(
+        "addi sp, sp, -96",
+        "sd   s2,  0(sp)
+         sd   s3,  8(sp)
+         sd   s4, 16(sp)
+         sd   s5, 24(sp)
+         sd   s6, 32(sp)
+         sd   s7, 40(sp)
+         sd   s8, 48(sp)
+         sd   s9, 56(sp)
+         sd  s10, 64(sp)
+         sd  s11, 72(sp)
+         sd   a1, 80(sp)",
+        "li   s2, {pattern} + 2
+         li   s3, {pattern} + 3
+         li   s4, {pattern} + 4
+         li   s5, {pattern} + 5
+         li   s6, {pattern} + 6
+         li   s7, {pattern} + 7
+         li   s8, {pattern} + 8
+         li   s9, {pattern} + 9
+         li  s10, {pattern} + 10
+         li  s11, {pattern} + 11
+         li   t3, {pattern} + 13
+         li   t4, {pattern} + 14
+         li   t5, {pattern} + 15
+         li   t6, {pattern} + 16",
+        "li   a1, 0
+         li   a2, 0
+         li   a6, {hart_suspend}
+         li   a7, {eid_hsm}
+         ecall",
+        "li   t0, 0",
+        "li   t1, {pattern} + 2
+         xor  t1, t1, s2
+         or   t0, t0, t1
+         li   t1, {pattern} + 3
+         xor  t1, t1, s3
+         or   t0, t0, t1
+         li   t1, {pattern} + 4
+         xor  t1, t1, s4
+         or   t0, t0, t1
+         li   t1, {pattern} + 5
+         xor  t1, t1, s5
+         or   t0, t0, t1
+         li   t1, {pattern} + 6
+         xor  t1, t1, s6
+         or   t0, t0, t1
+         li   t1, {pattern} + 7
+         xor  t1, t1, s7
+         or   t0, t0, t1
+         li   t1, {pattern} + 8
+         xor  t1, t1, s8
+         or   t0, t0, t1
+         li   t1, {pattern} + 9
+         xor  t1, t1, s9
+         or   t0, t0, t1
+         li   t1, {pattern} + 10
+         xor  t1, t1, s10
+         or   t0, t0, t1
+         li   t1, {pattern} + 11
+         xor  t1, t1, s11
+         or   t0, t0, t1
+         li   t1, {pattern} + 13
+         xor  t1, t1, t3
+         or   t0, t0, t1
+         li   t1, {pattern} + 14
+         xor  t1, t1, t4
+         or   t0, t0, t1
+         li   t1, {pattern} + 15
+         xor  t1, t1, t5
+         or   t0, t0, t1
+         li   t1, {pattern} + 16
+         xor  t1, t1, t6
+         or   t0, t0, t1",
+        "ld   t1, 80(sp)
+         sd   t0, 0(t1)",
+        "ld   s2,  0(sp)
+         ld   s3,  8(sp)
+         ld   s4, 16(sp)
+         ld   s5, 24(sp)
+         ld   s6, 32(sp)
+         ld   s7, 40(sp)
+         ld   s8, 48(sp)
+         ld   s9, 56(sp)
+         ld  s10, 64(sp)
+         ld  s11, 72(sp)",
+        "addi sp, sp, 96",
+        "ret",
+        pattern      = const PATTERN,
+        hart_suspend = const HART_SUSPEND,
+        eid_hsm      = const EID_HSM,
+    )
+}
+
+/// 以任意休眠类型调用 `hart_suspend`
+#[inline]
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+unsafe fn suspend_raw(suspend_type: usize, resume_addr: usize, opaque: usize) -> SbiRet {
+    let (error, value);
+    unsafe {
+        asm!(
+            "ecall",
+            inlateout("a0") suspend_type => error,
+            inlateout("a1") resume_addr => value,
+            in("a2") opaque,
+            in("a6") HART_SUSPEND,
+            in("a7") EID_HSM,
+        )
+    };
+    SbiRet { error, value }
+}
+
+#[inline]
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+unsafe fn suspend_raw(_suspend_type: usize, _resume_addr: usize, _opaque: usize) -> SbiRet {
+    unimplemented!("unsupported architecture")
+}
+
+#[inline]
+unsafe fn clear_ssip() {
+    unsafe { asm!("csrc sip, {}", in(reg) SSIP) };
+}
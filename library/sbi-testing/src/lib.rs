@@ -13,24 +13,70 @@ mod log_test;
 #[cfg(feature = "log")]
 pub use log_test::Testing;
 
+mod report;
+pub use report::{JUnit, Outcome, Reporter, Tap};
+
+mod fuzz;
+pub use fuzz::{Call as FuzzCall, Case as FuzzCase, test as test_fuzz};
+
+#[cfg(feature = "standalone")]
+mod fdt;
+#[cfg(feature = "standalone")]
+pub use fdt::BoardInfo;
+
 // §4
 mod base;
 pub use base::{Case as BaseCase, Extensions, test as test_base};
 // §6
 mod time;
 pub use time::{Case as TimerCase, test as test_timer};
+mod time_accuracy;
+pub use time_accuracy::{
+    Case as TimerAccuracyCase, Source as TimerSource, test as test_timer_accuracy,
+};
 // §7
 mod spi;
 pub use spi::{Case as IpiCase, test as test_ipi};
+mod spi_stress;
+pub use spi_stress::{Case as IpiStressCase, Shape as IpiShape, test as test_ipi_stress};
 // §8
-// pub mod rfnc;
+mod rfnc;
+pub use rfnc::{Case as RfncCase, test as test_rfnc};
 // §9
 mod hsm;
 pub use hsm::{Case as HsmCase, test as test_hsm};
+mod hsm_state;
+pub use hsm_state::{Case as HsmStateCase, test as test_hsm_state};
 // §10
-// pub mod srst;
+mod srst;
+pub use srst::{Case as SrstCase, Progress as SrstProgress, test as test_srst};
 // §11
-// pub mod pmu;
+mod pmu;
+pub use pmu::{Case as PmuCase, test as test_pmu};
 // §12
 mod dbcn;
 pub use dbcn::{Case as DbcnCase, test as test_dbcn};
+// §13
+mod susp;
+pub use susp::{Case as SuspCase, test as test_susp};
+// §14
+mod cppc;
+pub use cppc::{Case as CppcCase, test as test_cppc};
+// §15
+mod nacl;
+pub use nacl::{Case as NaclCase, test as test_nacl};
+// §16
+mod sta;
+pub use sta::{Case as StaCase, test as test_sta};
+// §17
+mod sse;
+pub use sse::{Case as SseCase, test as test_sse};
+// §18
+mod fwft;
+pub use fwft::{Case as FwftCase, test as test_fwft};
+// §19
+mod dbtr;
+pub use dbtr::{Case as DbtrCase, test as test_dbtr};
+// §20
+mod mpxy;
+pub use mpxy::{Case as MpxyCase, test as test_mpxy};
@@ -1,13 +1,18 @@
-﻿use crate::{base, dbcn, hsm, spi, time};
+use crate::{
+    base, cppc, dbcn, dbtr, fwft, hsm, hsm_state, mpxy, nacl, pmu,
+    report::{Outcome, Reporter},
+    rfnc, spi, spi_stress, sse, sta, time, time_accuracy,
+};
+use core::fmt::{self, Write};
 use log::*;
 
 /// Automatic SBI testing with logging enabled.
 pub struct Testing {
     /// The hart ID to test most of single core extensions.
     pub hartid: usize,
-    /// A list of harts to test Hart State Monitor extension.
+    /// A list of harts to test Hart State Monitor and Remote Fence extensions.
     pub hart_mask: usize,
-    /// Base of hart list to test Hart State Monitor extension.
+    /// Base of hart list to test Hart State Monitor and Remote Fence extensions.
     pub hart_mask_base: usize,
     /// Delay value to test Timer programmer extension.
     pub delay: u64,
@@ -17,10 +22,21 @@ const TARGET: &str = "sbi-testing";
 
 impl Testing {
     /// Start testing process of RISC-V SBI implementation.
+    #[inline]
     pub fn test(self) -> bool {
+        self.report(&mut ())
+    }
+
+    /// Start testing process of RISC-V SBI implementation, reporting each case of extension tests to `reporter`.
+    ///
+    /// Cases are named `<extension>/<case>`, e.g. `TIME/SetTimer`, and missing optional extensions are reported as skipped.
+    pub fn report(self, reporter: &mut impl Reporter) -> bool {
+        reporter.begin();
         let mut result = true;
+        let mut record = Record::new("Base");
         base::test(|case| {
             use base::Case::*;
+            record.begin(&case);
             match case {
                 NotExist => panic!("Sbi `Base` not exist"),
                 Begin => info!(target: TARGET, "Testing `Base`"),
@@ -50,14 +66,15 @@ impl Testing {
                     info!(target: TARGET, "mimp id = {id:#x}");
                 }
             }
+            record.end(reporter);
         });
+        result &= record.finish();
+        let mut record = Record::new("TIME");
         time::test(self.delay, |case| {
             use time::Case::*;
+            record.begin(&case);
             match case {
-                NotExist => {
-                    error!(target: TARGET, "Sbi `TIME` not exist");
-                    result = false;
-                }
+                NotExist => fail!(record, "Sbi `TIME` not exist"),
                 Begin => info!(target: TARGET, "Testing `TIME`"),
                 Pass => info!(target: TARGET, "Sbi `TIME` test pass"),
                 Interval { begin: _, end: _ } => {
@@ -66,52 +83,90 @@ impl Testing {
                         "read time register successfully, set timer +1s"
                     );
                 }
-                ReadFailed => {
-                    error!(target: TARGET, "csrr time failed");
-                    result = false;
-                }
-                TimeDecreased { a, b } => {
-                    error!(target: TARGET, "time decreased: {a} -> {b}");
-                    result = false;
-                }
+                ReadFailed => fail!(record, "csrr time failed"),
+                TimeDecreased { a, b } => fail!(record, "time decreased: {a} -> {b}"),
                 SetTimer => {
                     info!(target: TARGET, "timer interrupt delegate successfully");
                 }
                 UnexpectedTrap(trap) => {
-                    error!(
-                        target: TARGET,
+                    fail!(
+                        record,
                         "expect trap at supervisor timer, but {trap:?} was caught"
                     );
-                    result = false;
                 }
             }
+            record.end(reporter);
         });
+        result &= record.finish();
+        let mut record = Record::new("TIME accuracy");
+        time_accuracy::test(self.delay, |case| {
+            use time_accuracy::Case::*;
+            record.begin(&case);
+            match case {
+                NotExist => fail!(record, "Sbi `TIME` not exist"),
+                Begin => info!(target: TARGET, "Testing `TIME` accuracy"),
+                Pass => info!(target: TARGET, "Sbi `TIME` accuracy test pass"),
+                PendingNotCleared(source) => {
+                    fail!(record, "pending timer not cleared by {source:?}")
+                }
+                PastDeadline { deadline, fired } => {
+                    debug!(target: TARGET, "past deadline {deadline} fired at {fired}")
+                }
+                Lost { source, deadline } => {
+                    fail!(record, "{source:?} deadline {deadline} not fired")
+                }
+                FiredEarly {
+                    source,
+                    deadline,
+                    fired,
+                } => fail!(
+                    record,
+                    "{source:?} deadline {deadline} fired early at {fired}"
+                ),
+                NotOverridden { source, deadline } => {
+                    fail!(record, "{source:?} deadline {deadline} not overridden")
+                }
+                Latency {
+                    source,
+                    count,
+                    min,
+                    max,
+                    mean,
+                } => info!(
+                    target: TARGET,
+                    "{count} {source:?} deadlines fired, latency min = {min}, max = {max}, mean = {mean}"
+                ),
+                SstcNotPresent => info!(target: TARGET, "Sstc not present"),
+                SstcPresent => info!(target: TARGET, "Testing Sstc interaction"),
+            }
+            record.end(reporter);
+        });
+        result &= record.finish();
+        let mut record = Record::new("sPI");
         spi::test(self.hartid, |case| {
             use spi::Case::*;
+            record.begin(&case);
             match case {
-                NotExist => {
-                    error!(target: TARGET, "Sbi `sPI` not exist");
-                    result = false;
-                }
+                NotExist => fail!(record, "Sbi `sPI` not exist"),
                 Begin => info!(target: TARGET, "Testing `sPI`"),
                 Pass => info!(target: TARGET, "Sbi `sPI` test pass"),
                 SendIpi => info!(target: TARGET, "send ipi successfully"),
                 UnexpectedTrap(trap) => {
-                    error!(
-                        target: TARGET,
+                    fail!(
+                        record,
                         "expect trap at supervisor soft, but {trap:?} was caught"
                     );
-                    result = false;
                 }
             }
+            record.end(reporter);
         });
+        result &= record.finish();
+        let mut record = Record::new("HSM");
         hsm::test(self.hartid, self.hart_mask, self.hart_mask_base, |case| {
             use hsm::Case::*;
+            record.begin(&case);
             match case {
-                NotExist => {
-                    error!(target: TARGET, "Sbi `HSM` not exist");
-                    result = false;
-                }
+                NotExist => fail!(record, "Sbi `HSM` not exist"),
                 Begin => info!(target: TARGET, "Testing `HSM`"),
                 Pass => info!(target: TARGET, "Sbi `HSM` test pass"),
                 HartStartedBeforeTest(id) => warn!(target: TARGET, "hart {id} already started"),
@@ -119,8 +174,7 @@ impl Testing {
                 BatchBegin(batch) => info!(target: TARGET, "Testing harts: {batch:?}"),
                 HartStarted(id) => debug!(target: TARGET, "hart {id} started"),
                 HartStartFailed { hartid, ret } => {
-                    error!(target: TARGET, "hart {hartid} start failed: {ret:?}");
-                    result = false;
+                    fail!(record, "hart {hartid} start failed: {ret:?}")
                 }
                 HartSuspendedNonretentive(id) => {
                     debug!(target: TARGET, "hart {id} suspended nonretentive")
@@ -132,36 +186,671 @@ impl Testing {
                 HartStopped(id) => debug!(target: TARGET, "hart {id} stopped"),
                 BatchPass(batch) => info!(target: TARGET, "Testing Pass: {batch:?}"),
             }
+            record.end(reporter);
+        });
+        result &= record.finish();
+        let mut record = Record::new("HSM state");
+        hsm_state::test(
+            self.hartid,
+            self.hart_mask,
+            self.hart_mask_base,
+            self.delay,
+            |case| {
+                use hsm_state::Case::*;
+                record.begin(&case);
+                match case {
+                    NotExist => fail!(record, "Sbi `HSM` not exist"),
+                    Begin => info!(target: TARGET, "Testing `HSM` state machine"),
+                    Pass => info!(target: TARGET, "Sbi `HSM` state machine test pass"),
+                    InvalidHartStatus { hartid, ret } => {
+                        fail!(record, "invalid hart {hartid:#x} status: {ret:?}")
+                    }
+                    InvalidHartStart { hartid, ret } => {
+                        fail!(record, "invalid hart {hartid:#x} start: {ret:?}")
+                    }
+                    CurrentHartNotStarted(ret) => fail!(record, "current hart status: {ret:?}"),
+                    StartedHartRestarted { hartid, ret } => {
+                        fail!(record, "started hart {hartid} start: {ret:?}")
+                    }
+                    HartStartedBeforeTest(id) => warn!(target: TARGET, "hart {id} already started"),
+                    NoStoppedHart => skip!(record, "no stopped hart"),
+                    HartStartFailed { hartid, ret } => {
+                        fail!(record, "hart {hartid} start failed: {ret:?}")
+                    }
+                    HartStarted(id) => debug!(target: TARGET, "hart {id} started"),
+                    StatusFailed { hartid, ret } => {
+                        fail!(record, "hart {hartid} status failed: {ret:?}")
+                    }
+                    PendingObserved { hartid, state } => {
+                        debug!(target: TARGET, "hart {hartid} pending state {state} observed")
+                    }
+                    IllegalTransition { hartid, from, to } => {
+                        fail!(record, "hart {hartid} illegal transition {from} -> {to}")
+                    }
+                    Timeout { hartid, state } => {
+                        fail!(record, "hart {hartid} timeout at state {state}")
+                    }
+                    ReservedSuspendAccepted { suspend_type, ret } => {
+                        fail!(
+                            record,
+                            "reserved suspend type {suspend_type:#x} accepted: {ret:?}"
+                        )
+                    }
+                    SuspendFailed { suspend_type, ret } => {
+                        fail!(record, "suspend type {suspend_type:#x} failed: {ret:?}")
+                    }
+                    SuspendResumed(suspend_type) => {
+                        debug!(target: TARGET, "resumed from suspend type {suspend_type:#x}")
+                    }
+                    RetentiveClobbered(bits) => {
+                        fail!(
+                            record,
+                            "registers clobbered by retentive suspend: {bits:#x}"
+                        )
+                    }
+                    NonRetentiveState {
+                        a0,
+                        a1,
+                        satp,
+                        sstatus,
+                    } => fail!(
+                        record,
+                        "non-retentive resume with a0 = {a0:#x}, a1 = {a1:#x}, satp = {satp:#x}, sstatus = {sstatus:#x}"
+                    ),
+                    PlatformSuspend { suspend_type, ret } => {
+                        info!(target: TARGET, "platform suspend type {suspend_type:#x}: {ret:?}")
+                    }
+                    PlatformSuspendIllegal { suspend_type, ret } => {
+                        fail!(
+                            record,
+                            "platform suspend type {suspend_type:#x} illegal: {ret:?}"
+                        )
+                    }
+                    StopReturned { hartid, ret } => {
+                        fail!(record, "hart {hartid} stop returned: {ret:?}")
+                    }
+                    HartStopped(id) => debug!(target: TARGET, "hart {id} stopped"),
+                    StoppedHartWoken { hartid, state } => {
+                        fail!(record, "stopped hart {hartid} woken to state {state}")
+                    }
+                }
+                record.end(reporter);
+            },
+        );
+        result &= record.finish();
+        let mut record = Record::new("RFNC");
+        rfnc::test(self.hartid, self.hart_mask, self.hart_mask_base, |case| {
+            use rfnc::Case::*;
+            record.begin(&case);
+            match case {
+                NotExist => fail!(record, "Sbi `RFNC` not exist"),
+                Begin => info!(target: TARGET, "Testing `RFNC`"),
+                Pass => info!(target: TARGET, "Sbi `RFNC` test pass"),
+                InvalidHartMaskRejected => {
+                    debug!(target: TARGET, "invalid hart mask rejected")
+                }
+                InvalidHartMaskAccepted(ret) => {
+                    fail!(record, "invalid hart mask not rejected: {ret:?}")
+                }
+                InvalidAddressRejected => debug!(target: TARGET, "invalid address rejected"),
+                InvalidAddressFlushedAll => {
+                    warn!(target: TARGET, "invalid address accepted as full flush")
+                }
+                InvalidAddressFailed(ret) => fail!(record, "invalid address failed: {ret:?}"),
+                NoStoppedHart => warn!(target: TARGET, "no stopped hart"),
+                HartStartFailed { hartid, ret } => {
+                    fail!(record, "hart {hartid} start failed: {ret:?}")
+                }
+                PagingNotSupported(id) => {
+                    warn!(target: TARGET, "hart {id} doesn't support Sv39")
+                }
+                RemoteSfenceVma(id) => info!(target: TARGET, "remote sfence.vma on hart {id}"),
+                RemoteSfenceVmaAsid { hartid, asid } => {
+                    info!(target: TARGET, "remote sfence.vma on hart {hartid} asid {asid}")
+                }
+                RemoteFenceI(id) => info!(target: TARGET, "remote fence.i on hart {id}"),
+                FenceFailed { function, ret } => fail!(record, "{function} failed: {ret:?}"),
+                StaleTranslation { hartid, asid } => fail!(
+                    record,
+                    "hart {hartid} asid {asid} observed stale translation"
+                ),
+                StaleInstruction(id) => fail!(record, "hart {id} executed stale instruction"),
+                UnexpectedTrap { hartid, scause } => {
+                    fail!(record, "hart {hartid} trapped, scause = {scause:#x}")
+                }
+            }
+            record.end(reporter);
+        });
+        result &= record.finish();
+        let mut record = Record::new("sPI stress");
+        spi_stress::test(
+            self.hartid,
+            self.hart_mask,
+            self.hart_mask_base,
+            self.delay,
+            |case| {
+                use spi_stress::Case::*;
+                record.begin(&case);
+                match case {
+                    NotExist => fail!(record, "Sbi `sPI` or `HSM` not exist"),
+                    Begin => info!(target: TARGET, "Testing `sPI` stress"),
+                    Pass => info!(target: TARGET, "Sbi `sPI` stress test pass"),
+                    HartStartedBeforeTest(id) => warn!(target: TARGET, "hart {id} already started"),
+                    HartNotTested(id) => warn!(target: TARGET, "hart {id} not tested"),
+                    NoStoppedHart => skip!(record, "no stopped hart"),
+                    HartStartFailed { hartid, ret } => {
+                        fail!(record, "hart {hartid} start failed: {ret:?}")
+                    }
+                    HartStarted(id) => debug!(target: TARGET, "hart {id} started"),
+                    SendFailed { shape, ret } => {
+                        fail!(record, "send ipi {shape:?} failed: {ret:?}")
+                    }
+                    Lost { shape, hartid } => fail!(record, "hart {hartid} lost ipi {shape:?}"),
+                    Duplicated {
+                        shape,
+                        hartid,
+                        sent,
+                        received,
+                    } => fail!(
+                        record,
+                        "hart {hartid} received {received} ipi {shape:?} but {sent} sent"
+                    ),
+                    Latency {
+                        shape,
+                        delivered,
+                        min,
+                        max,
+                        mean,
+                    } => info!(
+                        target: TARGET,
+                        "{delivered} ipi {shape:?} delivered, latency min = {min}, max = {max}, mean = {mean}"
+                    ),
+                    Burst(n) => info!(target: TARGET, "burst of {n} ipi delivered"),
+                }
+                record.end(reporter);
+            },
+        );
+        result &= record.finish();
+        let mut record = Record::new("PMU");
+        pmu::test(|case| {
+            use pmu::Case::*;
+            record.begin(&case);
+            match case {
+                NotExist => skip!(record, "Sbi `PMU` not exist"),
+                Begin => info!(target: TARGET, "Testing `PMU`"),
+                Pass => info!(target: TARGET, "Sbi `PMU` test pass"),
+                NumCounters(num) => info!(target: TARGET, "{num} counters"),
+                CounterInfo { idx, info } => {
+                    if info.is_firmware() {
+                        debug!(target: TARGET, "counter {idx} is a firmware counter")
+                    } else {
+                        debug!(
+                            target: TARGET,
+                            "counter {idx}: csr = {:#x}, width = {}",
+                            info.csr(),
+                            info.width()
+                        )
+                    }
+                }
+                CounterInfoFailed { idx, ret } => {
+                    fail!(record, "counter {idx} get info failed: {ret:?}")
+                }
+                InvalidHardwareCounter { idx, info } => {
+                    fail!(record, "counter {idx} has invalid csr {:#x}", info.csr())
+                }
+                EventNotSupported(event) => {
+                    debug!(target: TARGET, "event {:#x} not supported", event.raw())
+                }
+                EventConfigFailed { event, error } => {
+                    fail!(record, "event {:#x} config failed: {error:?}", event.raw())
+                }
+                CounterTypeMismatch { event, idx } => {
+                    fail!(
+                        record,
+                        "event {:#x} mapped to counter {idx} of different type",
+                        event.raw()
+                    );
+                }
+                EventCounted { event, idx, delta } => {
+                    debug!(
+                        target: TARGET,
+                        "event {:#x} counted {delta} on counter {idx}",
+                        event.raw()
+                    )
+                }
+                EventNotCounted { event, idx } => {
+                    fail!(
+                        record,
+                        "event {:#x} not counted on counter {idx}",
+                        event.raw()
+                    );
+                }
+                CounterFailed { event, error } => {
+                    fail!(record, "event {:#x} counter failed: {error:?}", event.raw())
+                }
+                AlreadyStartedNotReported(ret) => fail!(record, "restart counter returned {ret:?}"),
+                AlreadyStoppedNotReported(ret) => fail!(record, "restop counter returned {ret:?}"),
+                StartStopSemantics => info!(target: TARGET, "counter start and stop successfully"),
+                SnapshotNotSupported => warn!(target: TARGET, "snapshot not supported"),
+                SnapshotFailed(ret) => fail!(record, "snapshot set shmem failed: {ret:?}"),
+                Snapshot(value) => info!(target: TARGET, "snapshot counter value = {value}"),
+                SnapshotMismatch { snapshot, counter } => fail!(
+                    record,
+                    "snapshot value {snapshot} != counter value {counter}"
+                ),
+            }
+            record.end(reporter);
         });
+        result &= record.finish();
+        let mut record = Record::new("DBCN");
         dbcn::test(|case| {
             use dbcn::Case::*;
+            record.begin(&case);
             match case {
-                NotExist => {
-                    error!(target: TARGET, "Sbi `DBCN` not exist");
-                    result = false;
-                }
+                NotExist => fail!(record, "Sbi `DBCN` not exist"),
                 Begin => info!(target: TARGET, "Testing `DBCN`"),
                 Pass => info!(target: TARGET, "Sbi `DBCN` test pass"),
                 WriteByte => {}
-                WritingByteFailed(ret) => {
-                    error!(target: TARGET, "writing byte failed: {ret:?}");
-                    result = false;
-                }
+                WritingByteFailed(ret) => fail!(record, "writing byte failed: {ret:?}"),
                 WriteSlice => info!(target: TARGET, "writing slice successfully"),
                 WritingPartialSlice(len) => {
                     warn!(target: TARGET, "writing partial slice: {len} bytes written");
                 }
-                WritingSliceFailed(ret) => {
-                    error!(target: TARGET, "writing slice failed: {ret:?}");
-                    result = false;
-                }
+                WritingSliceFailed(ret) => fail!(record, "writing slice failed: {ret:?}"),
                 Read(len) => info!(target: TARGET, "reading {len} bytes from console"),
-                ReadingFailed(ret) => {
-                    error!(target: TARGET, "reading failed: {ret:?}");
-                    result = false;
+                ReadingFailed(ret) => fail!(record, "reading failed: {ret:?}"),
+            }
+            record.end(reporter);
+        });
+        result &= record.finish();
+        let mut record = Record::new("CPPC");
+        cppc::test(|case| {
+            use cppc::Case::*;
+            record.begin(&case);
+            match case {
+                NotExist => skip!(record, "Sbi `CPPC` not exist"),
+                Begin => info!(target: TARGET, "Testing `CPPC`"),
+                Pass => info!(target: TARGET, "Sbi `CPPC` test pass"),
+                Register {
+                    reg_id,
+                    width,
+                    value,
+                } => {
+                    debug!(target: TARGET, "register {reg_id:#x}: {width} bits, value = {value}")
+                }
+                RegisterNotImplemented(id) => {
+                    debug!(target: TARGET, "register {id:#x} not implemented")
+                }
+                ProbeFailed { reg_id, ret } => {
+                    fail!(record, "probe register {reg_id:#x} failed: {ret:?}")
+                }
+                ReadFailed { reg_id, ret } => {
+                    fail!(record, "read register {reg_id:#x} failed: {ret:?}")
+                }
+                ReservedRegisterRejected => debug!(target: TARGET, "reserved register rejected"),
+                ReservedRegisterAccepted(ret) => {
+                    fail!(record, "reserved register not rejected: {ret:?}")
+                }
+                ReadOnlyDenied(id) => debug!(target: TARGET, "write register {id:#x} denied"),
+                ReadOnlyNotDenied { reg_id, ret } => {
+                    fail!(record, "write read-only register {reg_id:#x}: {ret:?}")
+                }
+            }
+            record.end(reporter);
+        });
+        result &= record.finish();
+        let mut record = Record::new("NACL");
+        nacl::test(|case| {
+            use nacl::Case::*;
+            record.begin(&case);
+            match case {
+                NotExist => skip!(record, "Sbi `NACL` not exist"),
+                Begin => info!(target: TARGET, "Testing `NACL`"),
+                Pass => info!(target: TARGET, "Sbi `NACL` test pass"),
+                Features(features) => info!(target: TARGET, "nacl features = {features:?}"),
+                ReservedFeatureUnavailable => {
+                    debug!(target: TARGET, "reserved feature unavailable")
+                }
+                ReservedFeatureAvailable(ret) => {
+                    fail!(record, "reserved feature reported as {ret:?}")
+                }
+                NoShmemRejected => debug!(target: TARGET, "sync without shmem rejected"),
+                NoShmemAccepted(ret) => fail!(record, "sync without shmem not rejected: {ret:?}"),
+                MisalignedShmemRejected => debug!(target: TARGET, "misaligned shmem rejected"),
+                MisalignedShmemAccepted(ret) => {
+                    fail!(record, "misaligned shmem not rejected: {ret:?}")
+                }
+                InvalidFlagsRejected => debug!(target: TARGET, "invalid flags rejected"),
+                InvalidFlagsAccepted(ret) => fail!(record, "invalid flags not rejected: {ret:?}"),
+                RegisterFailed(ret) => fail!(record, "set shmem failed: {ret:?}"),
+                SyncCsr => info!(target: TARGET, "sync csr successfully"),
+                SyncCsrFailed(ret) => fail!(record, "sync csr failed: {ret:?}"),
+                UnregisterFailed(ret) => fail!(record, "disable shmem failed: {ret:?}"),
+            }
+            record.end(reporter);
+        });
+        result &= record.finish();
+        let mut record = Record::new("STA");
+        sta::test(|case| {
+            use sta::Case::*;
+            record.begin(&case);
+            match case {
+                NotExist => skip!(record, "Sbi `STA` not exist"),
+                Begin => info!(target: TARGET, "Testing `STA`"),
+                Pass => info!(target: TARGET, "Sbi `STA` test pass"),
+                MisalignedShmemRejected => debug!(target: TARGET, "misaligned shmem rejected"),
+                MisalignedShmemAccepted(ret) => {
+                    fail!(record, "misaligned shmem not rejected: {ret:?}")
+                }
+                InvalidFlagsRejected => debug!(target: TARGET, "invalid flags rejected"),
+                InvalidFlagsAccepted(ret) => fail!(record, "invalid flags not rejected: {ret:?}"),
+                RegisterFailed(ret) => fail!(record, "set shmem failed: {ret:?}"),
+                StealTime(ns) => info!(target: TARGET, "steal time = {ns} ns"),
+                UnregisterFailed(ret) => fail!(record, "disable shmem failed: {ret:?}"),
+            }
+            record.end(reporter);
+        });
+        result &= record.finish();
+        let mut record = Record::new("SSE");
+        sse::test(self.hartid, |case| {
+            use sse::Case::*;
+            record.begin(&case);
+            match case {
+                NotExist => skip!(record, "Sbi `SSE` not exist"),
+                Begin => info!(target: TARGET, "Testing `SSE`"),
+                Pass => info!(target: TARGET, "Sbi `SSE` test pass"),
+                ReservedEventRejected => debug!(target: TARGET, "reserved event rejected"),
+                ReservedEventAccepted(ret) => fail!(record, "reserved event not rejected: {ret:?}"),
+                ReservedAttributeRejected => {
+                    debug!(target: TARGET, "reserved attribute rejected")
+                }
+                ReservedAttributeAccepted(ret) => {
+                    fail!(record, "reserved attribute not rejected: {ret:?}")
+                }
+                InjectionNotSupported => warn!(target: TARGET, "software injection not supported"),
+                Attributes {
+                    status,
+                    priority,
+                    config,
+                } => debug!(
+                    target: TARGET,
+                    "status = {status:#x}, priority = {priority:#x}, config = {config:#x}"
+                ),
+                EventFailed { function, ret } => fail!(record, "{function} failed: {ret:?}"),
+                UnmaskTwiceRejected => debug!(target: TARGET, "unmask twice rejected"),
+                UnmaskTwiceAccepted(ret) => fail!(record, "unmask twice not rejected: {ret:?}"),
+                MaskTwiceRejected => debug!(target: TARGET, "mask twice rejected"),
+                MaskTwiceAccepted(ret) => fail!(record, "mask twice not rejected: {ret:?}"),
+                CompleteOutsideHandler => {
+                    debug!(target: TARGET, "complete outside handler successfully")
+                }
+                Injected(n) => info!(target: TARGET, "{n} injected events handled in order"),
+                HandlerReentered => fail!(record, "event handler reentered"),
+                InjectedNotHandled(n) => fail!(record, "{n} of 2 injected events handled"),
+            }
+            record.end(reporter);
+        });
+        result &= record.finish();
+        let mut record = Record::new("FWFT");
+        // 锁定特性无法撤销，不在自动测试中进行
+        fwft::test(false, |case| {
+            use fwft::Case::*;
+            record.begin(&case);
+            match case {
+                NotExist => skip!(record, "Sbi `FWFT` not exist"),
+                Begin => info!(target: TARGET, "Testing `FWFT`"),
+                Pass => info!(target: TARGET, "Sbi `FWFT` test pass"),
+                Feature { feature, value } => {
+                    info!(target: TARGET, "feature {feature:#x} = {value:#x}")
+                }
+                FeatureNotSupported(feature) => {
+                    debug!(target: TARGET, "feature {feature:#x} not supported")
+                }
+                GetFailed { feature, ret } => {
+                    fail!(record, "get feature {feature:#x} failed: {ret:?}")
+                }
+                ReservedFeatureDenied => debug!(target: TARGET, "reserved feature denied"),
+                ReservedFeatureNotDenied(ret) => {
+                    fail!(record, "reserved feature not denied: {ret:?}")
+                }
+                InvalidFlagsRejected => debug!(target: TARGET, "invalid flags rejected"),
+                InvalidFlagsAccepted(ret) => fail!(record, "invalid flags not rejected: {ret:?}"),
+                SetCurrentValue(feature) => {
+                    debug!(target: TARGET, "set feature {feature:#x} successfully")
+                }
+                SetFailed { feature, ret } => {
+                    fail!(record, "set feature {feature:#x} failed: {ret:?}")
+                }
+                LockedDenied(feature) => {
+                    info!(target: TARGET, "feature {feature:#x} locked successfully")
+                }
+                LockNotEnforced { feature, ret } => {
+                    fail!(record, "set locked feature {feature:#x}: {ret:?}")
+                }
+                NoFeatureSupported => warn!(target: TARGET, "no feature supported"),
+            }
+            record.end(reporter);
+        });
+        result &= record.finish();
+        let mut record = Record::new("DBTR");
+        dbtr::test(|case| {
+            use dbtr::Case::*;
+            record.begin(&case);
+            match case {
+                NotExist => skip!(record, "Sbi `DBTR` not exist"),
+                Begin => info!(target: TARGET, "Testing `DBTR`"),
+                Pass => info!(target: TARGET, "Sbi `DBTR` test pass"),
+                NumTriggers { total, mcontrol6 } => {
+                    info!(target: TARGET, "{total} triggers, {mcontrol6} support mcontrol6")
                 }
+                NoShmemRejected => debug!(target: TARGET, "read without shmem rejected"),
+                NoShmemAccepted(ret) => fail!(record, "read without shmem not rejected: {ret:?}"),
+                RegisterFailed(error) => fail!(record, "set shmem failed: {error:?}"),
+                OutOfRangeRejected => debug!(target: TARGET, "out-of-range read rejected"),
+                OutOfRangeAccepted(ret) => fail!(record, "out-of-range read not rejected: {ret:?}"),
+                NoMcontrol6Trigger => warn!(target: TARGET, "no mcontrol6 trigger"),
+                Breakpoint(idx) => info!(target: TARGET, "breakpoint on trigger {idx}"),
+                BreakpointFailed(error) => fail!(record, "breakpoint failed: {error:?}"),
+                UnregisterFailed(error) => fail!(record, "disable shmem failed: {error:?}"),
             }
+            record.end(reporter);
         });
+        result &= record.finish();
+        let mut record = Record::new("MPXY");
+        mpxy::test(|case| {
+            use mpxy::Case::*;
+            record.begin(&case);
+            match case {
+                NotExist => skip!(record, "Sbi `MPXY` not exist"),
+                Begin => info!(target: TARGET, "Testing `MPXY`"),
+                Pass => info!(target: TARGET, "Sbi `MPXY` test pass"),
+                ShmemSize(size) => info!(target: TARGET, "shmem size = {size:#x}"),
+                InvalidShmemSize(size) => fail!(record, "invalid shmem size {size:#x}"),
+                NoShmemRejected => debug!(target: TARGET, "get channels without shmem rejected"),
+                NoShmemAccepted(ret) => {
+                    fail!(record, "get channels without shmem not rejected: {ret:?}")
+                }
+                MisalignedShmemRejected => debug!(target: TARGET, "misaligned shmem rejected"),
+                MisalignedShmemAccepted(ret) => {
+                    fail!(record, "misaligned shmem not rejected: {ret:?}")
+                }
+                ShmemTooLarge(size) => {
+                    warn!(target: TARGET, "shmem size {size:#x} too large to test channels")
+                }
+                RegisterFailed(error) => fail!(record, "set shmem failed: {error:?}"),
+                Channel {
+                    channel_id,
+                    attributes,
+                } => info!(target: TARGET, "channel {channel_id}: {attributes:?}"),
+                ChannelFailed(error) => fail!(record, "get channels failed: {error:?}"),
+                InvalidChannelRejected => debug!(target: TARGET, "invalid channel rejected"),
+                InvalidChannelAccepted(ret) => {
+                    fail!(record, "invalid channel not rejected: {ret:?}")
+                }
+                UnregisterFailed(error) => fail!(record, "disable shmem failed: {error:?}"),
+            }
+            record.end(reporter);
+        });
+        result &= record.finish();
+        reporter.end();
         result
     }
 }
+
+/// 记录失败信息并输出错误日志
+macro_rules! fail {
+    ($record:ident, $($arg:tt)+) => {{
+        error!(target: TARGET, $($arg)+);
+        $record.fail(format_args!($($arg)+));
+    }};
+}
+
+/// 记录跳过原因并输出警告日志
+macro_rules! skip {
+    ($record:ident, $reason:literal) => {{
+        warn!(target: TARGET, $reason);
+        $record.skip($reason);
+    }};
+}
+
+use {fail, skip};
+
+/// 一个扩展的测试结果，其中每个 `Case` 作为一个测试用例报告
+struct Record {
+    ext: &'static str,
+    passed: bool,
+    last: u64,
+    name: Message,
+    skip: Option<&'static str>,
+    failed: bool,
+    message: Message,
+}
+
+impl Record {
+    fn new(ext: &'static str) -> Self {
+        Self {
+            ext,
+            passed: true,
+            last: riscv::register::time::read64(),
+            name: Message::new(),
+            skip: None,
+            failed: false,
+            message: Message::new(),
+        }
+    }
+
+    /// 开始记录 `case`，用例名为 `<扩展>/<变体名>`
+    fn begin(&mut self, case: &impl fmt::Debug) {
+        self.name = Message::new();
+        self.skip = None;
+        self.failed = false;
+        self.message = Message::new();
+        let _ = write!(self.name, "{}/", self.ext);
+        let _ = write!(Variant(&mut self.name), "{case:?}");
+    }
+
+    /// 只保留用例的第一条失败信息
+    fn fail(&mut self, args: fmt::Arguments) {
+        if !self.failed {
+            self.failed = true;
+            self.passed = false;
+            let _ = self.message.write_fmt(args);
+        }
+    }
+
+    fn skip(&mut self, reason: &'static str) {
+        self.skip = Some(reason);
+    }
+
+    /// 向 `reporter` 报告当前用例，耗时从上一个用例结束时算起
+    fn end(&mut self, reporter: &mut impl Reporter) {
+        let ticks = riscv::register::time::read64().wrapping_sub(self.last);
+        let outcome = if self.failed {
+            Outcome::Fail(self.message.as_str())
+        } else if let Some(reason) = self.skip {
+            Outcome::Skip(reason)
+        } else {
+            Outcome::Pass
+        };
+        reporter.case(self.name.as_str(), outcome, ticks);
+        self.last = riscv::register::time::read64();
+    }
+
+    /// 返回扩展是否未失败
+    fn finish(self) -> bool {
+        self.passed
+    }
+}
+
+/// 只写入 `Debug` 输出开头的变体名
+struct Variant<'a>(&'a mut Message);
+
+impl Write for Variant<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match s.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')) {
+            Some(end) => {
+                self.0.write_str(&s[..end])?;
+                Err(fmt::Error)
+            }
+            None => self.0.write_str(s),
+        }
+    }
+}
+
+/// 定长的失败信息，超出部分被截断
+struct Message {
+    buf: [u8; 128],
+    len: usize,
+}
+
+impl Message {
+    const fn new() -> Self {
+        Self {
+            buf: [0; 128],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        // 只在字符边界截断
+        unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+}
+
+impl Write for Message {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut len = s.len().min(self.buf.len() - self.len);
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.buf[self.len..][..len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+        // 截断后不再写入
+        if len < s.len() {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Message, Variant};
+    use core::fmt::Write;
+
+    fn variant(case: &impl core::fmt::Debug) -> Message {
+        let mut name = Message::new();
+        let _ = write!(Variant(&mut name), "{case:?}");
+        name
+    }
+
+    #[test]
+    fn variant_name() {
+        use crate::{hsm, time};
+        assert_eq!(variant(&time::Case::Begin).as_str(), "Begin");
+        assert_eq!(variant(&hsm::Case::HartStarted(3)).as_str(), "HartStarted");
+        let case = time::Case::TimeDecreased { a: 2, b: 1 };
+        assert_eq!(variant(&case).as_str(), "TimeDecreased");
+    }
+}
@@ -0,0 +1,181 @@
+//! Standalone bootable image running all tests on any SBI implementation.
+//!
+//! The image is linked at `0x80200000` with a RISC-V Linux image header, thus can be booted
+//! as the next stage of any SBI implementation, e.g. with `qemu-system-riscv64 -kernel`.
+//! The boot hart parses the device tree, prints logs to the SBI console, runs all tests on
+//! every hart listed in the device tree, and finally shuts the system down through `SRST`
+//! extension with the test result. Other harts entering the image are stopped.
+//!
+//! The following environment variables are read at build time:
+//!
+//! - `LOG`: maximum log level, `info` by default;
+//! - `REPORT`: `tap` or `junit` to stream machine-readable results;
+//! - `FUZZ`: seed to send randomized malformed calls after testing, memory before this image
+//!   is treated as SBI implementation memory.
+#![no_std]
+#![no_main]
+#![deny(warnings)]
+
+use core::{
+    arch::naked_asm,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
+use log::{LevelFilter, error, info, warn};
+use sbi::console::{SbiConsole, SbiLogger};
+use sbi_spec::hsm::{EID_HSM, HART_STOP};
+use sbi_testing::{BoardInfo, FuzzCase, JUnit, Tap, Testing, sbi, test_fuzz};
+
+const RISCV_HEAD_FLAGS: u64 = 0;
+const RISCV_HEADER_VERSION: u32 = 0x2;
+const RISCV_IMAGE_MAGIC: u64 = 0x5643534952; /* Magic number, little endian, "RISCV" */
+const RISCV_IMAGE_MAGIC2: u32 = 0x05435352; /* Magic number 2, little endian, "RSC\x05" */
+
+/// RISC-V Linux 镜像头
+#[unsafe(naked)]
+#[unsafe(no_mangle)]
+#[unsafe(link_section = ".head.text")]
+unsafe extern "C" fn _boot_header() -> ! {
+    naked_asm!(
+        "j _start",
+        ".word 0",
+        ".balign 8",
+        ".dword 0x200000",
+        ".dword iend - istart",
+        ".dword {RISCV_HEAD_FLAGS}",
+        ".word  {RISCV_HEADER_VERSION}",
+        ".word  0",
+        ".dword 0",
+        ".dword {RISCV_IMAGE_MAGIC}",
+        ".balign 4",
+        ".word  {RISCV_IMAGE_MAGIC2}",
+        ".word  0",
+        RISCV_HEAD_FLAGS = const RISCV_HEAD_FLAGS,
+        RISCV_HEADER_VERSION = const RISCV_HEADER_VERSION,
+        RISCV_IMAGE_MAGIC = const RISCV_IMAGE_MAGIC,
+        RISCV_IMAGE_MAGIC2 = const RISCV_IMAGE_MAGIC2,
+    );
+}
+
+/// 抽签决定启动核，初值非 0 以放在 .data 段，不被清零
+static LOTTERY: AtomicU32 = AtomicU32::new(1);
+
+/// 镜像入口，只有一个核能进入 [`rust_main`]
+#[unsafe(naked)]
+#[unsafe(no_mangle)]
+#[unsafe(link_section = ".text.entry")]
+unsafe extern "C" fn _start(hartid: usize, dtb: usize) -> ! {
+    const STACK_SIZE: usize = 16384; // 16 KiB
+
+    #[unsafe(link_section = ".bss.uninit")]
+    static mut STACK: [u8; STACK_SIZE] = [0u8; STACK_SIZE];
+
+    naked_asm!(
+        // 抽签，未抽中的核不使用栈，直接关闭
+        "   .option push
+            .option arch, +a
+            la      t0, {lottery}
+            amoswap.w t0, zero, (t0)
+            .option pop
+            beqz    t0, 3f",
+        // clear bss segment
+        "   la      t0, sbss
+            la      t1, ebss
+        1:  bgeu    t0, t1, 2f
+            sd      zero, 0(t0)
+            addi    t0, t0, 8
+            j       1b",
+        "2:",
+        "   la sp, {stack} + {stack_size}",
+        "   j  {main}",
+        // 不支持 HSM 时休眠
+        "3: li      a7, {eid_hsm}
+            li      a6, {hart_stop}
+            ecall
+        4:  wfi
+            j       4b",
+        lottery    =   sym LOTTERY,
+        stack_size = const STACK_SIZE,
+        stack      =   sym STACK,
+        main       =   sym rust_main,
+        eid_hsm    = const EID_HSM,
+        hart_stop  = const HART_STOP,
+    )
+}
+
+extern "C" fn rust_main(hartid: usize, dtb: usize) -> ! {
+    let level = option_env!("LOG")
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(LevelFilter::Info);
+    let _ = SbiLogger::init(level);
+    let Some(board) = (unsafe { BoardInfo::parse(dtb) }) else {
+        error!("invalid device tree at {dtb:#x}");
+        exit(false)
+    };
+    info!(
+        "boot hart {hartid}, {} harts, timebase {} Hz, memory {:#x?}, dtb {dtb:#x}",
+        board.hart_count, board.frequency, board.memory
+    );
+    if board.hart_count > usize::BITS as usize {
+        warn!(
+            "only harts in [{}, {}) are tested",
+            board.hart_mask_base,
+            board.hart_mask_base + usize::BITS as usize
+        );
+    }
+    let testing = Testing {
+        hartid,
+        hart_mask: board.hart_mask,
+        hart_mask_base: board.hart_mask_base,
+        delay: board.frequency,
+    };
+    let console = SbiConsole::new();
+    let mut result = match option_env!("REPORT") {
+        Some("tap") => testing.report(&mut Tap::new(console, board.frequency)),
+        Some("junit") => testing.report(&mut JUnit::new(console, board.frequency)),
+        _ => testing.test(),
+    };
+    if let Some(seed) = option_env!("FUZZ").and_then(|seed| seed.parse().ok()) {
+        let image = _boot_header as *const () as usize;
+        let protected = board.memory.start..image.clamp(board.memory.start, board.memory.end);
+        let mut pass = false;
+        test_fuzz(seed, 64, protected, |case| match case {
+            FuzzCase::Begin => info!("fuzz seed = {seed}"),
+            FuzzCase::Extension(eid) => info!("fuzz extension {eid:#x}"),
+            FuzzCase::Call(call) => log::trace!("{call:x?}"),
+            FuzzCase::Pass => {
+                info!("fuzz test pass");
+                pass = true;
+            }
+            case => error!("{case:x?}"),
+        });
+        result &= pass;
+    }
+    exit(result)
+}
+
+/// 以测试结果关机
+fn exit(pass: bool) -> ! {
+    if pass {
+        info!("SBI test PASSED");
+        sbi::system_reset(sbi::Shutdown, sbi::NoReason);
+    } else {
+        error!("SBI test FAILED");
+        sbi::system_reset(sbi::Shutdown, sbi::SystemFailure);
+    }
+    // 不支持 SRST 扩展时使用遗留关机调用，它返回时会引发恐慌
+    #[allow(deprecated)]
+    sbi::legacy::shutdown()
+}
+
+#[panic_handler]
+fn panic(info: &core::panic::PanicInfo) -> ! {
+    // 遗留关机调用也返回时不再重复关机
+    static PANICKED: AtomicBool = AtomicBool::new(false);
+    if PANICKED.swap(true, Ordering::Relaxed) {
+        loop {
+            riscv::asm::wfi();
+        }
+    }
+    error!("panicked: {info}");
+    exit(false)
+}
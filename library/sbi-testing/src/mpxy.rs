@@ -0,0 +1,150 @@
+//! Message proxy extension test suite.
+
+use sbi::{
+    SbiRet, SharedPtr,
+    rpmi::{ChannelAttributes, MpxyClient},
+};
+use sbi_spec::binary::Error;
+
+/// Message proxy extension test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for message proxy extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for size of shared memory required, in bytes.
+    ShmemSize(usize),
+    /// Test failed for shared memory size is zero or not a multiple of 4 KiB.
+    InvalidShmemSize(usize),
+    /// Test process for getting channels without shared memory has been rejected.
+    NoShmemRejected,
+    /// Test failed for getting channels without shared memory is not rejected with `SBI_ERR_NO_SHMEM`.
+    NoShmemAccepted(SbiRet),
+    /// Test process for misaligned shared memory has been rejected.
+    MisalignedShmemRejected,
+    /// Test failed for misaligned shared memory is not rejected with `SBI_ERR_INVALID_PARAM`.
+    MisalignedShmemAccepted(SbiRet),
+    /// Test process for shared memory larger than this test provides, channels are not tested.
+    ShmemTooLarge(usize),
+    /// Test failed for can't set message proxy shared memory.
+    RegisterFailed(Error),
+    /// Test process for attributes of a message channel.
+    Channel {
+        /// The message channel ID.
+        channel_id: u32,
+        /// Standard attributes of the channel.
+        attributes: ChannelAttributes,
+    },
+    /// Test failed for can't get channel IDs or attributes.
+    ChannelFailed(Error),
+    /// Test process for reading attributes of an invalid channel has been rejected.
+    InvalidChannelRejected,
+    /// Test failed for reading attributes of an invalid channel is not rejected with `SBI_ERR_NOT_SUPPORTED`.
+    InvalidChannelAccepted(Result<ChannelAttributes, Error>),
+    /// Test failed for can't disable message proxy shared memory.
+    UnregisterFailed(Error),
+    /// All test cases on message proxy extension has passed.
+    Pass,
+}
+
+/// Test message proxy extension.
+///
+/// Memory must be identically mapped on the current hart.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(mut f: impl FnMut(Case)) {
+    // 不支持 MPXY 扩展
+    if sbi::probe_extension(sbi::Mpxy).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    let size = sbi::mpxy_get_shmem_size();
+    if size == 0 || !size.is_multiple_of(PAGE_SIZE) {
+        f(Case::InvalidShmemSize(size));
+        return;
+    }
+    f(Case::ShmemSize(size));
+    // 未设置共享内存时不能获取通道
+    let ret = sbi::mpxy_get_channel_ids(0);
+    if ret == SbiRet::no_shmem() {
+        f(Case::NoShmemRejected);
+    } else {
+        f(Case::NoShmemAccepted(ret));
+        return;
+    }
+    // 共享内存必须 4 KiB 对齐
+    let misaligned = &raw const CLIENT as usize + 8;
+    let ret = sbi::mpxy_set_shmem(SharedPtr::new(misaligned, 0), 0);
+    if ret == SbiRet::invalid_param() {
+        f(Case::MisalignedShmemRejected);
+    } else {
+        let _ = MpxyClient::<PAGES>::unregister();
+        f(Case::MisalignedShmemAccepted(ret));
+        return;
+    }
+    if size > PAGES * PAGE_SIZE {
+        f(Case::ShmemTooLarge(size));
+        f(Case::Pass);
+        return;
+    }
+    let client = unsafe { &mut *core::ptr::addr_of_mut!(CLIENT) };
+    if let Err(error) = unsafe { client.register() } {
+        f(Case::RegisterFailed(error));
+        return;
+    }
+    // 枚举通道
+    let mut max_channel_id = None;
+    let mut index = 0;
+    loop {
+        let channel_id = match client.channel_id(index) {
+            Ok(Some(channel_id)) => channel_id,
+            Ok(None) => break,
+            Err(error) => {
+                let _ = MpxyClient::<PAGES>::unregister();
+                f(Case::ChannelFailed(error));
+                return;
+            }
+        };
+        match client.channel_attributes(channel_id) {
+            Ok(attributes) => f(Case::Channel {
+                channel_id,
+                attributes,
+            }),
+            Err(error) => {
+                let _ = MpxyClient::<PAGES>::unregister();
+                f(Case::ChannelFailed(error));
+                return;
+            }
+        }
+        max_channel_id = max_channel_id.max(Some(channel_id));
+        index += 1;
+    }
+    // 不存在的通道
+    let invalid = match max_channel_id {
+        Some(u32::MAX) => None,
+        Some(id) => Some(id + 1),
+        None => Some(0),
+    };
+    if let Some(channel_id) = invalid {
+        match client.channel_attributes(channel_id) {
+            Err(Error::NotSupported) => f(Case::InvalidChannelRejected),
+            result => {
+                let _ = MpxyClient::<PAGES>::unregister();
+                f(Case::InvalidChannelAccepted(result));
+                return;
+            }
+        }
+    }
+    if let Err(error) = MpxyClient::<PAGES>::unregister() {
+        f(Case::UnregisterFailed(error));
+        return;
+    }
+    f(Case::Pass);
+}
+
+const PAGE_SIZE: usize = 4096;
+const PAGES: usize = 4;
+
+static mut CLIENT: MpxyClient<PAGES> = MpxyClient::new();
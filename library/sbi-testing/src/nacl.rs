@@ -0,0 +1,127 @@
+//! Nested acceleration extension test suite.
+
+use sbi::{NaclFeatures, NaclShmem, SbiRet, SharedPtr};
+use sbi_spec::nacl::shmem_size;
+
+/// Nested acceleration extension test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for nested acceleration extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for nested acceleration features provided.
+    Features(NaclFeatures),
+    /// Test process for reserved feature is reported as unavailable.
+    ReservedFeatureUnavailable,
+    /// Test failed for reserved feature is not reported as unavailable.
+    ReservedFeatureAvailable(SbiRet),
+    /// Test process for synchronizing without shared memory has been rejected.
+    NoShmemRejected,
+    /// Test failed for synchronizing without shared memory is not rejected with `SBI_ERR_NO_SHMEM`.
+    NoShmemAccepted(SbiRet),
+    /// Test process for misaligned shared memory has been rejected.
+    MisalignedShmemRejected,
+    /// Test failed for misaligned shared memory is not rejected with `SBI_ERR_INVALID_PARAM`.
+    MisalignedShmemAccepted(SbiRet),
+    /// Test process for reserved flags have been rejected.
+    InvalidFlagsRejected,
+    /// Test failed for reserved flags are not rejected with `SBI_ERR_INVALID_PARAM`.
+    InvalidFlagsAccepted(SbiRet),
+    /// Test failed for can't set nested acceleration shared memory.
+    RegisterFailed(SbiRet),
+    /// Test process for all CSRs in shared memory have been synchronized.
+    SyncCsr,
+    /// Test failed for synchronizing CSRs returned an error.
+    SyncCsrFailed(SbiRet),
+    /// Test failed for can't disable nested acceleration shared memory.
+    UnregisterFailed(SbiRet),
+    /// All test cases on nested acceleration extension has passed.
+    Pass,
+}
+
+/// Test nested acceleration extension.
+///
+/// Memory must be identically mapped on the current hart.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(mut f: impl FnMut(Case)) {
+    // 不支持 NACL 扩展
+    if sbi::probe_extension(sbi::Nacl).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    let features = NaclFeatures::probe();
+    f(Case::Features(features));
+    // 保留的特性不可用
+    let ret = sbi::nacl_probe_feature(RESERVED_FEATURE);
+    if ret == SbiRet::success(0) {
+        f(Case::ReservedFeatureUnavailable);
+    } else {
+        f(Case::ReservedFeatureAvailable(ret));
+        return;
+    }
+    // 未设置共享内存时不能同步
+    if features.sync_csr {
+        let ret = sbi::nacl_sync_csr(usize::MAX);
+        if ret == SbiRet::no_shmem() {
+            f(Case::NoShmemRejected);
+        } else {
+            f(Case::NoShmemAccepted(ret));
+            return;
+        }
+    }
+    // 共享内存必须 4 KiB 对齐
+    let misaligned = &raw const BUFFER as usize + 8;
+    let ret = sbi::nacl_set_shmem(SharedPtr::new(misaligned, 0), 0);
+    if ret == SbiRet::invalid_param() {
+        f(Case::MisalignedShmemRejected);
+    } else {
+        let _ = NaclShmem::unregister();
+        f(Case::MisalignedShmemAccepted(ret));
+        return;
+    }
+    // 标志位保留
+    let ret = sbi::nacl_set_shmem(SharedPtr::new(&raw const BUFFER as usize, 0), 1);
+    if ret == SbiRet::invalid_param() {
+        f(Case::InvalidFlagsRejected);
+    } else {
+        let _ = NaclShmem::unregister();
+        f(Case::InvalidFlagsAccepted(ret));
+        return;
+    }
+    // 注册并同步所有 CSR
+    let shmem = unsafe { &mut *core::ptr::addr_of_mut!(SHMEM) };
+    let ret = unsafe { shmem.register() };
+    if ret.is_err() {
+        f(Case::RegisterFailed(ret));
+        return;
+    }
+    if features.sync_csr {
+        let ret = shmem.sync_csr(None);
+        if ret.is_err() {
+            let _ = NaclShmem::unregister();
+            f(Case::SyncCsrFailed(ret));
+            return;
+        }
+        f(Case::SyncCsr);
+    }
+    let ret = NaclShmem::unregister();
+    if ret.is_err() {
+        f(Case::UnregisterFailed(ret));
+        return;
+    }
+    f(Case::Pass);
+}
+
+/// 第一个保留的特性
+const RESERVED_FEATURE: u32 = 4;
+
+static mut SHMEM: NaclShmem = NaclShmem::new();
+
+/// 错误地接受错位地址时，实现写入的范围仍在缓冲区内
+#[repr(C, align(4096))]
+struct Buffer([u8; 2 * shmem_size::NATIVE]);
+
+static mut BUFFER: Buffer = Buffer([0; 2 * shmem_size::NATIVE]);
@@ -0,0 +1,325 @@
+//! Performance monitoring unit extension test suite.
+
+use core::hint::black_box;
+use sbi::{CounterMask, PmuCounter, PmuSnapshot, SbiRet, SharedPtr};
+use sbi_spec::{
+    binary::Error,
+    pmu::{
+        CounterInfo, EventIdx, firmware_event,
+        flags::{CounterCfgFlags, CounterStartFlags, CounterStopFlags},
+        hardware_event,
+    },
+};
+
+/// Performance monitoring unit extension test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for performance monitoring unit extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for number of counters.
+    NumCounters(usize),
+    /// Test process for details of a counter.
+    CounterInfo {
+        /// Index of the counter.
+        idx: usize,
+        /// Details of the counter.
+        info: CounterInfo,
+    },
+    /// Test failed for can't get details of a counter.
+    CounterInfoFailed {
+        /// Index of the counter.
+        idx: usize,
+        /// The `SbiRet` value for the failed SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for a hardware counter is not backed by a `hpmcounter` CSR.
+    InvalidHardwareCounter {
+        /// Index of the counter.
+        idx: usize,
+        /// Details of the counter.
+        info: CounterInfo,
+    },
+    /// Test process for an event is not supported by SBI implementation.
+    EventNotSupported(EventIdx),
+    /// Test failed for can't configure a counter to monitor an event.
+    EventConfigFailed {
+        /// The event to be monitored.
+        event: EventIdx,
+        /// Error returned by SBI implementation.
+        error: Error,
+    },
+    /// Test failed for an event is mapped to a counter of different type.
+    CounterTypeMismatch {
+        /// The event to be monitored.
+        event: EventIdx,
+        /// Index of the counter the event is mapped to.
+        idx: usize,
+    },
+    /// Test process for an event is counted by a counter.
+    EventCounted {
+        /// The event monitored.
+        event: EventIdx,
+        /// Index of the counter the event is mapped to.
+        idx: usize,
+        /// Events counted during the workload.
+        delta: u64,
+    },
+    /// Test failed for an event that must occur during the workload is not counted.
+    EventNotCounted {
+        /// The event monitored.
+        event: EventIdx,
+        /// Index of the counter the event is mapped to.
+        idx: usize,
+    },
+    /// Test failed for a counter operation returned an error.
+    CounterFailed {
+        /// The event monitored.
+        event: EventIdx,
+        /// Error returned by SBI implementation.
+        error: Error,
+    },
+    /// Test failed for starting a started counter is not rejected with `SBI_ERR_ALREADY_STARTED`.
+    AlreadyStartedNotReported(SbiRet),
+    /// Test failed for stopping a stopped counter is not rejected with `SBI_ERR_ALREADY_STOPPED`.
+    AlreadyStoppedNotReported(SbiRet),
+    /// Test process for counter start and stop semantics.
+    StartStopSemantics,
+    /// Test process for snapshot shared memory is not supported.
+    SnapshotNotSupported,
+    /// Test failed for can't set snapshot shared memory.
+    SnapshotFailed(SbiRet),
+    /// Test process for counter value saved into snapshot shared memory.
+    Snapshot(u64),
+    /// Test failed for counter value in snapshot differs from the stopped counter value.
+    SnapshotMismatch {
+        /// Counter value saved into snapshot shared memory.
+        snapshot: u64,
+        /// Counter value read after stopping.
+        counter: u64,
+    },
+    /// All test cases on performance monitoring unit extension has passed.
+    Pass,
+}
+
+/// Test performance monitoring unit extension.
+///
+/// Memory must be identically mapped on the current hart to test the snapshot shared memory.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(mut f: impl FnMut(Case)) {
+    // 不支持 PMU 扩展
+    if sbi::probe_extension(sbi::Pmu).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    // 枚举计数器
+    let num = sbi::pmu_num_counters();
+    f(Case::NumCounters(num));
+    for idx in 0..num {
+        let ret = sbi::pmu_counter_get_info(idx);
+        let Some(raw) = ret.ok() else {
+            f(Case::CounterInfoFailed { idx, ret });
+            return;
+        };
+        let info = CounterInfo::from_raw(raw);
+        if info.is_hardware() && !(0xc00..0xc20).contains(&info.csr()) {
+            f(Case::InvalidHardwareCounter { idx, info });
+            return;
+        }
+        f(Case::CounterInfo { idx, info });
+    }
+    let counters = CounterMask::from_mask_base(
+        if num >= usize::BITS as usize {
+            usize::MAX
+        } else {
+            (1 << num) - 1
+        },
+        0,
+    );
+    // 硬件事件
+    for code in hardware_event::CPU_CYCLES..=hardware_event::REF_CPU_CYCLES {
+        let event = EventIdx::hardware_general(code);
+        let must_count = matches!(
+            code,
+            hardware_event::CPU_CYCLES | hardware_event::INSTRUCTIONS
+        );
+        if !test_event(counters, event, must_count, workload, &mut f) {
+            return;
+        }
+    }
+    // 固件事件，只有 `SET_TIMER` 能确定地触发
+    for code in firmware_event::MISALIGNED_LOAD..=firmware_event::HFENCE_VVMA_ASID_RECEIVED {
+        let event = EventIdx::firmware(code);
+        let must_count = code == firmware_event::SET_TIMER;
+        let trigger = || {
+            if must_count {
+                sbi::set_timer(u64::MAX);
+            }
+        };
+        if !test_event(counters, event, must_count, trigger, &mut f) {
+            return;
+        }
+    }
+    // 启停语义
+    if !test_start_stop(counters, &mut f) {
+        return;
+    }
+    // 快照共享内存
+    if !test_snapshot(counters, &mut f) {
+        return;
+    }
+    f(Case::Pass);
+}
+
+/// 供硬件事件计数的负载
+fn workload() {
+    let mut sum = 0usize;
+    for i in 0..1000 {
+        sum = black_box(sum + i);
+    }
+    black_box(sum);
+}
+
+/// 测试一个事件，不支持的事件不视为失败
+fn test_event(
+    counters: CounterMask,
+    event: EventIdx,
+    must_count: bool,
+    trigger: impl FnOnce(),
+    f: &mut impl FnMut(Case),
+) -> bool {
+    let mut counter = match PmuCounter::config(counters, event, 0, CounterCfgFlags::CLEAR_VALUE) {
+        Ok(counter) => counter,
+        Err(Error::NotSupported) => {
+            f(Case::EventNotSupported(event));
+            return true;
+        }
+        Err(error) => {
+            f(Case::EventConfigFailed { event, error });
+            return false;
+        }
+    };
+    let idx = counter.counter_idx();
+    if counter.info().is_firmware() != event.is_firmware_event() {
+        f(Case::CounterTypeMismatch { event, idx });
+        return false;
+    }
+    let result = (|| {
+        counter.start()?;
+        let before = counter.read()?;
+        trigger();
+        counter.stop()?;
+        Ok(counter.delta(before, counter.read()?))
+    })();
+    match result {
+        Ok(0) if must_count => {
+            f(Case::EventNotCounted { event, idx });
+            false
+        }
+        Ok(delta) => {
+            f(Case::EventCounted { event, idx, delta });
+            true
+        }
+        Err(error) => {
+            f(Case::CounterFailed { event, error });
+            false
+        }
+    }
+}
+
+/// 测试重复启动和重复停止计数器
+fn test_start_stop(counters: CounterMask, f: &mut impl FnMut(Case)) -> bool {
+    let events = [
+        EventIdx::hardware_general(hardware_event::CPU_CYCLES),
+        EventIdx::firmware(firmware_event::SET_TIMER),
+    ];
+    let mut configured = None;
+    for event in events {
+        match PmuCounter::config(counters, event, 0, CounterCfgFlags::AUTO_START) {
+            Ok(counter) => {
+                configured = Some((event, counter));
+                break;
+            }
+            Err(Error::NotSupported) => f(Case::EventNotSupported(event)),
+            Err(error) => {
+                f(Case::EventConfigFailed { event, error });
+                return false;
+            }
+        }
+    }
+    // 没有可用的事件，无法测试
+    let Some((event, counter)) = configured else {
+        return true;
+    };
+    let mask = CounterMask::from_mask_base(1, counter.counter_idx());
+    let ret = sbi::pmu_counter_start(mask, CounterStartFlags::empty(), 0);
+    if ret != SbiRet::already_started() {
+        f(Case::AlreadyStartedNotReported(ret));
+        return false;
+    }
+    let ret = sbi::pmu_counter_stop(mask, CounterStopFlags::empty());
+    if let Err(error) = ret.into_result() {
+        f(Case::CounterFailed { event, error });
+        return false;
+    }
+    let ret = sbi::pmu_counter_stop(mask, CounterStopFlags::empty());
+    if ret != SbiRet::already_stopped() {
+        f(Case::AlreadyStoppedNotReported(ret));
+        return false;
+    }
+    // `counter` 被认为仍在运行，由 drop 直接停止并释放
+    let ret = sbi::pmu_counter_start(mask, CounterStartFlags::empty(), 0);
+    if let Err(error) = ret.into_result() {
+        f(Case::CounterFailed { event, error });
+        return false;
+    }
+    drop(counter);
+    f(Case::StartStopSemantics);
+    true
+}
+
+static SNAPSHOT: PmuSnapshot = PmuSnapshot::new();
+
+/// 测试快照共享内存
+fn test_snapshot(counters: CounterMask, f: &mut impl FnMut(Case)) -> bool {
+    let ret = unsafe { sbi::pmu_snapshot_register(&SNAPSHOT) };
+    if ret == SbiRet::not_supported() {
+        f(Case::SnapshotNotSupported);
+        return true;
+    } else if ret.is_err() {
+        f(Case::SnapshotFailed(ret));
+        return false;
+    }
+    let event = EventIdx::hardware_general(hardware_event::CPU_CYCLES);
+    let result = PmuCounter::config(counters, event, 0, CounterCfgFlags::CLEAR_VALUE).and_then(
+        |mut counter| {
+            counter.start()?;
+            workload();
+            let (snapshot, _) = counter.stop_snapshot(&SNAPSHOT)?;
+            Ok((snapshot, counter.read()?))
+        },
+    );
+    // 关闭快照共享内存
+    let _ = sbi::pmu_snapshot_set_shmem(SharedPtr::new(usize::MAX, usize::MAX), 0);
+    match result {
+        Ok((snapshot, counter)) if snapshot == counter => {
+            f(Case::Snapshot(snapshot));
+            true
+        }
+        Ok((snapshot, counter)) => {
+            f(Case::SnapshotMismatch { snapshot, counter });
+            false
+        }
+        Err(Error::NotSupported) => {
+            f(Case::EventNotSupported(event));
+            true
+        }
+        Err(error) => {
+            f(Case::CounterFailed { event, error });
+            false
+        }
+    }
+}
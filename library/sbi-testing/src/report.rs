@@ -0,0 +1,202 @@
+//! Machine-readable test results.
+
+use core::fmt::{self, Write};
+
+/// Outcome of a test case.
+#[derive(Clone, Copy, Debug)]
+pub enum Outcome<'a> {
+    /// The test case has passed.
+    Pass,
+    /// The test case has failed, with the first failure message.
+    Fail(&'a str),
+    /// The test case is skipped, with the reason.
+    Skip(&'a str),
+}
+
+/// Receiver of test case results.
+pub trait Reporter {
+    /// Called once before any test case is reported.
+    fn begin(&mut self) {}
+    /// Report result of test case `name`, which took `ticks` of the `time` CSR to run.
+    fn case(&mut self, name: &str, outcome: Outcome, ticks: u64);
+    /// Called once after all test cases are reported.
+    fn end(&mut self) {}
+}
+
+/// Discard all results.
+impl Reporter for () {
+    #[inline]
+    fn case(&mut self, _name: &str, _outcome: Outcome, _ticks: u64) {}
+}
+
+/// Report results in [Test Anything Protocol](https://testanything.org/tap-version-13-specification.html) version 13.
+///
+/// The plan line is written after all test cases, durations are written as YAML diagnostics.
+pub struct Tap<W> {
+    writer: W,
+    frequency: u64,
+    count: usize,
+}
+
+impl<W: Write> Tap<W> {
+    /// Create a TAP reporter writing to `writer`, with `time` CSR running at `frequency` Hz.
+    #[inline]
+    pub const fn new(writer: W, frequency: u64) -> Self {
+        Self {
+            writer,
+            frequency,
+            count: 0,
+        }
+    }
+}
+
+impl<W: Write> Reporter for Tap<W> {
+    fn begin(&mut self) {
+        let _ = writeln!(self.writer, "TAP version 13");
+    }
+
+    fn case(&mut self, name: &str, outcome: Outcome, ticks: u64) {
+        self.count += 1;
+        let w = &mut self.writer;
+        let n = self.count;
+        let duration = Seconds::new(ticks, self.frequency);
+        // 诊断信息均为单行，不含换行符
+        let _ = match outcome {
+            Outcome::Pass => writeln!(w, "ok {n} - {name}\n  ---\n  duration: {duration}\n  ..."),
+            Outcome::Fail(message) => writeln!(
+                w,
+                "not ok {n} - {name}\n  ---\n  message: {:?}\n  duration: {duration}\n  ...",
+                OneLine(message)
+            ),
+            Outcome::Skip(reason) => writeln!(w, "ok {n} - {name} # SKIP {}", OneLine(reason)),
+        };
+    }
+
+    fn end(&mut self) {
+        let _ = writeln!(self.writer, "1..{}", self.count);
+    }
+}
+
+/// Report results as JUnit XML.
+///
+/// All test cases are reported in one `<testsuite>` element named `sbi-testing`.
+pub struct JUnit<W> {
+    writer: W,
+    frequency: u64,
+}
+
+impl<W: Write> JUnit<W> {
+    /// Create a JUnit reporter writing to `writer`, with `time` CSR running at `frequency` Hz.
+    #[inline]
+    pub const fn new(writer: W, frequency: u64) -> Self {
+        Self { writer, frequency }
+    }
+}
+
+impl<W: Write> Reporter for JUnit<W> {
+    fn begin(&mut self) {
+        let _ = writeln!(
+            self.writer,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n<testsuite name=\"sbi-testing\">"
+        );
+    }
+
+    fn case(&mut self, name: &str, outcome: Outcome, ticks: u64) {
+        let w = &mut self.writer;
+        let name = Xml(name);
+        let duration = Seconds::new(ticks, self.frequency);
+        let _ = match outcome {
+            Outcome::Pass => writeln!(
+                w,
+                "<testcase classname=\"sbi-testing\" name=\"{name}\" time=\"{duration}\"/>"
+            ),
+            Outcome::Fail(message) => writeln!(
+                w,
+                "<testcase classname=\"sbi-testing\" name=\"{name}\" time=\"{duration}\"><failure message=\"{}\"/></testcase>",
+                Xml(message)
+            ),
+            Outcome::Skip(reason) => writeln!(
+                w,
+                "<testcase classname=\"sbi-testing\" name=\"{name}\" time=\"{duration}\"><skipped message=\"{}\"/></testcase>",
+                Xml(reason)
+            ),
+        };
+    }
+
+    fn end(&mut self) {
+        let _ = writeln!(self.writer, "</testsuite>\n</testsuites>");
+    }
+}
+
+/// `time` CSR 计数转换的秒数，保留 6 位小数
+struct Seconds {
+    ticks: u64,
+    frequency: u64,
+}
+
+impl Seconds {
+    #[inline]
+    const fn new(ticks: u64, frequency: u64) -> Self {
+        Self { ticks, frequency }
+    }
+}
+
+impl fmt::Display for Seconds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = self.frequency.max(1);
+        let secs = self.ticks / frequency;
+        let micros = (self.ticks % frequency) as u128 * 1_000_000 / frequency as u128;
+        write!(f, "{secs}.{micros:06}")
+    }
+}
+
+/// 将换行符替换为空格
+struct OneLine<'a>(&'a str);
+
+impl fmt::Display for OneLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, line) in self.0.lines().enumerate() {
+            if i > 0 {
+                f.write_char(' ')?;
+            }
+            f.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for OneLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '\n' | '\r' => f.write_char(' ')?,
+                '"' | '\\' => {
+                    f.write_char('\\')?;
+                    f.write_char(c)?;
+                }
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+/// XML 属性值转义
+struct Xml<'a>(&'a str);
+
+impl fmt::Display for Xml<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\n' => f.write_str("&#10;")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
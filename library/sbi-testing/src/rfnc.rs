@@ -0,0 +1,402 @@
+//! Remote fence extension test suite.
+
+use core::sync::atomic::{AtomicUsize, Ordering, fence};
+use sbi::{HartMask, SbiRet};
+use sbi_spec::hsm::hart_state;
+
+/// Remote fence extension test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for remote fence extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for remote fence on an invalid hart mask has been rejected.
+    InvalidHartMaskRejected,
+    /// Test failed for remote fence on an invalid hart mask is not rejected with `SBI_ERR_INVALID_PARAM`.
+    InvalidHartMaskAccepted(SbiRet),
+    /// Test process for remote fence on an out-of-range address has been rejected.
+    InvalidAddressRejected,
+    /// Test process for remote fence on an out-of-range address has been handled as a full flush.
+    InvalidAddressFlushedAll,
+    /// Test failed for remote fence on an out-of-range address returned an unexpected error.
+    InvalidAddressFailed(SbiRet),
+    /// Test failed for no other harts are available to be tested.
+    NoStoppedHart,
+    /// Test failed for can't start target hart with [`SbiRet`] error.
+    HartStartFailed {
+        /// The target hart ID that has failed to start.
+        hartid: usize,
+        /// The `SbiRet` value for the failed hart start SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for target hart doesn't support Sv39, remote `sfence.vma` is not tested.
+    PagingNotSupported(usize),
+    /// Test process for target hart observed the changed page table after `remote_sfence_vma`.
+    RemoteSfenceVma(usize),
+    /// Test process for target hart observed the changed page table after `remote_sfence_vma_asid`.
+    RemoteSfenceVmaAsid {
+        /// The target hart ID.
+        hartid: usize,
+        /// The address space ID the target hart is running with.
+        asid: usize,
+    },
+    /// Test process for target hart executed the modified code after `remote_fence_i`.
+    RemoteFenceI(usize),
+    /// Test failed for a remote fence SBI call returned an error.
+    FenceFailed {
+        /// Name of the failed SBI function.
+        function: &'static str,
+        /// The `SbiRet` value for the failed SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for target hart still observed the stale page table entry.
+    StaleTranslation {
+        /// The target hart ID.
+        hartid: usize,
+        /// The address space ID the target hart is running with.
+        asid: usize,
+    },
+    /// Test failed for target hart still executed the stale instruction.
+    StaleInstruction(usize),
+    /// Test failed for target hart trapped with the given `scause`.
+    UnexpectedTrap {
+        /// The target hart ID.
+        hartid: usize,
+        /// Value of `scause` on target hart.
+        scause: usize,
+    },
+    /// All test cases on remote fence extension has passed.
+    Pass,
+}
+
+/// Test remote fence extension on given harts.
+///
+/// The first stopped hart except `primary_hart_id` in the hart list is started to observe
+/// remote fences. Memory must be identically mapped on the current hart, and the target hart
+/// runs Sv39 page tables built by this test.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(
+    primary_hart_id: usize,
+    mut hart_mask: usize,
+    hart_mask_base: usize,
+    mut f: impl FnMut(Case),
+) {
+    // 不支持 RFNC 扩展
+    if sbi::probe_extension(sbi::Fence).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    // 无效的核掩码
+    let ret = sbi::remote_fence_i(HartMask::from_mask_base(1, usize::MAX - 1));
+    if ret == SbiRet::invalid_param() {
+        f(Case::InvalidHartMaskRejected);
+    } else {
+        f(Case::InvalidHartMaskAccepted(ret));
+        return;
+    }
+    // 越界的地址范围
+    let ret = sbi::remote_sfence_vma(
+        HartMask::from_mask_base(1, primary_hart_id),
+        usize::MAX & !(PAGE_SIZE - 1),
+        2 * PAGE_SIZE,
+    );
+    if ret == SbiRet::invalid_address() {
+        f(Case::InvalidAddressRejected);
+    } else if ret.is_ok() {
+        f(Case::InvalidAddressFlushedAll);
+    } else {
+        f(Case::InvalidAddressFailed(ret));
+        return;
+    }
+    // 找到一个停止状态的副核
+    let mut hartid = hart_mask_base;
+    while hart_mask != 0 {
+        if hart_mask & 1 == 1
+            && hartid != primary_hart_id
+            && sbi::hart_get_status(hartid) == STOPPED
+        {
+            if test_hart(hartid, &mut f) {
+                f(Case::Pass);
+            }
+            return;
+        }
+        hart_mask >>= 1;
+        hartid += 1;
+    }
+    f(Case::NoStoppedHart);
+}
+
+const STOPPED: SbiRet = SbiRet::success(hart_state::STOPPED);
+
+const PAGE_SIZE: usize = 4096;
+/// 测试页所在虚地址，位于根页表第 255 项
+const TEST_VA: usize = 255 << 30;
+const TEST_ASID: usize = 1;
+const MAGIC_A: usize = 0x5a5a_a5a5;
+const MAGIC_B: usize = 0xa5a5_5a5a;
+
+const SATP_SV39: usize = 8 << 60;
+const PTE_V: usize = 1 << 0;
+const PTE_R: usize = 1 << 1;
+const PTE_W: usize = 1 << 2;
+const PTE_X: usize = 1 << 3;
+const PTE_A: usize = 1 << 6;
+const PTE_D: usize = 1 << 7;
+
+/// `li a0, 1`
+const LI_A0_1: u32 = 0x0010_0513;
+/// `li a0, 2`
+const LI_A0_2: u32 = 0x0020_0513;
+/// `ret`
+const RET: u32 = 0x0000_8067;
+
+#[repr(C, align(4096))]
+struct Page([usize; 512]);
+
+static mut ROOT: Page = Page([0; 512]);
+static mut L1: Page = Page([0; 512]);
+static mut L0: Page = Page([0; 512]);
+static mut PAGE_A: Page = Page([0; 512]);
+static mut PAGE_B: Page = Page([0; 512]);
+static mut CODE: [u32; 2] = [LI_A0_1, RET];
+
+static mut SECONDARY: Secondary = Secondary::ZERO;
+
+#[repr(C, align(4096))]
+struct Secondary {
+    cmd: AtomicUsize,
+    arg: AtomicUsize,
+    ret: AtomicUsize,
+    stack: [u8; 4072],
+}
+
+const CMD_IDLE: usize = 0;
+const CMD_PAGING: usize = 1;
+const CMD_READ: usize = 2;
+const CMD_EXEC: usize = 3;
+const CMD_STOP: usize = 4;
+const CMD_TRAPPED: usize = usize::MAX;
+
+impl Secondary {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: Self = Self {
+        cmd: AtomicUsize::new(CMD_IDLE),
+        arg: AtomicUsize::new(0),
+        ret: AtomicUsize::new(0),
+        stack: [0; 4072],
+    };
+
+    #[inline]
+    fn get() -> &'static Self {
+        unsafe { &*core::ptr::addr_of!(SECONDARY) }
+    }
+
+    /// 向副核发送命令并等待结果，副核陷入时返回 `scause`
+    fn call(&self, cmd: usize, arg: usize) -> Result<usize, usize> {
+        self.arg.store(arg, Ordering::Relaxed);
+        self.cmd.store(cmd, Ordering::Release);
+        loop {
+            match self.cmd.load(Ordering::Acquire) {
+                CMD_IDLE => break Ok(self.ret.load(Ordering::Relaxed)),
+                CMD_TRAPPED => break Err(self.ret.load(Ordering::Relaxed)),
+                _ => core::hint::spin_loop(),
+            }
+        }
+    }
+}
+
+#[inline]
+fn leaf_pte(page: *const Page) -> usize {
+    (page as usize >> 12) << 10 | PTE_V | PTE_R | PTE_W | PTE_A | PTE_D
+}
+
+#[inline]
+fn set_test_page(page: *const Page) {
+    unsafe { (&raw mut L0.0[0]).write_volatile(leaf_pte(page)) };
+    fence(Ordering::SeqCst);
+}
+
+/// 建立副核使用的页表：低 256 GiB 恒等映射，测试页映射到 `PAGE_A`
+fn build_page_table() {
+    unsafe {
+        for i in 0..256 {
+            let pte = (i << 30 >> 12) << 10 | PTE_V | PTE_R | PTE_W | PTE_X | PTE_A | PTE_D;
+            (&raw mut ROOT.0[i]).write_volatile(pte);
+        }
+        (&raw mut ROOT.0[TEST_VA >> 30])
+            .write_volatile((&raw const L1 as usize >> 12) << 10 | PTE_V);
+        (&raw mut L1.0[0]).write_volatile((&raw const L0 as usize >> 12) << 10 | PTE_V);
+        (&raw mut PAGE_A.0[0]).write_volatile(MAGIC_A);
+        (&raw mut PAGE_B.0[0]).write_volatile(MAGIC_B);
+    }
+    set_test_page(&raw const PAGE_A);
+}
+
+/// 在一个副核上测试远程屏障
+fn test_hart(hartid: usize, f: &mut impl FnMut(Case)) -> bool {
+    build_page_table();
+    unsafe { (&raw mut CODE).write_volatile([LI_A0_1, RET]) };
+    unsafe { core::arch::asm!("fence.i") };
+    let item = Secondary::get();
+    item.cmd.store(CMD_IDLE, Ordering::Relaxed);
+    let ret = sbi::hart_start(hartid, test_entry as _, item as *const _ as _);
+    if ret.is_err() {
+        f(Case::HartStartFailed { hartid, ret });
+        return false;
+    }
+    let mask = HartMask::from_mask_base(1, hartid);
+    macro_rules! call {
+        ($cmd:expr, $arg:expr) => {
+            match item.call($cmd, $arg) {
+                Ok(value) => value,
+                Err(scause) => {
+                    f(Case::UnexpectedTrap { hartid, scause });
+                    return false;
+                }
+            }
+        };
+    }
+    macro_rules! check {
+        ($function:literal, $ret:expr) => {
+            let ret = $ret;
+            if ret.is_err() {
+                f(Case::FenceFailed {
+                    function: $function,
+                    ret,
+                });
+                return false;
+            }
+        };
+    }
+    // 测试 sfence.vma
+    let satp = call!(CMD_PAGING, 0);
+    if satp & SATP_SV39 == SATP_SV39 {
+        // 副核读到 A
+        if call!(CMD_READ, 0) != MAGIC_A {
+            f(Case::StaleTranslation { hartid, asid: 0 });
+            return false;
+        }
+        // 修改为 B 并远程刷新
+        set_test_page(&raw const PAGE_B);
+        check!(
+            "remote_sfence_vma",
+            sbi::remote_sfence_vma(mask, TEST_VA, PAGE_SIZE)
+        );
+        if call!(CMD_READ, 0) != MAGIC_B {
+            f(Case::StaleTranslation { hartid, asid: 0 });
+            return false;
+        }
+        f(Case::RemoteSfenceVma(hartid));
+        // 切换地址空间，按 ASID 远程刷新
+        let asid = (call!(CMD_PAGING, TEST_ASID) >> 44) & 0xffff;
+        if call!(CMD_READ, 0) != MAGIC_B {
+            f(Case::StaleTranslation { hartid, asid });
+            return false;
+        }
+        set_test_page(&raw const PAGE_A);
+        check!(
+            "remote_sfence_vma_asid",
+            sbi::remote_sfence_vma_asid(mask, TEST_VA, PAGE_SIZE, asid)
+        );
+        if call!(CMD_READ, 0) != MAGIC_A {
+            f(Case::StaleTranslation { hartid, asid });
+            return false;
+        }
+        f(Case::RemoteSfenceVmaAsid { hartid, asid });
+    } else {
+        f(Case::PagingNotSupported(hartid));
+    }
+    // 测试 fence.i：副核先执行旧代码，修改后远程同步再执行
+    if call!(CMD_EXEC, 0) != 1 {
+        f(Case::StaleInstruction(hartid));
+        return false;
+    }
+    unsafe { (&raw mut CODE[0]).write_volatile(LI_A0_2) };
+    fence(Ordering::SeqCst);
+    check!("remote_fence_i", sbi::remote_fence_i(mask));
+    if call!(CMD_EXEC, 0) != 2 {
+        f(Case::StaleInstruction(hartid));
+        return false;
+    }
+    f(Case::RemoteFenceI(hartid));
+    // 关闭副核
+    call!(CMD_STOP, 0);
+    while sbi::hart_get_status(hartid) != STOPPED {
+        core::hint::spin_loop();
+    }
+    true
+}
+
+/// 测试用启动入口
+#[unsafe(naked)]
+unsafe extern "C" fn test_entry(hartid: usize, opaque: *const Secondary) -> ! {
+    core::arch::naked_asm!(
+        "csrw sie, zero",      // 关中断
+        "la   t0, 1f",         // 设置陷入入口
+        "csrw stvec, t0",
+        "li   t0, {size}",     // 设置栈
+        "add  sp, a1, t0",
+        "j    {rust_main}",    // 进入 rust
+        ".align 2",
+        "1:",
+        "csrr a0, scause",
+        "j    {trap}",
+        size = const core::mem::size_of::<Secondary>(),
+        rust_main = sym rust_main,
+        trap = sym trap,
+    )
+}
+
+extern "C" fn rust_main(_hartid: usize, opaque: *const Secondary) -> ! {
+    let item = unsafe { &*opaque };
+    loop {
+        let cmd = item.cmd.load(Ordering::Acquire);
+        let arg = item.arg.load(Ordering::Relaxed);
+        let ret = match cmd {
+            CMD_IDLE | CMD_TRAPPED => {
+                core::hint::spin_loop();
+                continue;
+            }
+            CMD_PAGING => {
+                let mut satp = SATP_SV39 | arg << 44 | (&raw const ROOT as usize >> 12);
+                unsafe {
+                    core::arch::asm!(
+                        "csrw satp, {0}",
+                        "sfence.vma",
+                        "csrr {0}, satp",
+                        inout(reg) satp,
+                    )
+                };
+                satp
+            }
+            CMD_READ => unsafe { (TEST_VA as *const usize).read_volatile() },
+            CMD_EXEC => {
+                let code: extern "C" fn() -> usize =
+                    unsafe { core::mem::transmute(&raw const CODE) };
+                code()
+            }
+            CMD_STOP => {
+                unsafe { core::arch::asm!("csrw satp, zero", "sfence.vma") };
+                item.cmd.store(CMD_IDLE, Ordering::Release);
+                let ret = sbi::hart_stop();
+                unreachable!("stop but {ret:?}")
+            }
+            _ => unreachable!(),
+        };
+        item.ret.store(ret, Ordering::Relaxed);
+        item.cmd.store(CMD_IDLE, Ordering::Release);
+    }
+}
+
+/// 副核陷入时报告 `scause` 并停留
+extern "C" fn trap(scause: usize) -> ! {
+    let item = Secondary::get();
+    item.ret.store(scause, Ordering::Relaxed);
+    item.cmd.store(CMD_TRAPPED, Ordering::Release);
+    loop {
+        core::hint::spin_loop();
+    }
+}
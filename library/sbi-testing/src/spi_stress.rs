@@ -0,0 +1,494 @@
+//! Inter-processor interrupt stress test suite on multiple harts.
+
+use core::{
+    arch::{asm, naked_asm},
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+};
+use riscv::register::time;
+use sbi::{HartMask, SbiRet};
+use sbi_spec::hsm::hart_state;
+
+/// Inter-processor interrupt stress test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for inter-processor interrupt or hart state monitor extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for a hart is not stopped before test and is not tested.
+    HartStartedBeforeTest(usize),
+    /// Test process for a hart is not tested for the test supports at most 16 other harts.
+    HartNotTested(usize),
+    /// Test failed for no other harts are available to be tested.
+    NoStoppedHart,
+    /// Test failed for can't start target hart with [`SbiRet`] error.
+    HartStartFailed {
+        /// The target hart ID that has failed to start.
+        hartid: usize,
+        /// The `SbiRet` value for the failed hart start SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for target hart to be tested has started.
+    HartStarted(usize),
+    /// Test failed for sending inter-processor interrupts returned an error.
+    SendFailed {
+        /// Shape of the hart mask.
+        shape: Shape,
+        /// The `SbiRet` value for the failed SBI call.
+        ret: SbiRet,
+    },
+    /// Test failed for a targeted hart has not received an inter-processor interrupt in time.
+    Lost {
+        /// Shape of the hart mask.
+        shape: Shape,
+        /// The hart which has lost the inter-processor interrupt.
+        hartid: usize,
+    },
+    /// Test failed for a hart has received more inter-processor interrupts than sent.
+    Duplicated {
+        /// Shape of the hart mask.
+        shape: Shape,
+        /// The hart which has received duplicated inter-processor interrupts.
+        hartid: usize,
+        /// Number of inter-processor interrupts sent to the hart.
+        sent: usize,
+        /// Number of inter-processor interrupts received by the hart.
+        received: usize,
+    },
+    /// Test process for all inter-processor interrupts of one shape have been delivered.
+    Latency {
+        /// Shape of the hart mask.
+        shape: Shape,
+        /// Number of inter-processor interrupts delivered.
+        delivered: usize,
+        /// Minimum latency in `time` ticks.
+        min: u64,
+        /// Maximum latency in `time` ticks.
+        max: u64,
+        /// Mean latency in `time` ticks.
+        mean: u64,
+    },
+    /// Test process for a burst of inter-processor interrupts without waiting has been delivered to all harts.
+    Burst(usize),
+    /// All test cases on inter-processor interrupt stress test has passed.
+    Pass,
+}
+
+/// Shape of hart masks used to send inter-processor interrupts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// All tested harts, grouped into masks of 64 harts.
+    All,
+    /// All harts including the current one, with `hart_mask_base` set to `-1`.
+    Broadcast,
+    /// Every other tested hart.
+    Sparse,
+    /// One tested hart with `hart_mask_base` set to its hart ID.
+    Single,
+    /// One tested hart on a high bit of `hart_mask`, with `hart_mask_base` set to the lowest
+    /// tested hart ID, or to the hart ID minus 63 if that is higher.
+    ///
+    /// Each round also sends a full `hart_mask` with `hart_mask_base` beyond the highest hart
+    /// of the tested hart mask, stepping over the following mask words. It must not interrupt
+    /// any hart, and may either succeed or fail with `SBI_ERR_INVALID_PARAM`.
+    Offset,
+    /// Consecutive calls to all tested harts without waiting for delivery.
+    Burst,
+}
+
+/// Stress inter-processor interrupt extension on given harts.
+///
+/// Other harts must not be running during the test, for `Broadcast` shape targets all harts.
+/// `timeout` is the time in `time` ticks to wait for each delivery.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(
+    primary_hart_id: usize,
+    mut hart_mask: usize,
+    hart_mask_base: usize,
+    timeout: u64,
+    mut f: impl FnMut(Case),
+) {
+    // 不支持 IPI 或 HSM 扩展
+    if sbi::probe_extension(sbi::Ipi).is_unavailable()
+        || sbi::probe_extension(sbi::Hsm).is_unavailable()
+    {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    // 收集停止状态的副核
+    let mut harts = [0usize; MAX_HARTS];
+    let mut len = 0;
+    let mut hartid = hart_mask_base;
+    let mut highest = hart_mask_base;
+    while hart_mask != 0 {
+        let distance = hart_mask.trailing_zeros();
+        hartid += distance as usize;
+        highest = hartid;
+        if hartid != primary_hart_id {
+            if sbi::hart_get_status(hartid) != STOPPED {
+                f(Case::HartStartedBeforeTest(hartid));
+            } else if len == MAX_HARTS {
+                f(Case::HartNotTested(hartid));
+            } else {
+                harts[len] = hartid;
+                len += 1;
+            }
+        }
+        hart_mask >>= distance;
+        hart_mask >>= 1;
+        hartid += 1;
+    }
+    if len == 0 {
+        f(Case::NoStoppedHart);
+        return;
+    }
+    let harts = &harts[..len];
+    // 启动副核
+    for (i, &hartid) in harts.iter().enumerate() {
+        let slot = unsafe { &mut *core::ptr::addr_of_mut!(SLOTS[i]) };
+        let ptr = slot.reset();
+        let ret = sbi::hart_start(hartid, test_entry as _, ptr as _);
+        if ret.is_err() {
+            stop(&harts[..i]);
+            f(Case::HartStartFailed { hartid, ret });
+            return;
+        }
+    }
+    for (i, &hartid) in harts.iter().enumerate() {
+        while !slot(i).ready.load(Ordering::Acquire) {
+            core::hint::spin_loop();
+        }
+        f(Case::HartStarted(hartid));
+    }
+    // 广播也会发给当前核，关中断以免陷入
+    let sie = unsafe { disable_interrupt() };
+    let pass = stress(primary_hart_id, highest, harts, timeout, &mut f);
+    unsafe { clear_ssip() };
+    if sie {
+        unsafe { enable_interrupt() };
+    }
+    stop(harts);
+    if pass {
+        f(Case::Pass);
+    }
+}
+
+const MAX_HARTS: usize = 16;
+const ROUNDS: usize = 16;
+/// 偏移形状中发往不存在的核的掩码字数
+const STRAY_WORDS: usize = 4;
+/// 超过 Prototyper 中核间中断队列的长度
+const BURST_CALLS: usize = 32;
+
+const STOPPED: SbiRet = SbiRet::success(hart_state::STOPPED);
+
+/// 按各种形状发送核间中断并检查送达
+fn stress(
+    primary: usize,
+    highest: usize,
+    harts: &[usize],
+    timeout: u64,
+    f: &mut impl FnMut(Case),
+) -> bool {
+    let mut expected = [0usize; MAX_HARTS];
+    let mut targets = [false; MAX_HARTS];
+    for shape in [
+        Shape::All,
+        Shape::Broadcast,
+        Shape::Sparse,
+        Shape::Single,
+        Shape::Offset,
+    ] {
+        let (mut delivered, mut min, mut max, mut sum) = (0, u64::MAX, 0, 0);
+        for round in 0..ROUNDS {
+            for (i, target) in targets[..harts.len()].iter_mut().enumerate() {
+                *target = match shape {
+                    Shape::Sparse => (i + round) % 2 == 0,
+                    Shape::Single | Shape::Offset => i == round % harts.len(),
+                    _ => true,
+                };
+            }
+            if !check_counts(shape, harts, &expected, f) {
+                return false;
+            }
+            // 基址高于所有核的掩码，依次覆盖其后的各个掩码字，不应中断任何核
+            if shape == Shape::Offset {
+                let word = (round % STRAY_WORDS) * usize::BITS as usize;
+                if let Some(base) = (highest + 1).checked_add(word) {
+                    let ret = sbi::send_ipi(HartMask::from_mask_base(usize::MAX, base));
+                    if ret.is_err() && ret != SbiRet::invalid_param() {
+                        f(Case::SendFailed { shape, ret });
+                        return false;
+                    }
+                }
+            }
+            let sent_at = time::read64();
+            let ret = send(shape, harts, &targets);
+            if ret.is_err() {
+                f(Case::SendFailed { shape, ret });
+                return false;
+            }
+            for (i, &hartid) in harts.iter().enumerate() {
+                if !targets[i] {
+                    continue;
+                }
+                expected[i] += 1;
+                if !wait_count(i, expected[i], sent_at, timeout) {
+                    f(Case::Lost { shape, hartid });
+                    return false;
+                }
+                let latency = slot(i).received_at.load(Ordering::Relaxed) - sent_at;
+                delivered += 1;
+                min = min.min(latency);
+                max = max.max(latency);
+                sum += latency;
+            }
+            // 广播也会发给当前核
+            if shape == Shape::Broadcast {
+                while !ssip_pending() {
+                    if time::read64() - sent_at > timeout {
+                        f(Case::Lost {
+                            shape,
+                            hartid: primary,
+                        });
+                        return false;
+                    }
+                }
+                unsafe { clear_ssip() };
+            }
+        }
+        f(Case::Latency {
+            shape,
+            delivered,
+            min,
+            max,
+            mean: sum / delivered as u64,
+        });
+    }
+    // 连续发送，不等待送达，每个核至少收到一次
+    if !check_counts(Shape::Burst, harts, &expected, f) {
+        return false;
+    }
+    let sent_at = time::read64();
+    targets.fill(true);
+    for _ in 0..BURST_CALLS {
+        let ret = send(Shape::Burst, harts, &targets);
+        if ret.is_err() {
+            f(Case::SendFailed {
+                shape: Shape::Burst,
+                ret,
+            });
+            return false;
+        }
+    }
+    for (i, &hartid) in harts.iter().enumerate() {
+        if !wait_count(i, expected[i] + 1, sent_at, timeout) {
+            f(Case::Lost {
+                shape: Shape::Burst,
+                hartid,
+            });
+            return false;
+        }
+    }
+    // 等待所有中断送达后，收到的不能多于发出的
+    let settle = time::read64();
+    while time::read64() - settle < timeout {
+        core::hint::spin_loop();
+    }
+    for (i, &hartid) in harts.iter().enumerate() {
+        let received = slot(i).count.load(Ordering::Acquire) - expected[i];
+        if received > BURST_CALLS {
+            f(Case::Duplicated {
+                shape: Shape::Burst,
+                hartid,
+                sent: BURST_CALLS,
+                received,
+            });
+            return false;
+        }
+    }
+    f(Case::Burst(BURST_CALLS));
+    true
+}
+
+/// 检查各核收到的中断数与发出的一致
+fn check_counts(
+    shape: Shape,
+    harts: &[usize],
+    expected: &[usize],
+    f: &mut impl FnMut(Case),
+) -> bool {
+    for (i, &hartid) in harts.iter().enumerate() {
+        let received = slot(i).count.load(Ordering::Acquire);
+        if received != expected[i] {
+            f(Case::Duplicated {
+                shape,
+                hartid,
+                sent: expected[i],
+                received,
+            });
+            return false;
+        }
+    }
+    true
+}
+
+/// 等待第 `i` 个核收到的中断数达到 `count`
+fn wait_count(i: usize, count: usize, sent_at: u64, timeout: u64) -> bool {
+    while slot(i).count.load(Ordering::Acquire) < count {
+        if time::read64() - sent_at > timeout {
+            return false;
+        }
+        core::hint::spin_loop();
+    }
+    true
+}
+
+/// 按形状向选中的核发送核间中断
+fn send(shape: Shape, harts: &[usize], targets: &[bool]) -> SbiRet {
+    let mut selected = harts
+        .iter()
+        .zip(targets)
+        .filter(|(_, target)| **target)
+        .map(|(hartid, _)| *hartid);
+    match shape {
+        Shape::Broadcast => sbi::send_ipi(HartMask::all()),
+        Shape::Single => {
+            let hartid = selected.next().unwrap();
+            sbi::send_ipi(HartMask::from_mask_base(1, hartid))
+        }
+        Shape::Offset => {
+            let hartid = selected.next().unwrap();
+            // 核编号较小时以最小的被测核为基址，覆盖非零基址
+            let base = hartid
+                .saturating_sub(usize::BITS as usize - 1)
+                .max(harts[0]);
+            sbi::send_ipi(HartMask::from_mask_base(1 << (hartid - base), base))
+        }
+        // 以最小的未发送核为基址，每 64 个核一组
+        Shape::All | Shape::Sparse | Shape::Burst => {
+            let mut ret = SbiRet::success(0);
+            let (mut base, mut mask) = (0, 0usize);
+            for hartid in selected {
+                if mask != 0 && hartid - base >= usize::BITS as usize {
+                    ret = sbi::send_ipi(HartMask::from_mask_base(mask, base));
+                    if ret.is_err() {
+                        return ret;
+                    }
+                    mask = 0;
+                }
+                if mask == 0 {
+                    base = hartid;
+                }
+                mask |= 1 << (hartid - base);
+            }
+            if mask != 0 {
+                ret = sbi::send_ipi(HartMask::from_mask_base(mask, base));
+            }
+            ret
+        }
+    }
+}
+
+/// 通知副核关闭并等待
+fn stop(harts: &[usize]) {
+    for (i, &hartid) in harts.iter().enumerate() {
+        slot(i).stop.store(true, Ordering::Release);
+        while sbi::hart_get_status(hartid) != STOPPED {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+static mut SLOTS: [Slot; MAX_HARTS] = [Slot::ZERO; MAX_HARTS];
+
+#[inline]
+fn slot(i: usize) -> &'static Slot {
+    unsafe { &*core::ptr::addr_of!(SLOTS[i]) }
+}
+
+/// 副核的状态和栈
+#[repr(C, align(1024))]
+struct Slot {
+    ready: AtomicBool,
+    stop: AtomicBool,
+    count: AtomicUsize,
+    received_at: AtomicU64,
+    stack: [u8; 1024 - 24],
+}
+
+impl Slot {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: Self = Self {
+        ready: AtomicBool::new(false),
+        stop: AtomicBool::new(false),
+        count: AtomicUsize::new(0),
+        received_at: AtomicU64::new(0),
+        stack: [0; 1024 - 24],
+    };
+
+    #[inline]
+    fn reset(&mut self) -> *const Slot {
+        self.ready.store(false, Ordering::Relaxed);
+        self.stop.store(false, Ordering::Relaxed);
+        self.count.store(0, Ordering::Relaxed);
+        self as _
+    }
+}
+
+/// 副核入口
+#[unsafe(naked)]
+unsafe extern "C" fn test_entry(hartid: usize, opaque: *const Slot) -> ! {
+    naked_asm!(
+        "csrw sie, zero",     // 关中断
+        "addi sp, a1, 1024",  // 设置栈
+        "j    {rust_main}",   // 进入 rust
+        rust_main = sym rust_main,
+    )
+}
+
+/// 副核轮询软件中断等待位，不进入陷入
+extern "C" fn rust_main(_hartid: usize, opaque: *const Slot) -> ! {
+    let slot = unsafe { &*opaque };
+    unsafe { clear_ssip() };
+    slot.ready.store(true, Ordering::Release);
+    while !slot.stop.load(Ordering::Acquire) {
+        if ssip_pending() {
+            unsafe { clear_ssip() };
+            slot.received_at.store(time::read64(), Ordering::Relaxed);
+            slot.count.fetch_add(1, Ordering::Release);
+        }
+    }
+    let ret = sbi::hart_stop();
+    unreachable!("stop but {ret:?}")
+}
+
+const SSIP: usize = 1 << 1;
+const SIE: usize = 1 << 1;
+
+#[inline]
+fn ssip_pending() -> bool {
+    let sip: usize;
+    unsafe { asm!("csrr {}, sip", out(reg) sip) };
+    sip & SSIP != 0
+}
+
+#[inline]
+unsafe fn clear_ssip() {
+    unsafe { asm!("csrc sip, {}", in(reg) SSIP) };
+}
+
+/// 关闭 S 态中断，返回之前是否开启
+#[inline]
+unsafe fn disable_interrupt() -> bool {
+    let sstatus: usize;
+    unsafe { asm!("csrrc {}, sstatus, {}", out(reg) sstatus, in(reg) SIE) };
+    sstatus & SIE != 0
+}
+
+#[inline]
+unsafe fn enable_interrupt() {
+    unsafe { asm!("csrs sstatus, {}", in(reg) SIE) };
+}
@@ -0,0 +1,197 @@
+//! System reset extension test suite.
+
+use core::sync::atomic::{Ordering, fence};
+use sbi::{ColdReboot, NoReason, ResetReason, ResetType, SbiRet, WarmReboot};
+
+/// System reset extension test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for system reset extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for reserved reset type has been rejected.
+    ReservedTypeRejected,
+    /// Test failed for reserved reset type is not rejected with `SBI_ERR_INVALID_PARAM`.
+    ReservedTypeAccepted(SbiRet),
+    /// Test process for reserved reset reason has been rejected.
+    ReservedReasonRejected,
+    /// Test failed for reserved reset reason is not rejected with `SBI_ERR_INVALID_PARAM`.
+    ReservedReasonAccepted(SbiRet),
+    /// Test failed for system was reset by a request with reserved parameters.
+    UnexpectedReset,
+    /// Test process for warm reboot is about to be requested.
+    WarmReboot,
+    /// Test process for warm reboot is not supported.
+    WarmRebootNotSupported,
+    /// Test failed for warm reboot request returned an error.
+    WarmRebootFailed(SbiRet),
+    /// Test process for test resumed after warm reboot.
+    WarmRebootResumed,
+    /// Test process for cold reboot is about to be requested.
+    ColdReboot,
+    /// Test process for cold reboot is not supported.
+    ColdRebootNotSupported,
+    /// Test failed for cold reboot request returned an error.
+    ColdRebootFailed(SbiRet),
+    /// Test process for test resumed after cold reboot.
+    ColdRebootResumed,
+    /// All test cases on system reset extension has passed.
+    Pass,
+}
+
+/// Progress of system reset test, persisted across system resets.
+///
+/// It should be placed in memory that is preserved over warm and cold reboots,
+/// and must not be initialized again by the supervisor after a reboot.
+#[repr(C)]
+pub struct Progress {
+    magic: usize,
+    stage: usize,
+}
+
+const MAGIC: usize = sbi_spec::srst::EID_SRST;
+const STAGE_VALIDATE: usize = 1;
+const STAGE_WARM_REBOOT: usize = 2;
+const STAGE_COLD_REBOOT: usize = 3;
+
+impl Progress {
+    /// Create a progress of a test that has not begun.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { magic: 0, stage: 0 }
+    }
+
+    #[inline]
+    fn load(&self) -> Option<usize> {
+        if unsafe { (&raw const self.magic).read_volatile() } == MAGIC {
+            Some(unsafe { (&raw const self.stage).read_volatile() })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn store(&mut self, stage: usize) {
+        unsafe {
+            (&raw mut self.stage).write_volatile(stage);
+            (&raw mut self.magic).write_volatile(MAGIC);
+        }
+        fence(Ordering::SeqCst);
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        unsafe { (&raw mut self.magic).write_volatile(0) };
+        fence(Ordering::SeqCst);
+    }
+}
+
+impl Default for Progress {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Test system reset extension.
+///
+/// The system is reset during this test. Call this function with the same `progress`
+/// on every boot; the test resumes from `progress` after each reboot and clears it
+/// when finished.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(progress: &mut Progress, mut f: impl FnMut(Case)) {
+    // 不支持 SRST 扩展
+    if sbi::probe_extension(sbi::Reset).is_unavailable() {
+        progress.clear();
+        f(Case::NotExist);
+        return;
+    }
+    match progress.load() {
+        // 保留参数导致了重启
+        Some(STAGE_VALIDATE) => {
+            progress.clear();
+            f(Case::UnexpectedReset);
+        }
+        // 热重启后恢复
+        Some(STAGE_WARM_REBOOT) => {
+            f(Case::WarmRebootResumed);
+            test_cold_reboot(progress, f);
+        }
+        // 冷重启后恢复
+        Some(STAGE_COLD_REBOOT) => {
+            progress.clear();
+            f(Case::ColdRebootResumed);
+            f(Case::Pass);
+        }
+        // 开始测试
+        _ => {
+            f(Case::Begin);
+            progress.store(STAGE_VALIDATE);
+            let ret = sbi::system_reset(Reserved(RESERVED_TYPE), NoReason);
+            if ret == SbiRet::invalid_param() {
+                f(Case::ReservedTypeRejected);
+            } else {
+                progress.clear();
+                f(Case::ReservedTypeAccepted(ret));
+                return;
+            }
+            let ret = sbi::system_reset(WarmReboot, Reserved(RESERVED_REASON));
+            if ret == SbiRet::invalid_param() {
+                f(Case::ReservedReasonRejected);
+            } else {
+                progress.clear();
+                f(Case::ReservedReasonAccepted(ret));
+                return;
+            }
+            progress.store(STAGE_WARM_REBOOT);
+            f(Case::WarmReboot);
+            let ret = sbi::system_reset(WarmReboot, NoReason);
+            if ret == SbiRet::not_supported() {
+                f(Case::WarmRebootNotSupported);
+                test_cold_reboot(progress, f);
+            } else {
+                progress.clear();
+                f(Case::WarmRebootFailed(ret));
+            }
+        }
+    }
+}
+
+/// 测试冷重启，成功时不返回
+fn test_cold_reboot(progress: &mut Progress, mut f: impl FnMut(Case)) {
+    progress.store(STAGE_COLD_REBOOT);
+    f(Case::ColdReboot);
+    let ret = sbi::system_reset(ColdReboot, NoReason);
+    progress.clear();
+    if ret == SbiRet::not_supported() {
+        f(Case::ColdRebootNotSupported);
+        f(Case::Pass);
+    } else {
+        f(Case::ColdRebootFailed(ret));
+    }
+}
+
+/// 第一个保留的重启类型
+const RESERVED_TYPE: u32 = 3;
+/// 第一个保留的重启原因
+const RESERVED_REASON: u32 = 2;
+
+/// 保留的参数值
+#[derive(Clone, Copy, Debug)]
+struct Reserved(u32);
+
+impl ResetType for Reserved {
+    #[inline]
+    fn raw(&self) -> u32 {
+        self.0
+    }
+}
+
+impl ResetReason for Reserved {
+    #[inline]
+    fn raw(&self) -> u32 {
+        self.0
+    }
+}
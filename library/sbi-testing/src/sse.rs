@@ -0,0 +1,227 @@
+//! Supervisor software events extension test suite.
+
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use sbi::{SbiRet, SharedPtr, SseContext, SseStack};
+use sbi_spec::sse::{attr_id, event_id::LOCAL_SOFTWARE_INJECTED};
+
+/// Supervisor software events extension test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for supervisor software events extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for reserved event has been rejected.
+    ReservedEventRejected,
+    /// Test failed for reserved event is not rejected with `SBI_ERR_INVALID_PARAM`.
+    ReservedEventAccepted(SbiRet),
+    /// Test process for reading reserved attributes has been rejected.
+    ReservedAttributeRejected,
+    /// Test failed for reading reserved attributes is not rejected with `SBI_ERR_BAD_RANGE`.
+    ReservedAttributeAccepted(SbiRet),
+    /// Test process for software injected event is not supported.
+    InjectionNotSupported,
+    /// Test process for attributes of the local software injected event.
+    Attributes {
+        /// Value of `STATUS` attribute.
+        status: usize,
+        /// Value of `PRIORITY` attribute.
+        priority: usize,
+        /// Value of `CONFIG` attribute.
+        config: usize,
+    },
+    /// Test failed for an event operation returned an error.
+    EventFailed {
+        /// Name of the failed SBI function.
+        function: &'static str,
+        /// The `SbiRet` value for the failed SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for unmasking unmasked events has been rejected.
+    UnmaskTwiceRejected,
+    /// Test failed for unmasking unmasked events is not rejected with `SBI_ERR_ALREADY_STARTED`.
+    UnmaskTwiceAccepted(SbiRet),
+    /// Test process for masking masked events has been rejected.
+    MaskTwiceRejected,
+    /// Test failed for masking masked events is not rejected with `SBI_ERR_ALREADY_STOPPED`.
+    MaskTwiceAccepted(SbiRet),
+    /// Test process for completing outside of event handler has no effect.
+    CompleteOutsideHandler,
+    /// Test process for injected events have been handled in order.
+    ///
+    /// The event injected from its own handler runs after the handler completes.
+    Injected(usize),
+    /// Test failed for event handler is reentered by an event injected from itself.
+    HandlerReentered,
+    /// Test failed for injected events have not been handled before injection returns.
+    InjectedNotHandled(usize),
+    /// All test cases on supervisor software events extension has passed.
+    Pass,
+}
+
+/// Test supervisor software events extension on current hart.
+///
+/// Memory must be identically mapped on the current hart.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(hartid: usize, mut f: impl FnMut(Case)) {
+    // 不支持 SSE 扩展
+    if sbi::probe_extension(sbi::Sse).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    let mut attrs = [0usize; 3];
+    let output = SharedPtr::new(attrs.as_mut_ptr() as usize, 0);
+    // 保留的事件
+    let ret = sbi::sse_read_attrs(RESERVED_EVENT, attr_id::STATUS, 1, output);
+    if ret == SbiRet::invalid_param() {
+        f(Case::ReservedEventRejected);
+    } else {
+        f(Case::ReservedEventAccepted(ret));
+        return;
+    }
+    // 保留的属性
+    let ret = sbi::sse_read_attrs(LOCAL_SOFTWARE_INJECTED, RESERVED_ATTRIBUTE, 1, output);
+    if ret == SbiRet::not_supported() {
+        f(Case::InjectionNotSupported);
+        f(Case::Pass);
+        return;
+    } else if ret == SbiRet::bad_range() {
+        f(Case::ReservedAttributeRejected);
+    } else {
+        f(Case::ReservedAttributeAccepted(ret));
+        return;
+    }
+    // 读取属性
+    let ret = sbi::sse_read_attrs(LOCAL_SOFTWARE_INJECTED, attr_id::STATUS, 3, output);
+    if ret.is_err() {
+        f(Case::EventFailed {
+            function: "sse_read_attrs",
+            ret,
+        });
+        return;
+    }
+    f(Case::Attributes {
+        status: attrs[0],
+        priority: attrs[1],
+        config: attrs[2],
+    });
+    // 屏蔽状态
+    let ret = sbi::sse_hart_unmask();
+    if ret.is_err() {
+        f(Case::EventFailed {
+            function: "sse_hart_unmask",
+            ret,
+        });
+        return;
+    }
+    let ret = sbi::sse_hart_unmask();
+    if ret == SbiRet::already_started() {
+        f(Case::UnmaskTwiceRejected);
+    } else {
+        let _ = sbi::sse_hart_mask();
+        f(Case::UnmaskTwiceAccepted(ret));
+        return;
+    }
+    // 在处理函数外完成无效果
+    let ret = sbi::sse_complete();
+    if ret.is_ok() {
+        f(Case::CompleteOutsideHandler);
+    } else {
+        let _ = sbi::sse_hart_mask();
+        f(Case::EventFailed {
+            function: "sse_complete",
+            ret,
+        });
+        return;
+    }
+    // 注入事件
+    let injected = test_inject(hartid, &mut f);
+    let ret = sbi::sse_hart_mask();
+    if !injected {
+        return;
+    }
+    if ret.is_err() {
+        f(Case::EventFailed {
+            function: "sse_hart_mask",
+            ret,
+        });
+        return;
+    }
+    let ret = sbi::sse_hart_mask();
+    if ret == SbiRet::already_stopped() {
+        f(Case::MaskTwiceRejected);
+    } else {
+        f(Case::MaskTwiceAccepted(ret));
+        return;
+    }
+    f(Case::Pass);
+}
+
+/// 第一个保留的事件
+const RESERVED_EVENT: u32 = 0x0000_0002;
+/// 第一个保留的属性
+const RESERVED_ATTRIBUTE: u32 = attr_id::INTERRUPTED_A7 + 1;
+
+static mut STACK: SseStack<4096> = SseStack::new();
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+static IN_HANDLER: AtomicBool = AtomicBool::new(false);
+static REENTERED: AtomicBool = AtomicBool::new(false);
+
+/// 事件处理函数，第一次处理时再次注入本事件
+fn handler(ctx: &mut SseContext) {
+    if IN_HANDLER.swap(true, Ordering::AcqRel) {
+        REENTERED.store(true, Ordering::Release);
+    }
+    if HANDLED.fetch_add(1, Ordering::AcqRel) == 0 {
+        let _ = sbi::sse_inject(ctx.event_id, ctx.hart_id);
+    }
+    IN_HANDLER.store(false, Ordering::Release);
+}
+
+/// 注册、启用并注入事件，检查嵌套注入的顺序
+fn test_inject(hartid: usize, f: &mut impl FnMut(Case)) -> bool {
+    HANDLED.store(0, Ordering::Relaxed);
+    IN_HANDLER.store(false, Ordering::Relaxed);
+    REENTERED.store(false, Ordering::Relaxed);
+    let stack = unsafe { &mut *core::ptr::addr_of_mut!(STACK) };
+    let ret = unsafe { sbi::sse_register_handler(LOCAL_SOFTWARE_INJECTED, stack, handler) };
+    if ret.is_err() {
+        f(Case::EventFailed {
+            function: "sse_register",
+            ret,
+        });
+        return false;
+    }
+    let ret = sbi::sse_enable(LOCAL_SOFTWARE_INJECTED);
+    if ret.is_err() {
+        let _ = sbi::sse_unregister(LOCAL_SOFTWARE_INJECTED);
+        f(Case::EventFailed {
+            function: "sse_enable",
+            ret,
+        });
+        return false;
+    }
+    let ret = sbi::sse_inject(LOCAL_SOFTWARE_INJECTED, hartid);
+    let _ = sbi::sse_disable(LOCAL_SOFTWARE_INJECTED);
+    let _ = sbi::sse_unregister(LOCAL_SOFTWARE_INJECTED);
+    if ret.is_err() {
+        f(Case::EventFailed {
+            function: "sse_inject",
+            ret,
+        });
+        return false;
+    }
+    let handled = HANDLED.load(Ordering::Acquire);
+    if REENTERED.load(Ordering::Acquire) {
+        f(Case::HandlerReentered);
+        false
+    } else if handled != 2 {
+        f(Case::InjectedNotHandled(handled));
+        false
+    } else {
+        f(Case::Injected(handled));
+        true
+    }
+}
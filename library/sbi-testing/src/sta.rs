@@ -0,0 +1,82 @@
+//! Steal-time accounting extension test suite.
+
+use sbi::{SbiRet, SharedPtr, StealTime};
+
+/// Steal-time accounting extension test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for steal-time accounting extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for misaligned shared memory has been rejected.
+    MisalignedShmemRejected,
+    /// Test failed for misaligned shared memory is not rejected with `SBI_ERR_INVALID_PARAM`.
+    MisalignedShmemAccepted(SbiRet),
+    /// Test process for reserved flags have been rejected.
+    InvalidFlagsRejected,
+    /// Test failed for reserved flags are not rejected with `SBI_ERR_INVALID_PARAM`.
+    InvalidFlagsAccepted(SbiRet),
+    /// Test failed for can't set steal-time shared memory.
+    RegisterFailed(SbiRet),
+    /// Test process for steal-time read from shared memory, in nanoseconds.
+    StealTime(u64),
+    /// Test failed for can't disable steal-time shared memory.
+    UnregisterFailed(SbiRet),
+    /// All test cases on steal-time accounting extension has passed.
+    Pass,
+}
+
+/// Test steal-time accounting extension.
+///
+/// Memory must be identically mapped on the current hart.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(mut f: impl FnMut(Case)) {
+    // 不支持 STA 扩展
+    if sbi::probe_extension(sbi::Sta).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    // 共享内存必须 64 字节对齐
+    let misaligned = &raw const BUFFER as usize + 8;
+    let ret = sbi::sta_set_shmem(SharedPtr::new(misaligned, 0), 0);
+    if ret == SbiRet::invalid_param() {
+        f(Case::MisalignedShmemRejected);
+    } else {
+        let _ = StealTime::unregister();
+        f(Case::MisalignedShmemAccepted(ret));
+        return;
+    }
+    // 标志位保留
+    let ret = sbi::sta_set_shmem(SharedPtr::new(&raw const BUFFER as usize, 0), 1);
+    if ret == SbiRet::invalid_param() {
+        f(Case::InvalidFlagsRejected);
+    } else {
+        let _ = StealTime::unregister();
+        f(Case::InvalidFlagsAccepted(ret));
+        return;
+    }
+    // 注册并读取
+    let ret = unsafe { STEAL_TIME.register() };
+    if ret.is_err() {
+        f(Case::RegisterFailed(ret));
+        return;
+    }
+    f(Case::StealTime(STEAL_TIME.steal()));
+    let ret = StealTime::unregister();
+    if ret.is_err() {
+        f(Case::UnregisterFailed(ret));
+        return;
+    }
+    f(Case::Pass);
+}
+
+static STEAL_TIME: StealTime = StealTime::new();
+
+/// 错误地接受错位地址时，实现写入的范围仍在缓冲区内
+#[repr(C, align(64))]
+struct Buffer([u8; 128]);
+
+static mut BUFFER: Buffer = Buffer([0; 128]);
@@ -0,0 +1,246 @@
+//! System suspend extension test suite.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use riscv::register::{sie, time};
+use sbi::{SbiRet, SleepType, SuspendToRam};
+use sbi_spec::hsm::hart_state;
+
+/// System suspend extension test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for system suspend extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test process for reserved sleep type has been rejected.
+    ReservedTypeRejected,
+    /// Test failed for reserved sleep type is not rejected with `SBI_ERR_INVALID_PARAM`.
+    ReservedTypeAccepted(SbiRet),
+    /// Test process for suspend to RAM is not supported.
+    SuspendNotSupported,
+    /// Test failed for no other harts are available to test suspending while they are running.
+    NoStoppedHart,
+    /// Test failed for can't start target hart with [`SbiRet`] error.
+    HartStartFailed {
+        /// The target hart ID that has failed to start.
+        hartid: usize,
+        /// The `SbiRet` value for the failed hart start SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for system suspend has been denied while another hart is running.
+    DeniedWhileHartRunning(usize),
+    /// Test failed for system suspend is not denied while another hart is running.
+    NotDeniedWhileHartRunning {
+        /// The running hart ID.
+        hartid: usize,
+        /// The `SbiRet` value for the system suspend SBI call.
+        ret: SbiRet,
+    },
+    /// Test process for system has resumed from suspend to RAM.
+    Resumed,
+    /// Test failed for suspend to RAM returned an error.
+    SuspendFailed(SbiRet),
+    /// All test cases on system suspend extension has passed.
+    Pass,
+}
+
+/// Test system suspend extension on given harts.
+///
+/// The first stopped hart except `primary_hart_id` in the hart list is started to check
+/// that suspend is denied while other harts are running. Other harts in the list must
+/// be stopped. The system is then suspended to RAM and woken up by a timer interrupt
+/// after `delay` ticks, and the test continues after resuming.
+///
+/// Memory must be identically mapped on the current hart, as the system resumes with
+/// address translation disabled.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(
+    primary_hart_id: usize,
+    mut hart_mask: usize,
+    hart_mask_base: usize,
+    delay: u64,
+    mut f: impl FnMut(Case),
+) {
+    // 不支持 SUSP 扩展
+    if sbi::probe_extension(sbi::Suspend).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    // 保留的休眠类型
+    let ret = unsafe { suspend(RESERVED_TYPE) };
+    if ret == SbiRet::invalid_param() {
+        f(Case::ReservedTypeRejected);
+    } else {
+        f(Case::ReservedTypeAccepted(ret));
+        return;
+    }
+    // 其他核运行时应拒绝休眠
+    let mut hartid = hart_mask_base;
+    loop {
+        if hart_mask == 0 {
+            f(Case::NoStoppedHart);
+            break;
+        }
+        if hart_mask & 1 == 1
+            && hartid != primary_hart_id
+            && sbi::hart_get_status(hartid) == STOPPED
+        {
+            if !test_denied(hartid, &mut f) {
+                return;
+            }
+            break;
+        }
+        hart_mask >>= 1;
+        hartid += 1;
+    }
+    // 休眠到内存，由时钟中断唤醒
+    sbi::set_timer(time::read64() + delay);
+    let ret = unsafe {
+        sie::set_stimer();
+        let ret = suspend(SuspendToRam.raw());
+        sie::clear_stimer();
+        ret
+    };
+    sbi::set_timer(u64::MAX);
+    if ret.is_ok() {
+        f(Case::Resumed);
+        f(Case::Pass);
+    } else if ret == SbiRet::not_supported() {
+        f(Case::SuspendNotSupported);
+        f(Case::Pass);
+    } else {
+        f(Case::SuspendFailed(ret));
+    }
+}
+
+const STARTED: SbiRet = SbiRet::success(hart_state::STARTED);
+const STOPPED: SbiRet = SbiRet::success(hart_state::STOPPED);
+
+/// 第一个保留的休眠类型
+const RESERVED_TYPE: u32 = 1;
+
+/// 通知副核停止
+static STOP: AtomicUsize = AtomicUsize::new(0);
+
+/// 启动一个副核，测试此时休眠被拒绝
+fn test_denied(hartid: usize, f: &mut impl FnMut(Case)) -> bool {
+    STOP.store(0, Ordering::Relaxed);
+    let ret = sbi::hart_start(hartid, spin_entry as _, &STOP as *const _ as _);
+    if ret.is_err() {
+        f(Case::HartStartFailed { hartid, ret });
+        return false;
+    }
+    while sbi::hart_get_status(hartid) != STARTED {
+        core::hint::spin_loop();
+    }
+    let ret = unsafe { suspend(SuspendToRam.raw()) };
+    // 关闭副核
+    STOP.store(1, Ordering::Release);
+    while sbi::hart_get_status(hartid) != STOPPED {
+        core::hint::spin_loop();
+    }
+    if ret == SbiRet::denied() {
+        f(Case::DeniedWhileHartRunning(hartid));
+        true
+    } else if ret == SbiRet::not_supported() {
+        f(Case::SuspendNotSupported);
+        true
+    } else {
+        f(Case::NotDeniedWhileHartRunning { hartid, ret });
+        false
+    }
+}
+
+/// 副核入口，等待信号后停止
+#[unsafe(naked)]
+unsafe extern "C" fn spin_entry(hartid: usize, stop: *const AtomicUsize) -> ! {
+    core::arch::naked_asm!(
+        "1: ld   t0, (a1)",
+        "   beqz t0, 1b",
+        "   li   a7, {eid}",
+        "   li   a6, {fid}",
+        "   ecall",
+        "2: j    2b",
+        eid = const sbi_spec::hsm::EID_HSM,
+        fid = const sbi_spec::hsm::HART_STOP,
+    )
+}
+
+/// 保存上下文并休眠系统
+///
+/// 恢复后如同 `system_suspend` 返回成功；失败时返回错误。
+#[unsafe(naked)]
+unsafe extern "C" fn suspend(sleep_type: u32) -> SbiRet {
+    core::arch::naked_asm!(
+        "addi sp, sp, -18*8",
+        "sd   ra,  0*8(sp)",
+        "sd   s0,  1*8(sp)",
+        "sd   s1,  2*8(sp)",
+        "sd   s2,  3*8(sp)",
+        "sd   s3,  4*8(sp)",
+        "sd   s4,  5*8(sp)",
+        "sd   s5,  6*8(sp)",
+        "sd   s6,  7*8(sp)",
+        "sd   s7,  8*8(sp)",
+        "sd   s8,  9*8(sp)",
+        "sd   s9, 10*8(sp)",
+        "sd  s10, 11*8(sp)",
+        "sd  s11, 12*8(sp)",
+        "sd   gp, 13*8(sp)",
+        "sd   tp, 14*8(sp)",
+        "csrr t0, stvec",
+        "sd   t0, 15*8(sp)",
+        "csrr t0, satp",
+        "sd   t0, 16*8(sp)",
+        "csrr t0, sie",
+        "sd   t0, 17*8(sp)",
+        "la   a1, {resume}",
+        "mv   a2, sp",
+        "li   a7, {eid}",
+        "li   a6, {fid}",
+        "ecall",
+        // 休眠失败，直接返回
+        "ld   ra,  0*8(sp)",
+        "addi sp, sp, 18*8",
+        "ret",
+        resume = sym resume,
+        eid = const sbi_spec::susp::EID_SUSP,
+        fid = const sbi_spec::susp::SUSPEND,
+    )
+}
+
+/// 休眠恢复入口，`a1` 为保存上下文的栈
+#[unsafe(naked)]
+unsafe extern "C" fn resume(hartid: usize, opaque: usize) -> ! {
+    core::arch::naked_asm!(
+        "mv   sp, a1",
+        "ld   t0, 16*8(sp)",
+        "csrw satp, t0",
+        "sfence.vma",
+        "ld   t0, 15*8(sp)",
+        "csrw stvec, t0",
+        "ld   t0, 17*8(sp)",
+        "csrw sie, t0",
+        "ld   ra,  0*8(sp)",
+        "ld   s0,  1*8(sp)",
+        "ld   s1,  2*8(sp)",
+        "ld   s2,  3*8(sp)",
+        "ld   s3,  4*8(sp)",
+        "ld   s4,  5*8(sp)",
+        "ld   s5,  6*8(sp)",
+        "ld   s6,  7*8(sp)",
+        "ld   s7,  8*8(sp)",
+        "ld   s8,  9*8(sp)",
+        "ld   s9, 10*8(sp)",
+        "ld  s10, 11*8(sp)",
+        "ld  s11, 12*8(sp)",
+        "ld   gp, 13*8(sp)",
+        "ld   tp, 14*8(sp)",
+        "addi sp, sp, 18*8",
+        "li   a0, 0",
+        "li   a1, 0",
+        "ret",
+    )
+}
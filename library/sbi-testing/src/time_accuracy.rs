@@ -0,0 +1,282 @@
+//! Timer programmer extension accuracy and Sstc interaction test suite.
+
+use core::arch::asm;
+use riscv::register::time;
+
+/// Timer accuracy test cases.
+#[derive(Clone, Debug)]
+pub enum Case {
+    /// Can't proceed test for Timer extension does not exist.
+    NotExist,
+    /// Test begin.
+    Begin,
+    /// Test failed for pending timer interrupt is not cleared after setting the deadline to `u64::MAX`.
+    PendingNotCleared(Source),
+    /// Test process for a deadline in the past has fired.
+    PastDeadline {
+        /// The deadline in the past.
+        deadline: u64,
+        /// The time counter value when the timer interrupt was observed.
+        fired: u64,
+    },
+    /// Test failed for timer interrupt has not fired in time after the deadline.
+    Lost {
+        /// How the deadline was set.
+        source: Source,
+        /// The deadline.
+        deadline: u64,
+    },
+    /// Test failed for timer interrupt has fired before the deadline.
+    FiredEarly {
+        /// How the deadline was set.
+        source: Source,
+        /// The deadline.
+        deadline: u64,
+        /// The time counter value when the timer interrupt was observed.
+        fired: u64,
+    },
+    /// Test failed for timer interrupt has fired after the deadline was overridden by the other source.
+    NotOverridden {
+        /// How the overridden deadline was set.
+        source: Source,
+        /// The overridden deadline.
+        deadline: u64,
+    },
+    /// Test process for timer interrupt latency across all deadlines.
+    Latency {
+        /// How the deadlines were set.
+        source: Source,
+        /// Number of deadlines tested.
+        count: usize,
+        /// Minimum latency in `time` ticks.
+        min: u64,
+        /// Maximum latency in `time` ticks.
+        max: u64,
+        /// Mean latency in `time` ticks.
+        mean: u64,
+    },
+    /// Test process for `stimecmp` is not accessible, Sstc tests are skipped.
+    SstcNotPresent,
+    /// Test process for `stimecmp` is accessible, Sstc tests begin.
+    SstcPresent,
+    /// All test cases on timer accuracy has passed.
+    Pass,
+}
+
+/// How a timer deadline is set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// `set_timer` function in Timer extension.
+    Sbi,
+    /// Direct write to `stimecmp` CSR provided by Sstc extension.
+    Stimecmp,
+}
+
+/// Test accuracy of timer extension and its interaction with Sstc extension.
+///
+/// Supervisor interrupts are disabled during the test, timer interrupts are observed by polling `sip.STIP`.
+/// `timeout` is the time in `time` ticks to wait for each deadline, deadlines are multiples of `timeout / 1000`.
+///
+/// The test case output is to be handled in `f`.
+pub fn test(timeout: u64, mut f: impl FnMut(Case)) {
+    if sbi::probe_extension(sbi::Timer).is_unavailable() {
+        f(Case::NotExist);
+        return;
+    }
+    f(Case::Begin);
+    let sie = unsafe { disable_interrupt() };
+    let pass = accuracy(timeout, &mut f);
+    sbi::set_timer(u64::MAX);
+    if sie {
+        unsafe { enable_interrupt() };
+    }
+    if pass {
+        f(Case::Pass);
+    }
+}
+
+const ROUNDS: usize = 32;
+
+fn accuracy(timeout: u64, f: &mut impl FnMut(Case)) -> bool {
+    let step = (timeout / 1000).max(1);
+    // 清除等待的中断
+    if !clear(Source::Sbi, f) {
+        return false;
+    }
+    // 过去的时刻立即触发
+    let deadline = time::read64().saturating_sub(step);
+    sbi::set_timer(deadline);
+    let Some(fired) = wait(time::read64(), timeout) else {
+        f(Case::Lost {
+            source: Source::Sbi,
+            deadline,
+        });
+        return false;
+    };
+    f(Case::PastDeadline { deadline, fired });
+    // 已触发的中断也能清除
+    if !clear(Source::Sbi, f) {
+        return false;
+    }
+    // 不同时长的延迟
+    if !latency(Source::Sbi, step, timeout, f) {
+        return false;
+    }
+    if !sstc_present() {
+        f(Case::SstcNotPresent);
+        return true;
+    }
+    f(Case::SstcPresent);
+    if !clear(Source::Stimecmp, f) || !latency(Source::Stimecmp, step, timeout, f) {
+        return false;
+    }
+    // 两种方式设置的时刻互相覆盖
+    for (first, second) in [
+        (Source::Stimecmp, Source::Sbi),
+        (Source::Sbi, Source::Stimecmp),
+    ] {
+        let deadline = time::read64() + step;
+        set(first, deadline);
+        set(second, u64::MAX);
+        if wait(deadline, step).is_some() {
+            f(Case::NotOverridden {
+                source: first,
+                deadline,
+            });
+            return false;
+        }
+        // 覆盖后再次设置仍能触发
+        let deadline = time::read64() + step;
+        set(first, deadline);
+        if !fire(first, deadline, timeout, f).is_some_and(|_| clear(second, f)) {
+            return false;
+        }
+    }
+    true
+}
+
+/// 依次设置 `ROUNDS` 个时刻，检查触发时间
+fn latency(source: Source, step: u64, timeout: u64, f: &mut impl FnMut(Case)) -> bool {
+    let (mut min, mut max, mut sum) = (u64::MAX, 0, 0);
+    for round in 0..ROUNDS {
+        let deadline = time::read64() + step * (round as u64 + 1);
+        set(source, deadline);
+        let Some(latency) = fire(source, deadline, timeout, f) else {
+            return false;
+        };
+        min = min.min(latency);
+        max = max.max(latency);
+        sum += latency;
+        if !clear(source, f) {
+            return false;
+        }
+    }
+    f(Case::Latency {
+        source,
+        count: ROUNDS,
+        min,
+        max,
+        mean: sum / ROUNDS as u64,
+    });
+    true
+}
+
+/// 等待 `deadline` 触发，返回延迟
+fn fire(source: Source, deadline: u64, timeout: u64, f: &mut impl FnMut(Case)) -> Option<u64> {
+    match wait(deadline, timeout) {
+        Some(fired) if fired < deadline => {
+            f(Case::FiredEarly {
+                source,
+                deadline,
+                fired,
+            });
+            None
+        }
+        Some(fired) => Some(fired - deadline),
+        None => {
+            f(Case::Lost { source, deadline });
+            None
+        }
+    }
+}
+
+/// 将时刻设为最大值，等待的中断应立即清除
+fn clear(source: Source, f: &mut impl FnMut(Case)) -> bool {
+    set(source, u64::MAX);
+    if stip_pending() {
+        f(Case::PendingNotCleared(source));
+        return false;
+    }
+    true
+}
+
+/// 轮询等待中断，返回观察到中断时的时间，超过 `deadline + timeout` 返回 `None`
+fn wait(deadline: u64, timeout: u64) -> Option<u64> {
+    loop {
+        // 先读中断再读时间，观察到的时间不早于触发时间
+        let pending = stip_pending();
+        let now = time::read64();
+        if pending {
+            return Some(now);
+        }
+        if now > deadline.saturating_add(timeout) {
+            return None;
+        }
+        core::hint::spin_loop();
+    }
+}
+
+#[inline]
+fn set(source: Source, deadline: u64) {
+    match source {
+        Source::Sbi => {
+            sbi::set_timer(deadline);
+        }
+        Source::Stimecmp => unsafe { asm!("csrw 0x14d, {}", in(reg) deadline) },
+    }
+}
+
+/// 尝试读取 `stimecmp`，不存在时陷入到下一条指令之后
+fn sstc_present() -> bool {
+    let mut present = 0usize;
+    unsafe {
+        asm!(
+            "   csrr {stvec}, stvec
+                la   {tmp},   2f
+                csrw stvec,   {tmp}
+                csrr {tmp},   0x14d
+                li   {present}, 1
+            .align 2
+            2:
+                csrw stvec,   {stvec}
+            ",
+            stvec   = out(reg) _,
+            tmp     = out(reg) _,
+            present = inlateout(reg) present,
+        );
+    }
+    present != 0
+}
+
+const STIP: usize = 1 << 5;
+const SIE: usize = 1 << 1;
+
+#[inline]
+fn stip_pending() -> bool {
+    let sip: usize;
+    unsafe { asm!("csrr {}, sip", out(reg) sip) };
+    sip & STIP != 0
+}
+
+/// 关闭 S 态中断，返回之前是否开启
+#[inline]
+unsafe fn disable_interrupt() -> bool {
+    let sstatus: usize;
+    unsafe { asm!("csrrc {}, sstatus, {}", out(reg) sstatus, in(reg) SIE) };
+    sstatus & SIE != 0
+}
+
+#[inline]
+unsafe fn enable_interrupt() {
+    unsafe { asm!("csrs sstatus, {}", in(reg) SIE) };
+}
@@ -74,7 +74,7 @@ impl PmuState {
             return None;
         }
 
-        Some(EventIdx::new(self.active_event[counter_idx]))
+        Some(EventIdx::from_raw(self.active_event[counter_idx]))
     }
 
     /// Gets the value of a firmware counter.
@@ -183,9 +183,7 @@ impl Pmu for SbiPmu {
                 if count == counter_idx {
                     // Found the counter - get its CSR offset
                     let offset = remaining_mask.trailing_zeros() as u16;
-                    return SbiRet::success(
-                        CounterInfo::with_hardware_info(CSR_CYCLE + offset, 63).inner(),
-                    );
+                    return SbiRet::success(CounterInfo::hardware(CSR_CYCLE + offset, 63).raw());
                 }
                 remaining_mask &= remaining_mask - 1;
                 count += 1;
@@ -193,7 +191,7 @@ impl Pmu for SbiPmu {
             return SbiRet::invalid_param();
         }
 
-        SbiRet::success(CounterInfo::with_firmware_info().inner())
+        SbiRet::success(CounterInfo::firmware().raw())
     }
 
     /// Find and configure a matching counter (FID #2)
@@ -211,14 +209,14 @@ impl Pmu for SbiPmu {
             None => return SbiRet::invalid_param(), // Reserved bits are set
         };
 
-        let event = EventIdx::new(event_idx);
+        let event = EventIdx::from_raw(event_idx);
         let pmu_state = &mut hart_context_mut(current_hartid()).pmu_state;
         let is_firmware_event = event.is_firmware_event();
 
         if counter_idx_base >= pmu_state.total_counters_num
             || (counter_idx_mask & ((1 << pmu_state.total_counters_num) - 1)) == 0
-            || !event.check_event_type()
-            || (is_firmware_event && !event.firmware_event_valid())
+            || !event.is_valid()
+            || (is_firmware_event && !firmware_event_valid(event))
         {
             return SbiRet::invalid_param();
         }
@@ -381,7 +379,7 @@ impl Pmu for SbiPmu {
     fn counter_fw_read(&self, counter_idx: usize) -> SbiRet {
         let pmu_state = &hart_context(current_hartid()).pmu_state;
         match pmu_state.get_event_idx(counter_idx, true) {
-            Some(event_id) if event_id.firmware_event_valid() => {
+            Some(event_id) if firmware_event_valid(event_id) => {
                 if event_id.event_code() == firmware_event::PLATFORM {
                     // TODO: Handle platform-specific PMU events
                     return SbiRet::invalid_param();
@@ -431,8 +429,8 @@ impl SbiPmu {
         pmu_state: &PmuState,
     ) -> Result<usize, SbiRet> {
         // TODO: support `PLATFORM` event
-        let event = EventIdx::new(event_idx);
-        if !event.firmware_event_valid() {
+        let event = EventIdx::from_raw(event_idx);
+        if !firmware_event_valid(event) {
             return Err(SbiRet::not_supported());
         }
 
@@ -468,7 +466,7 @@ impl SbiPmu {
         event_data: u64,
         pmu_state: &PmuState,
     ) -> Result<usize, SbiRet> {
-        let event = EventIdx::new(event_idx);
+        let event = EventIdx::from_raw(event_idx);
         let mut hw_counters_mask = 0;
         // Find the counters available for the event.
         if event.is_raw_event() {
@@ -550,7 +548,7 @@ impl SbiPmu {
             return Err(SbiRet::not_supported());
         }
 
-        let event = EventIdx::new(event_idx);
+        let event = EventIdx::from_raw(event_idx);
 
         // Determine the value to write to mhpmevent CSR
         let mhpmevent_val = if event.is_raw_event() {
@@ -790,182 +788,14 @@ fn write_mhpmcounter(mhpm_offset: u16, mhpmcounter_val: u64) {
     }
 }
 
-/// Wrap for counter info
-struct CounterInfo {
-    /// Packed representation of counter information:
-    /// - Bits [11:0]: CSR number for hardware counters
-    /// - Bits [17:12]: Counter width (typically 63 for RV64)
-    /// - MSB: Set for firmware counters, clear for hardware counters
-    inner: usize,
-}
-
-#[allow(unused)]
-impl CounterInfo {
-    const CSR_MASK: usize = 0xFFF; // Bits [11:0]
-    const WIDTH_MASK: usize = 0x3F << 12; // Bits [17:12]
-    const FIRMWARE_FLAG: usize = 1 << (size_of::<usize>() * 8 - 1); // MSB
-
-    #[inline]
-    pub const fn new() -> Self {
-        Self { inner: 0 }
-    }
-
-    #[inline]
-    pub fn set_csr(&mut self, csr_num: u16) {
-        self.inner = (self.inner & !Self::CSR_MASK) | ((csr_num as usize) & Self::CSR_MASK);
-    }
-
-    #[inline]
-    pub fn set_width(&mut self, width: u8) {
-        self.inner = (self.inner & !Self::WIDTH_MASK) | (((width as usize) & 0x3F) << 12);
-    }
-
-    #[inline]
-    pub const fn with_hardware_info(csr_num: u16, width: u8) -> Self {
-        Self {
-            inner: ((csr_num as usize) & Self::CSR_MASK) | (((width as usize) & 0x3F) << 12),
-        }
-    }
-
-    #[inline]
-    pub const fn with_firmware_info() -> Self {
-        Self {
-            inner: Self::FIRMWARE_FLAG,
-        }
-    }
-
-    #[inline]
-    pub const fn inner(self) -> usize {
-        self.inner
-    }
-}
-
-impl Default for CounterInfo {
-    #[inline]
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-#[derive(Clone, Copy)]
-pub struct EventIdx {
-    /// Packed representation of event information:
-    /// - Bits [15:0]: Event code
-    /// - Bits [19:16]: Event type
-    inner: usize,
-}
-
-#[allow(unused)]
-impl EventIdx {
-    #[inline]
-    const fn new(event_idx: usize) -> Self {
-        Self { inner: event_idx }
-    }
-
-    #[inline]
-    fn from_firmwarw_event(firmware_event: usize) -> Self {
-        Self {
-            inner: 0xf << 16 | firmware_event,
-        }
-    }
-
-    #[inline]
-    fn raw(&self) -> usize {
-        self.inner
-    }
-
-    #[inline]
-    const fn event_type(&self) -> usize {
-        (self.inner >> 16) & 0xF
-    }
-
-    #[inline]
-    const fn event_code(&self) -> usize {
-        self.inner & 0xFFFF
-    }
-
-    /// Extracts the cache ID for HARDWARE_CACHE events (13 bits, [15:3])
-    #[inline]
-    const fn cache_id(&self) -> usize {
-        (self.inner >> 3) & 0x1FFF
-    }
-
-    /// Extracts the cache operation ID (2 bits, [2:1])
-    #[inline]
-    const fn cache_op_id(&self) -> usize {
-        (self.inner >> 1) & 0x3
-    }
-
-    /// Extracts the cache result ID (1 bit, [0])
-    #[inline]
-    const fn cache_result_id(&self) -> usize {
-        self.inner & 0x1
-    }
-
-    #[inline]
-    const fn is_general_event(&self) -> bool {
-        self.event_type() == event_type::HARDWARE_GENERAL
-    }
-
-    #[inline]
-    const fn is_cache_event(&self) -> bool {
-        self.event_type() == event_type::HARDWARE_CACHE
-    }
-
-    #[inline]
-    const fn is_raw_event_v1(&self) -> bool {
-        self.event_type() == event_type::HARDWARE_RAW
-    }
-
-    #[inline]
-    const fn is_raw_event_v2(&self) -> bool {
-        self.event_type() == event_type::HARDWARE_RAW_V2
-    }
-
-    #[inline]
-    const fn is_raw_event(&self) -> bool {
-        self.is_raw_event_v1() || self.is_raw_event_v2()
-    }
-
-    #[inline]
-    const fn is_firmware_event(&self) -> bool {
-        self.event_type() == event_type::FIRMWARE
-    }
-
-    #[inline]
-    fn check_event_type(self) -> bool {
-        let event_type = self.event_type();
-        let event_code = self.event_code();
-
-        match event_type {
-            event_type::HARDWARE_GENERAL => event_code <= hardware_event::REF_CPU_CYCLES,
-            event_type::HARDWARE_CACHE => {
-                self.cache_id() <= cache_event::NODE
-                    && self.cache_op_id() <= cache_operation::PREFETCH
-                    && self.cache_result_id() <= cache_result::MISS
-            }
-            event_type::HARDWARE_RAW | event_type::HARDWARE_RAW_V2 => event_code == 0,
-            event_type::FIRMWARE => true,
-            _ => false,
-        }
-    }
-
-    #[inline]
-    fn firmware_event_valid(self) -> bool {
-        let event_type = self.event_type();
-        let event_code = self.event_code();
-        if event_type != event_type::FIRMWARE {
-            return false;
-        }
-        if (event_code > firmware_event::HFENCE_VVMA_ASID_RECEIVED
-            && event_code < firmware_event::PLATFORM)
-            || event_code >= firmware_event::PLATFORM
-        {
-            // TODO:Currently RustSBI Prototyper does not support PLATFORM practice
-            return false;
-        }
-        true
+/// Checks if the firmware event is supported by RustSBI Prototyper.
+#[inline]
+fn firmware_event_valid(event: EventIdx) -> bool {
+    if !event.is_firmware_event() {
+        return false;
     }
+    // TODO:Currently RustSBI Prototyper does not support PLATFORM practice
+    event.event_code() <= firmware_event::HFENCE_VVMA_ASID_RECEIVED
 }
 
 /// event to mhpmcounter map
@@ -1131,8 +961,7 @@ pub fn pmu_firmware_counter_increment(firmware_event: usize) {
     let counter_idx_start = pmu_state.hw_counters_num;
     for counter_idx in counter_idx_start..counter_idx_start + PMU_FIRMWARE_COUNTER_MAX {
         let fw_idx = counter_idx - counter_idx_start;
-        if pmu_state.active_event[counter_idx]
-            == EventIdx::from_firmwarw_event(firmware_event).raw()
+        if pmu_state.active_event[counter_idx] == EventIdx::firmware(firmware_event).raw()
             && pmu_state.is_firmware_event_start(counter_idx)
         {
             pmu_state.fw_counter[fw_idx] += 1;
@@ -12,7 +12,7 @@ use core::{
 use riscv::register::cycle;
 use sbi_spec::{
     binary::{CounterMask, HartMask, SbiRet},
-    pmu::firmware_event,
+    pmu::{CounterInfo, EventIdx, firmware_event},
 };
 use sbi_testing::sbi::{self, ConfigFlags, StartFlags, StopFlags};
 // use sbi_spec::pmu::*;
@@ -80,6 +80,7 @@ extern "C" fn rust_main(hartid: usize, dtb_pa: usize) -> ! {
         smp,
         frequency,
         uart,
+        memory,
     } = BoardInfo::parse(dtb_pa);
     unsafe { UART = Uart16550Map(uart as _) };
     rcore_console::init_console(&Console);
@@ -104,11 +105,22 @@ extern "C" fn rust_main(hartid: usize, dtb_pa: usize) -> ! {
         hart_mask_base: 0,
         delay: frequency,
     };
-    let test_result = testing.test();
+    // Stream machine-readable results over the SBI console when built with `REPORT=tap` or `REPORT=junit`
+    let console = sbi::console::SbiConsole::new();
+    let mut test_result = match option_env!("REPORT") {
+        Some("tap") => testing.report(&mut sbi_testing::Tap::new(console, frequency)),
+        Some("junit") => testing.report(&mut sbi_testing::JUnit::new(console, frequency)),
+        _ => testing.test(),
+    };
 
     pmu_test();
     fence_test();
 
+    // Send randomized malformed SBI calls when built with `FUZZ=<seed>`
+    if let Some(seed) = option_env!("FUZZ").and_then(|seed| seed.parse().ok()) {
+        test_result &= fuzz_test(seed, memory);
+    }
+
     if test_result {
         sbi::system_reset(sbi::Shutdown, sbi::NoReason);
     } else {
@@ -124,15 +136,15 @@ fn pmu_test() {
     println!("[pmu] counters number: {}", counters_num);
     for idx in 0..counters_num {
         let counter_info = sbi::pmu_counter_get_info(idx);
-        let counter_info = CounterInfo::new(counter_info.value);
-        if counter_info.is_firmware_counter() {
+        let counter_info = CounterInfo::from_raw(counter_info.value);
+        if counter_info.is_firmware() {
             println!("[pmu] counter index:{:>2}, is a firmware counter", idx);
         } else {
             println!(
                 "[pmu] counter index:{:>2}, csr num: {:#03x}, width: {}",
                 idx,
-                counter_info.get_csr(),
-                counter_info.get_width()
+                counter_info.csr(),
+                counter_info.width()
             );
         }
     }
@@ -201,7 +213,7 @@ fn pmu_test() {
     let result = sbi::pmu_counter_config_matching(
         counter_mask,
         Flag::new(0b010),
-        EventIdx::new_firmware_event(firmware_event::ACCESS_LOAD).raw(),
+        EventIdx::firmware(firmware_event::ACCESS_LOAD).raw(),
         0,
     );
     assert_eq!(result, SbiRet::not_supported());
@@ -211,7 +223,7 @@ fn pmu_test() {
     let result = sbi::pmu_counter_config_matching(
         counter_mask,
         Flag::new(0b010),
-        EventIdx::new_firmware_event(firmware_event::IPI_SENT).raw(),
+        EventIdx::firmware(firmware_event::IPI_SENT).raw(),
         0,
     );
     assert!(result.is_ok());
@@ -312,6 +324,25 @@ fn fence_test() {
     assert!(ret.is_ok() || ret == SbiRet::not_supported());
 }
 
+// Fuzz test, SBI implementation is loaded in RAM below the test kernel,
+// so memory from the RAM start in device tree up to the kernel is protected
+fn fuzz_test(seed: u64, memory: usize) -> bool {
+    use sbi_testing::{FuzzCase, test_fuzz};
+    let kernel = _boot_header as *const () as usize;
+    let mut pass = false;
+    test_fuzz(seed, 64, memory.min(kernel)..kernel, |case| match case {
+        FuzzCase::Begin => println!("[fuzz] seed = {seed}"),
+        FuzzCase::Extension(eid) => println!("[fuzz] extension {eid:#x}"),
+        FuzzCase::Call(call) => log::trace!("[fuzz] {call:x?}"),
+        FuzzCase::Pass => {
+            println!("[fuzz] test pass");
+            pass = true;
+        }
+        case => println!("[fuzz] {case:x?}"),
+    });
+    pass
+}
+
 #[cfg_attr(not(test), panic_handler)]
 fn panic(info: &core::panic::PanicInfo) -> ! {
     let (hart_id, pc): (usize, usize);
@@ -328,6 +359,7 @@ struct BoardInfo {
     smp: usize,
     frequency: u64,
     uart: usize,
+    memory: usize,
 }
 
 impl BoardInfo {
@@ -338,6 +370,7 @@ impl BoardInfo {
             smp: 0,
             frequency: 0,
             uart: 0,
+            memory: 0,
         };
         unsafe {
             Dtb::from_raw_parts_filtered(dtb_pa as _, |e| {
@@ -347,7 +380,11 @@ impl BoardInfo {
         .unwrap()
         .walk(|ctx, obj| match obj {
             DtbObj::SubNode { name } => {
-                if ctx.is_root() && (name == Str::from("cpus") || name == Str::from("soc")) {
+                if ctx.is_root()
+                    && (name == Str::from("cpus")
+                        || name == Str::from("soc")
+                        || name.starts_with("memory@"))
+                {
                     StepInto
                 } else if ctx.name() == Str::from("cpus") && name.starts_with("cpu@") {
                     ans.smp += 1;
@@ -363,6 +400,8 @@ impl BoardInfo {
             DtbObj::Property(Property::Reg(mut reg)) => {
                 if ctx.name().starts_with("uart") || ctx.name().starts_with("serial") {
                     ans.uart = reg.next().unwrap().start;
+                } else if ctx.name().starts_with("memory@") && ans.memory == 0 {
+                    ans.memory = reg.next().unwrap().start;
                 }
                 StepOut
             }
@@ -435,85 +474,3 @@ impl Flag {
         Self { inner: flag }
     }
 }
-
-/// Wrap for counter info
-struct CounterInfo {
-    /// Packed representation of counter information:
-    /// - Bits [11:0]: CSR number for hardware counters
-    /// - Bits [17:12]: Counter width (typically 63 for RV64)
-    /// - MSB: Set for firmware counters, clear for hardware counters
-    inner: usize,
-}
-
-#[allow(unused)]
-impl CounterInfo {
-    const CSR_MASK: usize = 0xFFF; // Bits [11:0]
-    const WIDTH_MASK: usize = 0x3F << 12; // Bits [17:12]
-    const FIRMWARE_FLAG: usize = 1 << (size_of::<usize>() * 8 - 1); // MSB
-
-    #[inline]
-    pub const fn new(counter_info: usize) -> Self {
-        Self {
-            inner: counter_info,
-        }
-    }
-
-    #[inline]
-    pub fn set_csr(&mut self, csr_num: u16) {
-        self.inner = (self.inner & !Self::CSR_MASK) | ((csr_num as usize) & Self::CSR_MASK);
-    }
-
-    #[inline]
-    pub fn get_csr(&self) -> usize {
-        self.inner & Self::CSR_MASK
-    }
-
-    #[inline]
-    pub fn set_width(&mut self, width: u8) {
-        self.inner = (self.inner & !Self::WIDTH_MASK) | (((width as usize) & 0x3F) << 12);
-    }
-
-    #[inline]
-    pub fn get_width(&self) -> usize {
-        (self.inner & Self::WIDTH_MASK) >> 12
-    }
-
-    #[inline]
-    pub fn is_firmware_counter(&self) -> bool {
-        self.inner & Self::FIRMWARE_FLAG != 0
-    }
-
-    #[inline]
-    pub const fn with_hardware_info(csr_num: u16, width: u8) -> Self {
-        Self {
-            inner: ((csr_num as usize) & Self::CSR_MASK) | (((width as usize) & 0x3F) << 12),
-        }
-    }
-
-    #[inline]
-    pub const fn with_firmware_info() -> Self {
-        Self {
-            inner: Self::FIRMWARE_FLAG,
-        }
-    }
-
-    #[inline]
-    pub const fn inner(self) -> usize {
-        self.inner
-    }
-}
-
-struct EventIdx {
-    inner: usize,
-}
-
-impl EventIdx {
-    fn raw(&self) -> usize {
-        self.inner
-    }
-
-    fn new_firmware_event(event_code: usize) -> Self {
-        let inner = 0xf << 16 | event_code;
-        Self { inner }
-    }
-}
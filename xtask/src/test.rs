@@ -1,10 +1,11 @@
 use std::{
     env, fs,
+    io::{BufRead, BufReader},
     path::{Path, PathBuf},
-    process::{Command, ExitStatus},
+    process::{Command, ExitStatus, Stdio},
 };
 
-use clap::Args;
+use clap::{Args, ValueEnum};
 
 use crate::utils::cargo;
 
@@ -13,20 +14,57 @@ pub struct TestArg {
     /// Package Prototyper and Test-Kernel into a single image
     #[clap(long, help = "Create a combined image with Prototyper and test kernel")]
     pub pack: bool,
+    /// Machine-readable result format streamed by the test kernel
+    #[clap(long, value_enum)]
+    pub report: Option<ReportFormat>,
+    /// Send randomized malformed SBI calls generated from this seed after testing
+    #[clap(long, value_name = "SEED")]
+    pub fuzz: Option<u64>,
+    /// Run the test kernel in QEMU on Prototyper in dynamic mode and check the results
+    #[clap(long)]
+    pub run: bool,
+    /// Number of harts for QEMU when running the test kernel
+    #[clap(long, default_value_t = 4, requires = "run")]
+    pub smp: usize,
+    /// Check results in the `--report` format (TAP by default) in a captured test kernel console log instead of building
+    #[clap(long, value_name = "LOG", conflicts_with = "run")]
+    pub results: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    /// Test Anything Protocol version 13
+    Tap,
+    /// JUnit XML
+    Junit,
 }
 
 const ARCH: &str = "riscv64imac-unknown-none-elf";
 const TEST_KERNEL_NAME: &str = "rustsbi-test-kernel";
 const PROTOTYPER_BIN: &str = "rustsbi-prototyper.bin";
+const PROTOTYPER_DYNAMIC_ELF: &str = "rustsbi-prototyper-dynamic.elf";
 
 #[must_use]
 pub fn run(arg: &TestArg) -> Option<ExitStatus> {
+    // Results are reported in TAP by default when they are to be checked
+    let format = arg.report.unwrap_or(ReportFormat::Tap);
+    if let Some(log) = &arg.results {
+        return match fs::read_to_string(log) {
+            Ok(content) => check_results(&content, format),
+            Err(e) => {
+                error!("Failed to read '{}': {}", log.display(), e);
+                exit_status(false)
+            }
+        };
+    }
+
     let current_dir = env::current_dir().ok()?;
     let target_dir = get_target_dir(&current_dir);
 
     // Build the test kernel
     info!("Building test kernel");
-    let build_status = build_test_kernel()?;
+    let report = if arg.run { Some(format) } else { arg.report };
+    let build_status = build_test_kernel(report, arg.fuzz)?;
     if !build_status.success() {
         error!("Failed to build test kernel");
         return Some(build_status);
@@ -40,6 +78,12 @@ pub fn run(arg: &TestArg) -> Option<ExitStatus> {
         return Some(exit_status);
     }
 
+    // Run in QEMU and check results if requested
+    if arg.run {
+        info!("Running test kernel in QEMU");
+        return run_qemu(&target_dir, format, arg.smp);
+    }
+
     // Pack into image if requested
     if arg.pack {
         info!("Packing into image");
@@ -78,13 +122,368 @@ fn get_target_dir(current_dir: &Path) -> PathBuf {
     current_dir.join("target").join(ARCH).join("release")
 }
 
-fn build_test_kernel() -> Option<ExitStatus> {
-    cargo::Cargo::new("build")
-        .package(TEST_KERNEL_NAME)
-        .target(ARCH)
-        .release()
-        .status()
-        .ok()
+fn build_test_kernel(report: Option<ReportFormat>, fuzz: Option<u64>) -> Option<ExitStatus> {
+    let mut cargo = cargo::Cargo::new("build");
+    cargo.package(TEST_KERNEL_NAME).target(ARCH).release();
+    match report {
+        Some(ReportFormat::Tap) => cargo.env("REPORT", "tap"),
+        Some(ReportFormat::Junit) => cargo.env("REPORT", "junit"),
+        None => cargo.env_remove("REPORT"),
+    };
+    match fuzz {
+        Some(seed) => cargo.env("FUZZ", seed.to_string()),
+        None => cargo.env_remove("FUZZ"),
+    };
+    cargo.status().ok()
+}
+
+/// Run the test kernel on Prototyper in QEMU, echo the console and check the results.
+///
+/// JUnit XML in the console, without log lines printed while reporting, is also saved next to
+/// the test kernel for CI to collect, and the saved file is what gets checked.
+fn run_qemu(target_dir: &Path, format: ReportFormat, smp: usize) -> Option<ExitStatus> {
+    let prototyper = target_dir.join(PROTOTYPER_DYNAMIC_ELF);
+    if !prototyper.exists() {
+        error!(
+            "Prototyper not found at '{}'\n\
+             Please run 'cargo prototyper' first to build the Prototyper in dynamic mode.",
+            prototyper.display()
+        );
+        return exit_status(false);
+    }
+    let mut child = match Command::new("qemu-system-riscv64")
+        .args(["-machine", "virt", "-display", "none", "-serial", "stdio"])
+        .args(["-smp", &smp.to_string()])
+        .arg("-bios")
+        .arg(&prototyper)
+        .arg("-kernel")
+        .arg(target_dir.join(TEST_KERNEL_NAME))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            error!("Failed to execute qemu-system-riscv64: {}", e);
+            return exit_status(false);
+        }
+    };
+    let mut content = String::new();
+    for line in BufReader::new(child.stdout.take()?).lines() {
+        let Ok(line) = line else { break };
+        println!("{}", line);
+        content.push_str(&line);
+        content.push('\n');
+    }
+    let status = child.wait().ok()?;
+    if !status.success() {
+        error!("QEMU exited with {}", status);
+        return Some(status);
+    }
+    if let ReportFormat::Junit = format {
+        let Some(document) = junit_document(&content) else {
+            error!("JUnit results not finished, test kernel may have stopped early");
+            return exit_status(false);
+        };
+        let xml = target_dir.join(format!("{}.xml", TEST_KERNEL_NAME));
+        if let Err(e) = fs::write(&xml, document) {
+            error!("Failed to write '{}': {}", xml.display(), e);
+            return exit_status(false);
+        }
+        info!("JUnit results saved at: {}", xml.display());
+        // Check the saved file itself, as it is what CI collects
+        return match fs::read_to_string(&xml) {
+            Ok(saved) => check_junit_document(&saved),
+            Err(e) => {
+                error!("Failed to read '{}': {}", xml.display(), e);
+                exit_status(false)
+            }
+        };
+    }
+    check_results(&content, format)
+}
+
+fn check_results(content: &str, format: ReportFormat) -> Option<ExitStatus> {
+    match format {
+        ReportFormat::Tap => check_tap(content),
+        ReportFormat::Junit => check_junit(content),
+    }
+}
+
+fn check_tap(content: &str) -> Option<ExitStatus> {
+    let (mut passed, mut skipped, mut failed) = (0, 0, Vec::new());
+    let mut plan = None;
+    for line in content.lines().map(str::trim_end) {
+        if let Some(rest) = line.strip_prefix("not ok ") {
+            failed.push(test_name(rest));
+        } else if let Some(rest) = line.strip_prefix("ok ") {
+            if rest.contains("# SKIP") {
+                skipped += 1;
+            } else {
+                passed += 1;
+            }
+        } else if let Some(count) = line.strip_prefix("1..") {
+            plan = count.parse::<usize>().ok();
+        }
+    }
+    let total = passed + skipped + failed.len();
+    summarize(passed, skipped, &failed);
+    match plan {
+        Some(plan) if plan == total => exit_status(failed.is_empty()),
+        Some(plan) => {
+            error!("Planned {} test cases, but {} reported", plan, total);
+            exit_status(false)
+        }
+        None => {
+            error!("No TAP plan found, test kernel may have stopped early");
+            exit_status(false)
+        }
+    }
+}
+
+fn check_junit(content: &str) -> Option<ExitStatus> {
+    match junit_document(content) {
+        Some(document) => check_junit_document(&document),
+        None => {
+            error!("JUnit results not finished, test kernel may have stopped early");
+            exit_status(false)
+        }
+    }
+}
+
+fn check_junit_document(document: &str) -> Option<ExitStatus> {
+    match parse_junit(document) {
+        Ok((passed, skipped, failed)) => {
+            summarize(passed, skipped, &failed);
+            exit_status(failed.is_empty())
+        }
+        Err(e) => {
+            error!("Invalid JUnit XML: {}", e);
+            exit_status(false)
+        }
+    }
+}
+
+/// Extract the JUnit XML document from the console, dropping log lines printed while reporting.
+fn junit_document(content: &str) -> Option<String> {
+    let start = content.find("<?xml")?;
+    let end = content.rfind("</testsuites>")? + "</testsuites>".len();
+    let mut document = String::new();
+    for line in content.get(start..end)?.lines() {
+        let line = strip_ansi(line);
+        let line = line.trim();
+        if ["<?xml", "<testsuite", "</testsuite", "<testcase"]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            document.push_str(line);
+            document.push('\n');
+        }
+    }
+    Some(document)
+}
+
+/// Remove ANSI escape sequences, e.g. colors of log lines.
+fn strip_ansi(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            result.push(c);
+        } else if chars.next_if_eq(&'[').is_some() {
+            // Skip parameters up to the final byte of the control sequence
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Parse a JUnit XML document as written by the test kernel, returning the number of passed
+/// and skipped test cases, and names of failed ones.
+///
+/// Only elements the reporter writes are accepted: `<testsuites>` containing `<testsuite>`,
+/// which contains `<testcase>` with at most one `<failure>` or `<skipped>` child.
+fn parse_junit(document: &str) -> Result<(usize, usize, Vec<&str>), String> {
+    if let Some(c) = document
+        .chars()
+        .find(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+    {
+        return Err(format!("control character {:?} in document", c));
+    }
+    let mut rest = document
+        .trim_start()
+        .strip_prefix("<?xml")
+        .ok_or("missing XML declaration")?;
+    rest = &rest[rest.find("?>").ok_or("unterminated XML declaration")? + 2..];
+    let (mut passed, mut skipped, mut failed) = (0, 0, Vec::new());
+    let mut stack = Vec::new();
+    // Name and child element of the open test case
+    let mut case: Option<(&str, Option<&str>)> = None;
+    let mut closed = false;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        if !rest.starts_with('<') {
+            let text = rest.lines().next().unwrap_or(rest);
+            return Err(format!("unexpected text '{}'", text));
+        }
+        let end = rest.find('>').ok_or("unterminated tag")?;
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if let Some(name) = tag.strip_prefix('/') {
+            let open = stack
+                .pop()
+                .ok_or_else(|| format!("unexpected </{}>", name))?;
+            if name.trim_end() != open {
+                return Err(format!("<{}> closed by </{}>", open, name));
+            }
+            if open == "testcase" {
+                finish_case(case.take(), &mut passed, &mut skipped, &mut failed);
+            }
+            closed = stack.is_empty();
+            continue;
+        }
+        let (tag, empty) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let attributes = parse_attributes(attributes)?;
+        let parent = match name {
+            "testsuites" => None,
+            "testsuite" => Some("testsuites"),
+            "testcase" => Some("testsuite"),
+            "failure" | "skipped" => Some("testcase"),
+            _ => return Err(format!("unexpected element <{}>", name)),
+        };
+        if closed || stack.last().copied() != parent {
+            return Err(format!("unexpected <{}> at this position", name));
+        }
+        match name {
+            "testcase" => {
+                let get = |key| {
+                    attributes
+                        .iter()
+                        .find(|(k, _)| *k == key)
+                        .map(|(_, v)| *v)
+                        .ok_or_else(|| format!("<testcase> without '{}' attribute", key))
+                };
+                get("classname")?;
+                let time = get("time")?;
+                if time.parse::<f64>().is_err() {
+                    return Err(format!("invalid time '{}'", time));
+                }
+                case = Some((get("name")?, None));
+            }
+            "failure" | "skipped" => match &mut case {
+                Some((_, child @ None)) => *child = Some(name),
+                _ => return Err("more than one result in <testcase>".into()),
+            },
+            _ => {}
+        }
+        if !empty {
+            stack.push(name);
+        } else if name == "testcase" {
+            finish_case(case.take(), &mut passed, &mut skipped, &mut failed);
+        }
+    }
+    if let Some(open) = stack.last() {
+        return Err(format!("<{}> not closed", open));
+    }
+    if !closed {
+        return Err("no <testsuites> element".into());
+    }
+    Ok((passed, skipped, failed))
+}
+
+fn finish_case<'a>(
+    case: Option<(&'a str, Option<&str>)>,
+    passed: &mut usize,
+    skipped: &mut usize,
+    failed: &mut Vec<&'a str>,
+) {
+    match case {
+        Some((name, Some("failure"))) => failed.push(name),
+        Some((_, Some(_))) => *skipped += 1,
+        Some((_, None)) => *passed += 1,
+        None => {}
+    }
+}
+
+/// Parse attributes of an XML tag, checking that values are properly escaped.
+fn parse_attributes(mut rest: &str) -> Result<Vec<(&str, &str)>, String> {
+    let mut attributes = Vec::new();
+    rest = rest.trim();
+    while !rest.is_empty() {
+        let (key, quoted) = rest
+            .split_once("=\"")
+            .ok_or_else(|| format!("malformed attribute '{}'", rest))?;
+        let key = key.trim_end();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':'))
+        {
+            return Err(format!("invalid attribute name '{}'", key));
+        }
+        let len = quoted.find('"').ok_or("unterminated attribute value")?;
+        let value = &quoted[..len];
+        if value.contains('<') {
+            return Err(format!("unescaped '<' in attribute '{}'", key));
+        }
+        for (i, _) in value.match_indices('&') {
+            let entity = value[i + 1..].split(';').next().unwrap_or("");
+            let valid = matches!(entity, "amp" | "lt" | "gt" | "quot" | "apos")
+                || entity
+                    .strip_prefix('#')
+                    .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+            if !valid || !value[i + 1..].contains(';') {
+                return Err(format!("unescaped '&' in attribute '{}'", key));
+            }
+        }
+        attributes.push((key, value));
+        rest = quoted[len + 1..].trim_start();
+    }
+    Ok(attributes)
+}
+
+fn summarize(passed: usize, skipped: usize, failed: &[&str]) {
+    for name in failed {
+        error!("Test case failed: {}", name);
+    }
+    info!(
+        "{} passed, {} skipped, {} failed",
+        passed,
+        skipped,
+        failed.len()
+    );
+}
+
+/// Extract the test case name from a TAP line after `ok ` or `not ok `.
+fn test_name(rest: &str) -> &str {
+    let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+    let rest = rest.trim_start().trim_start_matches('-').trim_start();
+    rest.split(" # ").next().unwrap_or(rest)
+}
+
+fn exit_status(success: bool) -> Option<ExitStatus> {
+    // TODO cross-platform ExitStatus return value
+    #[cfg(unix)]
+    return Some(
+        <ExitStatus as std::os::unix::process::ExitStatusExt>::from_raw(if success {
+            0
+        } else {
+            1 << 8
+        }),
+    );
+    #[cfg(not(unix))]
+    return None;
 }
 
 fn convert_to_binary(target_dir: &Path) -> Option<ExitStatus> {